@@ -0,0 +1,411 @@
+//! Headless balance simulator.
+//!
+//! ```text
+//! simulate [--games N] [--seed S] [--level easy|medium|hard|extreme|all|none]
+//!          [--profiles FILE] [--max-frames F] [--size WxH] [--format table|json|csv]
+//!          [--dump-frames FILE] [--dump-every N] [--db FILE|:memory:]
+//! ```
+//!
+//! Every built-in level selected by `--level` is simulated, followed by every
+//! custom profile in `--profiles`.  Game `i` on each uses seed `S + i`, so two
+//! runs with the same arguments produce identical output.  `table` and `json`
+//! print per-profile aggregates; `csv` prints one row per game.
+//!
+//! `--dump-frames` writes every `N`th frame (default 300, ten seconds) of the
+//! first game on each profile to `FILE` as plain text.
+//!
+//! `--db` records every game in a scores database, as the game would, under
+//! the name `bot-<seed>`; handy for filling a leaderboard.  Games stopped by
+//! `--max-frames` only count towards `top_scores`.  `:memory:` goes through
+//! the same inserts without keeping anything.
+
+use std::collections::BTreeMap;
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::PathBuf;
+use std::process::exit;
+
+use serde::Serialize;
+
+use shooting_game::bindings::Bindings;
+use shooting_game::db;
+use shooting_game::display;
+use shooting_game::entities::{
+    Difficulty, EntireGameStateInfo, HitCause, Level, MIN_HEIGHT, MIN_WIDTH,
+};
+use shooting_game::profiles::{load_profiles, score_key, Profile};
+use shooting_game::renderer::TextRenderer;
+use shooting_game::sim::{
+    cause_label, simulate_game, simulate_game_observed, Distribution, GameSummary, ProfileReport,
+};
+
+#[derive(Clone, Copy, PartialEq)]
+enum Format {
+    Table,
+    Json,
+    Csv,
+}
+
+struct Args {
+    games: u64,
+    seed: u64,
+    levels: Vec<Level>,
+    profiles: Vec<Profile>,
+    max_frames: u64,
+    width: u16,
+    height: u16,
+    format: Format,
+    dump_frames: Option<PathBuf>,
+    dump_every: u64,
+    db: Option<db::Location>,
+}
+
+const USAGE: &str = concat!(
+    "usage: simulate [--games N] [--seed S] [--level easy|medium|hard|extreme|all|none]\n",
+    "                [--profiles FILE] [--max-frames F] [--size WxH] [--format table|json|csv]\n",
+    "                [--dump-frames FILE] [--dump-every N] [--db FILE|:memory:]"
+);
+
+fn parse_args() -> Result<Args, String> {
+    let mut args = Args {
+        games: 100,
+        seed: 0,
+        levels: Level::ALL.to_vec(),
+        profiles: Vec::new(),
+        max_frames: 30 * 60 * 10, // ten minutes at 30 FPS
+        width: 80,
+        height: 24,
+        format: Format::Table,
+        dump_frames: None,
+        dump_every: 300,
+        db: None,
+    };
+
+    let mut it = std::env::args().skip(1);
+    while let Some(flag) = it.next() {
+        if flag == "--help" || flag == "-h" {
+            println!("{}", USAGE);
+            exit(0);
+        }
+        let value = it.next().ok_or_else(|| format!("{} needs a value", flag))?;
+        let bad = || format!("bad value for {}: {}", flag, value);
+        match flag.as_str() {
+            "--games" => args.games = value.parse().map_err(|_| bad())?,
+            "--seed" => args.seed = value.parse().map_err(|_| bad())?,
+            "--max-frames" => args.max_frames = value.parse().map_err(|_| bad())?,
+            "--level" => {
+                args.levels = match value.as_str() {
+                    "all" => Level::ALL.to_vec(),
+                    "none" => Vec::new(),
+                    name => vec![Level::parse(name).ok_or_else(bad)?],
+                }
+            }
+            "--profiles" => {
+                args.profiles = load_profiles(value.as_ref())?;
+                if args.profiles.is_empty() {
+                    return Err(format!("no profiles found in {}", value));
+                }
+            }
+            "--size" => {
+                let (w, h) = value.split_once('x').ok_or_else(bad)?;
+                args.width = w.parse().map_err(|_| bad())?;
+                args.height = h.parse().map_err(|_| bad())?;
+                if args.width < MIN_WIDTH || args.height < MIN_HEIGHT {
+                    return Err(format!(
+                        "{} is smaller than the game's {}x{} minimum",
+                        bad(),
+                        MIN_WIDTH,
+                        MIN_HEIGHT
+                    ));
+                }
+            }
+            "--format" => {
+                args.format = match value.as_str() {
+                    "table" => Format::Table,
+                    "json" => Format::Json,
+                    "csv" => Format::Csv,
+                    _ => return Err(bad()),
+                }
+            }
+            "--dump-frames" => args.dump_frames = Some(PathBuf::from(value)),
+            "--dump-every" => {
+                args.dump_every = value.parse().map_err(|_| bad())?;
+                if args.dump_every == 0 {
+                    return Err(bad());
+                }
+            }
+            "--db" => args.db = Some(db::Location::from_path(PathBuf::from(value))),
+            _ => return Err(format!("unknown option {}\n{}", flag, USAGE)),
+        }
+    }
+    Ok(args)
+}
+
+fn main() {
+    let args = parse_args().unwrap_or_else(|msg| {
+        eprintln!("simulate: {}", msg);
+        exit(2);
+    });
+
+    let runs: Vec<(Level, Difficulty)> = args
+        .levels
+        .iter()
+        .map(|level| (level.clone(), Difficulty::preset(level)))
+        .chain(
+            args.profiles
+                .iter()
+                .map(|p| (p.level.clone(), p.difficulty.clone())),
+        )
+        .collect();
+
+    let mut dump = args.dump_frames.as_ref().map(|path| {
+        let file = File::create(path).unwrap_or_else(|e| {
+            eprintln!("simulate: {}: {}", path.display(), e);
+            exit(2);
+        });
+        TextRenderer::new(BufWriter::new(file))
+    });
+
+    let per_profile: Vec<(String, Vec<GameSummary>)> = runs
+        .iter()
+        .map(|(level, difficulty)| {
+            let games = (0..args.games)
+                .map(|i| {
+                    let seed = args.seed.wrapping_add(i);
+                    match dump.as_mut().filter(|_| i == 0) {
+                        Some(text) => simulate_game_observed(
+                            level.clone(),
+                            difficulty,
+                            seed,
+                            args.width,
+                            args.height,
+                            args.max_frames,
+                            &mut |state| {
+                                if state.frame.is_multiple_of(args.dump_every) {
+                                    dump_frame(text, &difficulty.name, seed, state);
+                                }
+                            },
+                        ),
+                        None => simulate_game(
+                            level.clone(),
+                            difficulty,
+                            seed,
+                            args.width,
+                            args.height,
+                            args.max_frames,
+                        ),
+                    }
+                })
+                .collect();
+            (difficulty.name.clone(), games)
+        })
+        .collect();
+
+    if let Some(text) = dump {
+        if let Err(e) = text.into_inner().flush() {
+            eprintln!("simulate: writing frames: {}", e);
+            exit(1);
+        }
+    }
+
+    if let Some(location) = &args.db {
+        if let Err(e) = record_games(location, &runs, &per_profile) {
+            eprintln!("simulate: {}: {}", location, e);
+            exit(1);
+        }
+    }
+
+    match args.format {
+        Format::Csv => print_csv(&per_profile),
+        Format::Table | Format::Json => {
+            let reports: Vec<ProfileReport> = per_profile
+                .iter()
+                .map(|(name, games)| ProfileReport::from_games(name, games))
+                .collect();
+            if args.format == Format::Json {
+                print_json(&args, &reports);
+            } else {
+                print_table(&args, &reports);
+            }
+        }
+    }
+}
+
+/// Write one labelled frame to the `--dump-frames` file.
+fn dump_frame(
+    text: &mut TextRenderer<BufWriter<File>>,
+    profile: &str,
+    seed: u64,
+    state: &EntireGameStateInfo,
+) {
+    let result = writeln!(
+        text.writer(),
+        "# {} seed {} frame {}",
+        profile,
+        seed,
+        state.frame
+    )
+    .and_then(|_| display::render(text, state, &Bindings::default(), false));
+    if let Err(e) = result {
+        eprintln!("simulate: writing frames: {}", e);
+        exit(1);
+    }
+}
+
+/// Store every game in the scores database at `location` (`--db`).
+fn record_games(
+    location: &db::Location,
+    runs: &[(Level, Difficulty)],
+    per_profile: &[(String, Vec<GameSummary>)],
+) -> Result<(), db::DbError> {
+    let conn = db::open(location)?;
+    for ((level, difficulty), (_, games)) in runs.iter().zip(per_profile) {
+        let key = score_key(level, difficulty);
+        for game in games {
+            let result = db::GameResult {
+                username: format!("bot-{}", game.seed),
+                difficulty: key.clone(),
+                points: game.score,
+                wave: game.wave,
+                p2_points: None,
+                // A game cut off by `--max-frames` never reached game over:
+                // like one left early, it only counts as a best.
+                finished: game.death.is_some(),
+                stats: game.stats.clone(),
+            };
+            db::save_result(&conn, &result)?;
+        }
+    }
+    Ok(())
+}
+
+// ── Output formats ────────────────────────────────────────────────────────────
+
+fn print_table(args: &Args, reports: &[ProfileReport]) {
+    println!(
+        "{} games per profile · seeds {}.. · {}x{} · cap {} frames",
+        args.games, args.seed, args.width, args.height, args.max_frames
+    );
+    for r in reports {
+        println!();
+        println!("── {} ──", r.profile.to_uppercase());
+        println!(
+            "  {:<16} {:>8} {:>8} {:>8} {:>8} {:>8} {:>10}",
+            "", "min", "p25", "median", "p75", "max", "mean"
+        );
+        print_dist("survival frames", &r.frames);
+        print_dist("score", &r.scores);
+        print_dist("wave reached", &r.waves);
+        println!("  {:<16} {}", "kills", counts(&r.kills, r.games));
+        println!("  {:<16} {}", "power-ups", counts(&r.power_ups, r.games));
+        println!("  {:<16} {}", "lives lost to", counts(&r.hits, r.games));
+        println!("  {:<16} {}", "game ended by", counts(&r.deaths, r.games));
+    }
+}
+
+fn print_dist(label: &str, d: &Distribution) {
+    println!(
+        "  {:<16} {:>8} {:>8} {:>8} {:>8} {:>8} {:>10.1}",
+        label, d.min, d.p25, d.median, d.p75, d.max, d.mean
+    );
+}
+
+/// `name total (per-game)` pairs on one line.
+fn counts(map: &BTreeMap<&'static str, u32>, games: usize) -> String {
+    if map.is_empty() {
+        return "-".to_string();
+    }
+    map.iter()
+        .map(|(k, n)| format!("{} {} ({:.2}/game)", k, n, *n as f64 / games.max(1) as f64))
+        .collect::<Vec<_>>()
+        .join("  ")
+}
+
+/// The whole `--format json` document.
+#[derive(Serialize)]
+struct JsonOutput<'a> {
+    games_per_profile: u64,
+    seed: u64,
+    width: u16,
+    height: u16,
+    max_frames: u64,
+    profiles: &'a [ProfileReport],
+}
+
+fn print_json(args: &Args, reports: &[ProfileReport]) {
+    let output = JsonOutput {
+        games_per_profile: args.games,
+        seed: args.seed,
+        width: args.width,
+        height: args.height,
+        max_frames: args.max_frames,
+        profiles: reports,
+    };
+    println!(
+        "{}",
+        serde_json::to_string(&output).expect("reports always serialize")
+    );
+}
+
+fn print_csv(per_profile: &[(String, Vec<GameSummary>)]) {
+    // Fixed column set so files from different runs line up.
+    const KILLS: [&str; 4] = ["spacecraft", "octopus", "armored", "boss"];
+    const POWER_UPS: [&str; 5] = [
+        "spread_shot",
+        "extra_life",
+        "rapid_fire",
+        "flame_burst",
+        "firebomb",
+    ];
+    const CAUSES: [HitCause; 2] = [HitCause::EnemyBullet, HitCause::EnemyContact];
+
+    let mut header = vec!["profile", "seed", "frames", "score", "wave", "ended_by"];
+    let kill_cols: Vec<String> = KILLS.iter().map(|k| format!("kills_{}", k)).collect();
+    let pu_cols: Vec<String> = POWER_UPS.iter().map(|k| format!("caught_{}", k)).collect();
+    header.extend(kill_cols.iter().map(String::as_str));
+    let lost_cols: Vec<String> = CAUSES
+        .iter()
+        .map(|c| format!("lost_to_{}", cause_label(c)))
+        .collect();
+    header.extend(pu_cols.iter().map(String::as_str));
+    header.extend(lost_cols.iter().map(String::as_str));
+    println!("{}", header.join(","));
+
+    for (name, games) in per_profile {
+        for g in games {
+            let mut row = vec![
+                csv_field(name),
+                g.seed.to_string(),
+                g.frames.to_string(),
+                g.score.to_string(),
+                g.wave.to_string(),
+                g.death.as_ref().map_or("survived", cause_label).to_string(),
+            ];
+            row.extend(
+                KILLS
+                    .iter()
+                    .map(|k| g.kills.get(k).copied().unwrap_or(0).to_string()),
+            );
+            row.extend(
+                POWER_UPS
+                    .iter()
+                    .map(|k| g.power_ups.get(k).copied().unwrap_or(0).to_string()),
+            );
+            row.extend(
+                CAUSES
+                    .iter()
+                    .map(|c| g.hits.get(cause_label(c)).copied().unwrap_or(0).to_string()),
+            );
+            println!("{}", row.join(","));
+        }
+    }
+}
+
+/// Quote a CSV field when it contains a separator, quote or newline.
+fn csv_field(s: &str) -> String {
+    if s.contains([',', '"', '\n']) {
+        format!("\"{}\"", s.replace('"', "\"\""))
+    } else {
+        s.to_string()
+    }
+}
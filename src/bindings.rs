@@ -0,0 +1,328 @@
+//! Key bindings: which keys trigger which in-game `Action`, kept in a small
+//! TOML file next to the settings.
+//!
+//! ```toml
+//! move_left = ["Left", "a"]
+//! shoot = ["Space", "k"]
+//! ```
+//!
+//! Each entry replaces the default keys of that action; actions left out keep
+//! their defaults.  Letters match regardless of case, so `"a"` also covers
+//! Shift+A and Caps Lock.  A key may serve several actions — `Esc` pauses
+//! while playing and quits from the game-over screen.
+
+use std::collections::HashMap;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use crossterm::event::KeyCode;
+
+/// Default bindings file, looked up in the working directory.
+pub const DEFAULT_BINDINGS_FILE: &str = "keys.toml";
+
+/// Something the player can do with a key, independent of which key it is.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Action {
+    MoveLeft,
+    MoveRight,
+    /// Held together with a direction: move two columns per frame.
+    Fast,
+    /// Held together with a direction: jump ten columns.
+    Warp,
+    Shoot,
+    /// Open or close the pause menu.
+    Pause,
+    /// Play again from the game-over screen.
+    Restart,
+    Quit,
+    /// Toggle the debug overlay.
+    Debug,
+    /// Debug only: toggle invincibility.
+    GodMode,
+    /// Debug only: toggle quarter-speed play.
+    SlowMo,
+    /// Co-op: player two's controls.
+    P2MoveLeft,
+    P2MoveRight,
+    P2Shoot,
+}
+
+impl Action {
+    /// Every action, in the order the rebinding screen and the file list them.
+    pub const ALL: [Action; 14] = [
+        Action::MoveLeft,
+        Action::MoveRight,
+        Action::Fast,
+        Action::Warp,
+        Action::Shoot,
+        Action::Pause,
+        Action::Restart,
+        Action::Quit,
+        Action::Debug,
+        Action::GodMode,
+        Action::SlowMo,
+        Action::P2MoveLeft,
+        Action::P2MoveRight,
+        Action::P2Shoot,
+    ];
+
+    /// The name used in the bindings file.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Action::MoveLeft => "move_left",
+            Action::MoveRight => "move_right",
+            Action::Fast => "fast",
+            Action::Warp => "warp",
+            Action::Shoot => "shoot",
+            Action::Pause => "pause",
+            Action::Restart => "restart",
+            Action::Quit => "quit",
+            Action::Debug => "debug",
+            Action::GodMode => "god_mode",
+            Action::SlowMo => "slow_mo",
+            Action::P2MoveLeft => "p2_move_left",
+            Action::P2MoveRight => "p2_move_right",
+            Action::P2Shoot => "p2_shoot",
+        }
+    }
+
+    pub fn parse(s: &str) -> Option<Action> {
+        Action::ALL.into_iter().find(|a| a.as_str() == s)
+    }
+
+    /// Human-readable name for the rebinding screen.
+    pub fn label(&self) -> &'static str {
+        match self {
+            Action::MoveLeft => "Move left",
+            Action::MoveRight => "Move right",
+            Action::Fast => "Fast (+ direction)",
+            Action::Warp => "Warp (+ direction)",
+            Action::Shoot => "Shoot",
+            Action::Pause => "Pause / resume",
+            Action::Restart => "Play again (game over)",
+            Action::Quit => "Quit",
+            Action::Debug => "Debug overlay",
+            Action::GodMode => "God mode (debug)",
+            Action::SlowMo => "Slow motion (debug)",
+            Action::P2MoveLeft => "Player 2: move left",
+            Action::P2MoveRight => "Player 2: move right",
+            Action::P2Shoot => "Player 2: shoot",
+        }
+    }
+
+    /// The keys bound to this action out of the box.
+    pub fn default_keys(&self) -> Vec<KeyCode> {
+        match self {
+            Action::MoveLeft => vec![KeyCode::Left, KeyCode::Char('a')],
+            Action::MoveRight => vec![KeyCode::Right, KeyCode::Char('d')],
+            Action::Fast => vec![KeyCode::Char('f')],
+            Action::Warp => vec![KeyCode::Char('w')],
+            Action::Shoot => vec![KeyCode::Char(' ')],
+            Action::Pause => vec![KeyCode::Char('p'), KeyCode::Esc],
+            Action::Restart => vec![KeyCode::Char('r')],
+            Action::Quit => vec![KeyCode::Char('q'), KeyCode::Esc],
+            Action::Debug => vec![KeyCode::Char('`')],
+            Action::GodMode => vec![KeyCode::Char('g')],
+            Action::SlowMo => vec![KeyCode::Char('s')],
+            Action::P2MoveLeft => vec![KeyCode::Char('j')],
+            Action::P2MoveRight => vec![KeyCode::Char('l')],
+            Action::P2Shoot => vec![KeyCode::Char('i')],
+        }
+    }
+}
+
+/// The key table: every action and the keys that trigger it.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Bindings {
+    keys: HashMap<Action, Vec<KeyCode>>,
+}
+
+impl Default for Bindings {
+    fn default() -> Self {
+        Bindings {
+            keys: Action::ALL
+                .into_iter()
+                .map(|a| (a, a.default_keys()))
+                .collect(),
+        }
+    }
+}
+
+impl Bindings {
+    /// The keys bound to `action`; empty if the player unbound them all.
+    pub fn keys(&self, action: Action) -> &[KeyCode] {
+        self.keys.get(&action).map_or(&[], |k| k.as_slice())
+    }
+
+    /// Every action `code` triggers, in `Action::ALL` order.
+    pub fn actions_for(&self, code: KeyCode) -> Vec<Action> {
+        let code = normalize(code);
+        Action::ALL
+            .into_iter()
+            .filter(|a| self.keys(*a).contains(&code))
+            .collect()
+    }
+
+    pub fn triggers(&self, action: Action, code: KeyCode) -> bool {
+        self.keys(action).contains(&normalize(code))
+    }
+
+    /// Make `code` the only key for `action`, taking it away from any other
+    /// action so one press never does two unrelated things.
+    pub fn rebind(&mut self, action: Action, code: KeyCode) {
+        let code = normalize(code);
+        for keys in self.keys.values_mut() {
+            keys.retain(|k| *k != code);
+        }
+        self.keys.insert(action, vec![code]);
+    }
+
+    /// Put `action` back on its default keys.
+    pub fn reset(&mut self, action: Action) {
+        self.keys.insert(action, action.default_keys());
+    }
+
+    /// The first key of `action`, for one-line hints.
+    pub fn hint(&self, action: Action) -> String {
+        self.keys(action)
+            .first()
+            .map_or_else(|| "—".to_string(), |k| key_label(*k))
+    }
+
+    /// The keys of `action` for on-screen hints, e.g. `← / A`.
+    pub fn label(&self, action: Action) -> String {
+        match self.keys(action) {
+            [] => "(unbound)".to_string(),
+            keys => keys
+                .iter()
+                .map(|k| key_label(*k))
+                .collect::<Vec<_>>()
+                .join(" / "),
+        }
+    }
+}
+
+/// Letters are stored and looked up in lower case.
+fn normalize(code: KeyCode) -> KeyCode {
+    match code {
+        KeyCode::Char(c) => KeyCode::Char(c.to_ascii_lowercase()),
+        other => other,
+    }
+}
+
+// ── Key names ─────────────────────────────────────────────────────────────────
+
+/// Named keys accepted in the file, besides single characters and `F1`–`F12`.
+const NAMED_KEYS: &[(&str, KeyCode)] = &[
+    ("Left", KeyCode::Left),
+    ("Right", KeyCode::Right),
+    ("Up", KeyCode::Up),
+    ("Down", KeyCode::Down),
+    ("Space", KeyCode::Char(' ')),
+    ("Esc", KeyCode::Esc),
+    ("Enter", KeyCode::Enter),
+    ("Tab", KeyCode::Tab),
+    ("Backspace", KeyCode::Backspace),
+    ("Insert", KeyCode::Insert),
+    ("Delete", KeyCode::Delete),
+    ("Home", KeyCode::Home),
+    ("End", KeyCode::End),
+    ("PageUp", KeyCode::PageUp),
+    ("PageDown", KeyCode::PageDown),
+];
+
+/// Parse a key name from the bindings file: `"a"`, `"Space"`, `"F5"`, ...
+pub fn parse_key(name: &str) -> Option<KeyCode> {
+    if let Some(&(_, code)) = NAMED_KEYS
+        .iter()
+        .find(|(n, _)| n.eq_ignore_ascii_case(name))
+    {
+        return Some(code);
+    }
+    let mut chars = name.chars();
+    match (chars.next(), chars.next()) {
+        (Some(c), None) if !c.is_whitespace() && !c.is_control() => {
+            Some(normalize(KeyCode::Char(c)))
+        }
+        _ => {
+            let n: u8 = name.strip_prefix(['F', 'f'])?.parse().ok()?;
+            (1..=12).contains(&n).then_some(KeyCode::F(n))
+        }
+    }
+}
+
+/// The file name of `code`, or `None` for keys that cannot be bound.
+pub fn key_name(code: KeyCode) -> Option<String> {
+    if let Some((name, _)) = NAMED_KEYS.iter().find(|(_, c)| *c == code) {
+        return Some(name.to_string());
+    }
+    match code {
+        KeyCode::Char(c) if !c.is_control() => Some(c.to_ascii_lowercase().to_string()),
+        KeyCode::F(n) if (1..=12).contains(&n) => Some(format!("F{}", n)),
+        _ => None,
+    }
+}
+
+/// How `code` is shown on screen: arrows as arrows, letters in upper case.
+pub fn key_label(code: KeyCode) -> String {
+    match code {
+        KeyCode::Left => "←".to_string(),
+        KeyCode::Right => "→".to_string(),
+        KeyCode::Up => "↑".to_string(),
+        KeyCode::Down => "↓".to_string(),
+        KeyCode::Char(c) => match key_name(code) {
+            Some(name) if name.len() > 1 => name.to_uppercase(),
+            _ => c.to_uppercase().to_string(),
+        },
+        other => key_name(other).map_or_else(|| "?".to_string(), |n| n.to_uppercase()),
+    }
+}
+
+// ── File ──────────────────────────────────────────────────────────────────────
+
+/// Load bindings from `path`.  A missing file is not an error — it simply
+/// means every action is on its default keys.
+pub fn load_bindings(path: &Path) -> Result<Bindings, String> {
+    match fs::read_to_string(path) {
+        Ok(text) => parse_bindings(&text).map_err(|e| format!("{}: {}", path.display(), e)),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(Bindings::default()),
+        Err(e) => Err(format!("{}: {}", path.display(), e)),
+    }
+}
+
+pub fn parse_bindings(text: &str) -> Result<Bindings, String> {
+    let table: HashMap<String, Vec<String>> = toml::from_str(text).map_err(|e| e.to_string())?;
+    let mut bindings = Bindings::default();
+    for (name, keys) in table {
+        let action = Action::parse(&name).ok_or_else(|| format!("unknown action {:?}", name))?;
+        let keys = keys
+            .iter()
+            .map(|k| parse_key(k).ok_or_else(|| format!("{}: unknown key {:?}", name, k)))
+            .collect::<Result<Vec<_>, _>>()?;
+        bindings.keys.insert(action, keys);
+    }
+    Ok(bindings)
+}
+
+/// Write every action, in `Action::ALL` order, so the file doubles as a
+/// reference of what can be bound.
+pub fn save_bindings(path: &Path, bindings: &Bindings) -> io::Result<()> {
+    fs::write(path, bindings_to_text(bindings))
+}
+
+pub fn bindings_to_text(bindings: &Bindings) -> String {
+    Action::ALL
+        .into_iter()
+        .map(|action| {
+            let keys = bindings
+                .keys(action)
+                .iter()
+                .filter_map(|k| key_name(*k))
+                .map(|k| format!("{:?}", k))
+                .collect::<Vec<_>>()
+                .join(", ");
+            format!("{} = [{}]\n", action.as_str(), keys)
+        })
+        .collect()
+}
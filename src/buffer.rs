@@ -0,0 +1,105 @@
+//! Cell-grid back buffer.
+//!
+//! `display` draws each frame into a fresh [`Buffer`]; a
+//! [`Renderer`](crate::renderer::Renderer) then shows it — on the terminal,
+//! or as plain text.
+
+use crossterm::style::Color;
+
+/// One character cell: the glyph and its colours.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Cell {
+    pub ch: char,
+    pub fg: Color,
+    pub bg: Color,
+}
+
+impl Cell {
+    /// An empty cell in the terminal's default colours.
+    pub const BLANK: Cell = Cell {
+        ch: ' ',
+        fg: Color::Reset,
+        bg: Color::Reset,
+    };
+}
+
+/// A `width` × `height` grid of cells, row-major.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Buffer {
+    width: u16,
+    height: u16,
+    cells: Vec<Cell>,
+}
+
+impl Buffer {
+    /// A buffer of blank cells.
+    pub fn new(width: u16, height: u16) -> Self {
+        Buffer {
+            width,
+            height,
+            cells: vec![Cell::BLANK; width as usize * height as usize],
+        }
+    }
+
+    pub fn width(&self) -> u16 {
+        self.width
+    }
+
+    pub fn height(&self) -> u16 {
+        self.height
+    }
+
+    /// The cell at (`x`, `y`), or `None` outside the buffer.
+    pub fn cell(&self, x: u16, y: u16) -> Option<&Cell> {
+        if x < self.width && y < self.height {
+            self.cells
+                .get(y as usize * self.width as usize + x as usize)
+        } else {
+            None
+        }
+    }
+
+    /// The glyph at (`x`, `y`); a space outside the buffer.
+    pub fn char_at(&self, x: u16, y: u16) -> char {
+        self.cell(x, y).map_or(' ', |c| c.ch)
+    }
+
+    /// Row `y` as a string, one char per column.
+    pub fn row_str(&self, y: u16) -> String {
+        (0..self.width).map(|x| self.char_at(x, y)).collect()
+    }
+
+    /// The glyphs as plain text: one line per row, trailing spaces trimmed.
+    /// Colours are dropped.
+    pub fn to_text(&self) -> String {
+        (0..self.height)
+            .map(|y| self.row_str(y).trim_end().to_string() + "\n")
+            .collect()
+    }
+
+    /// Replace the cell at (`x`, `y`).  Writes outside the buffer are dropped.
+    pub fn set(&mut self, x: u16, y: u16, cell: Cell) {
+        if x < self.width && y < self.height {
+            self.cells[y as usize * self.width as usize + x as usize] = cell;
+        }
+    }
+
+    /// Write `text` left to right from (`x`, `y`) in `fg` on the default
+    /// background, one char per column, clipped at the right edge.
+    pub fn print(&mut self, x: u16, y: u16, text: &str, fg: Color) {
+        for (i, ch) in text.chars().enumerate() {
+            let Some(col) = x.checked_add(i as u16) else {
+                break;
+            };
+            self.set(
+                col,
+                y,
+                Cell {
+                    ch,
+                    fg,
+                    bg: Color::Reset,
+                },
+            );
+        }
+    }
+}
@@ -7,48 +7,63 @@
 use rand::Rng;
 
 use crate::entities::{
-    BonusItem, BonusKind, Bullet, BulletOwner, Enemy, EnemyKind, EntireGameStateInfo, Explosion,
-    FirebombProj, FlameBullet, GameStatus, Level, Player,
+    BonusItem, BonusKind, Boss, BossPattern, Bullet, BulletOwner, Difficulty, Enemy, EnemyBehavior,
+    EnemyKind, EnemyShot, EntireGameStateInfo, Explosion, FirePattern, FirebombProj, FlameBullet,
+    GameEvent, GameStatus, HitCause, Level, PauseItem, Player, Player2, PlayerAction, PlayerId,
+    PowerUpLoss, RunStats, WaveSpec, WaveState, BOSS_HALF_WIDTH, BOSS_HEIGHT, MIN_HEIGHT,
+    MIN_WIDTH, SCORE_RATE_WINDOW,
 };
 
-// ── Difficulty tables ────────────────────────────────────────────────────────
-
-fn enemy_move_interval(level: &Level) -> u64 {
-    match level {
-        Level::Easy => 22,   // new — very relaxed
-        Level::Medium => 14, // old Easy
-        Level::Hard => 8,    // old Medium
-        Level::Extreme => 4, // old Hard
-    }
-}
-
-fn enemy_spawn_rate(level: &Level) -> u64 {
-    match level {
-        Level::Easy => 130,   // new — fewer opponents
-        Level::Medium => 90,  // old Easy
-        Level::Hard => 55,    // old Medium
-        Level::Extreme => 28, // old Hard
-    }
-}
+// ── Scoring ──────────────────────────────────────────────────────────────────
 
 /// Score awarded per enemy destroyed.
 fn score_for(kind: &EnemyKind) -> u32 {
     match kind {
         EnemyKind::Spacecraft => 100,
         EnemyKind::Octopus => 150,
+        EnemyKind::Armored => 250,
     }
 }
 
+/// Subtract `damage[i]` from each enemy's HP.  Enemies brought to 0 are
+/// removed, scored and reported as `EnemyKilled`; the rest survive with
+/// their reduced HP.  The points go to `hit_by[i]`, the player who landed
+/// the last hit.
+fn resolve_enemy_damage(
+    enemies: Vec<Enemy>,
+    damage: &[u32],
+    hit_by: &[PlayerId],
+    score_gain: &mut [u32; 2],
+    events: &mut Vec<GameEvent>,
+) -> Vec<Enemy> {
+    enemies
+        .into_iter()
+        .zip(damage.iter().zip(hit_by))
+        .filter_map(|(e, (&dmg, by))| {
+            if dmg >= e.hp {
+                score_gain[by.index()] += score_for(&e.kind);
+                events.push(GameEvent::EnemyKilled(e.kind));
+                None
+            } else {
+                Some(Enemy {
+                    hp: e.hp - dmg,
+                    ..e
+                })
+            }
+        })
+        .collect()
+}
+
 // ── Bonus-item constants ──────────────────────────────────────────────────────
 
-/// Frames between bonus-item drops.
-const BONUS_SPAWN_INTERVAL: u64 = 150;
 /// Frames between each downward step of a bonus item.
 const BONUS_MOVE_INTERVAL: u64 = 10;
 /// How many frames a timed power-up lasts (≈10 seconds at 30 FPS).
 const POWER_UP_DURATION: u32 = 300;
 /// Maximum lives the player can hold.
 const MAX_LIVES: u32 = 5;
+/// Frames of invulnerability after losing a life (≈2 seconds at 30 FPS).
+const INVULNERABLE_FRAMES: u32 = 60;
 
 // ── FlameBurst constants ──────────────────────────────────────────────────────
 
@@ -67,6 +82,8 @@ const FIREBOMB_FUSE: u32 = 90;
 const FIREBOMB_CAP: usize = 2;
 /// Squared Euclidean radius for the explosion's kill zone (radius = 4 cells).
 const EXPLOSION_KILL_RADIUS_SQ: i32 = 16;
+/// HP an explosion takes off each enemy in its kill zone.
+const EXPLOSION_DAMAGE: u32 = 2;
 /// Squared radius for the proximity trigger (radius = 2 cells).
 const EXPLOSION_TRIGGER_RADIUS_SQ: i32 = 4;
 /// Frames the explosion visual stays on screen.
@@ -85,18 +102,435 @@ const SCORE_MILESTONES: &[(u32, &str)] = &[
     (10000, "LEGENDARY!!!"),
 ];
 
+// ── Boss constants ────────────────────────────────────────────────────────────
+
+/// Every this-many waves ends with a boss fight.
+const BOSS_WAVE_INTERVAL: u32 = 3;
+/// Crossing each multiple of this score summons a boss, unless one is
+/// already on the field.
+const BOSS_SCORE_INTERVAL: u32 = 5000;
+/// Boss hit points: `BOSS_BASE_HP + BOSS_HP_PER_WAVE × wave`.
+const BOSS_BASE_HP: u32 = 20;
+const BOSS_HP_PER_WAVE: u32 = 5;
+/// Score per point of the boss's maximum HP, awarded on the killing blow.
+const BOSS_POINTS_PER_HP: u32 = 50;
+/// HP a firebomb blast takes off a boss caught in its radius.
+const BOSS_BLAST_DAMAGE: u32 = 5;
+/// The boss steps one column sideways every this many frames.
+const BOSS_MOVE_INTERVAL: u64 = 3;
+/// Frames spent in each attack pattern before moving on to the next.
+const BOSS_PATTERN_FRAMES: u32 = 120;
+/// Frames between shots in each pattern.
+const BOSS_AIMED_INTERVAL: u32 = 24;
+const BOSS_FAN_INTERVAL: u32 = 45;
+const BOSS_SWEEP_INTERVAL: u32 = 8;
+/// Fan shot angles, in degrees from straight down.
+const BOSS_FAN_ANGLES: [f32; 5] = [-40.0, -20.0, 0.0, 20.0, 40.0];
+/// A sweep turns from −BOSS_SWEEP_ARC to +BOSS_SWEEP_ARC degrees over one pattern.
+const BOSS_SWEEP_ARC: f32 = 60.0;
+/// Speed of an `EnemyShot`, in cells per frame.
+const ENEMY_SHOT_SPEED: f32 = 0.5;
+
+// ── Waves ─────────────────────────────────────────────────────────────────────
+
+/// Enemies in wave 1; each later wave adds `WAVE_BUDGET_STEP`.
+const WAVE_BASE_BUDGET: u32 = 6;
+const WAVE_BUDGET_STEP: u32 = 2;
+/// Waves after which speed, spawn rate and fire rate stop ramping
+/// (wave 11 onwards runs at twice the base pace).
+const WAVE_RAMP_CAP: u32 = 10;
+/// Armoured share added per wave after the first (up to 40 % at the ramp cap).
+const ARMORED_CHANCE_PER_WAVE: f64 = 0.04;
+/// Breather before each wave, while the "WAVE N" banner is up (≈3 s).
+const WAVE_BREATHER: u32 = 90;
+/// Wave from which each movement behaviour can spawn (Hard and Extreme
+/// unlock them one wave earlier), and its share of spawns once unlocked.
+const ZIGZAG_FROM_WAVE: u32 = 2;
+const ZIGZAG_CHANCE: f64 = 0.25;
+const DIVER_FROM_WAVE: u32 = 3;
+const DIVER_CHANCE: f64 = 0.15;
+const FORMATION_FROM_WAVE: u32 = 4;
+const FORMATION_CHANCE: f64 = 0.1;
+
+/// Scale a frame interval down as waves progress: `base` on wave 1,
+/// `base / 2` from wave `WAVE_RAMP_CAP + 1`.  Never below 1.
+fn ramp_interval(base: u64, ramp: u32) -> u64 {
+    (base.saturating_mul(10) / (10 + ramp as u64)).max(1)
+}
+
+/// Budget, speed and composition of wave `number` (1-based).  Later waves
+/// have more enemies that move, spawn and fire faster, with a growing share
+/// of octopuses and, from wave 2, armoured enemies.  Zigzaggers, divers and
+/// formations are unlocked wave by wave, earlier on the harder `level`s.
+pub fn wave_spec(level: &Level, difficulty: &Difficulty, number: u32) -> WaveSpec {
+    let ramp = number.saturating_sub(1).min(WAVE_RAMP_CAP);
+    let reached = number + matches!(level, Level::Hard | Level::Extreme) as u32;
+    let unlocked = |from: u32, chance: f64| if reached >= from { chance } else { 0.0 };
+    WaveSpec {
+        budget: WAVE_BASE_BUDGET + WAVE_BUDGET_STEP * number.saturating_sub(1),
+        enemy_move_interval: ramp_interval(difficulty.enemy_move_interval, ramp),
+        enemy_spawn_rate: ramp_interval(difficulty.enemy_spawn_rate, ramp),
+        enemy_fire_chance: ramp_interval(difficulty.enemy_fire_chance as u64, ramp) as u32,
+        spacecraft_chance: difficulty.spacecraft_chance * 10.0 / (10 + ramp) as f64,
+        armored_chance: ARMORED_CHANCE_PER_WAVE * ramp as f64,
+        formation_chance: unlocked(FORMATION_FROM_WAVE, FORMATION_CHANCE),
+        diver_chance: unlocked(DIVER_FROM_WAVE, DIVER_CHANCE),
+        zigzag_chance: unlocked(ZIGZAG_FROM_WAVE, ZIGZAG_CHANCE),
+    }
+}
+
+/// Wave `number` with its full budget, waiting out the breather.
+fn start_wave(level: &Level, difficulty: &Difficulty, number: u32) -> WaveState {
+    WaveState {
+        number,
+        remaining: wave_spec(level, difficulty, number).budget,
+        breather: WAVE_BREATHER,
+        boss_due: number.is_multiple_of(BOSS_WAVE_INTERVAL),
+    }
+}
+
+fn wave_banner(number: u32) -> (String, u32) {
+    (format!("WAVE {}", number), WAVE_BREATHER)
+}
+
+// ── Enemy movement ────────────────────────────────────────────────────────────
+
+/// Row new enemies appear on.
+const ENEMY_SPAWN_Y: i32 = 2;
+/// Rows a zigzagger descends before turning.
+const ZIGZAG_LEG: u32 = 4;
+/// Rows a diver descends normally before it starts diving.
+const DIVE_AFTER_ROWS: i32 = 3;
+/// Frames per row when a dive starts; one frame quicker every
+/// `DIVE_ACCEL_FRAMES`, down to a row every frame.
+const DIVE_START_INTERVAL: u32 = 4;
+const DIVE_ACCEL_FRAMES: u32 = 12;
+/// Enemies per formation, and the columns between neighbours.
+const FORMATION_SIZE: u32 = 5;
+const FORMATION_SPACING: i32 = 4;
+/// Move steps a formation sways one way before reversing.
+const FORMATION_SWAY: u64 = 3;
+/// Each formation member peels off into a dive with a 1-in-N chance per
+/// move step.
+const FORMATION_PEEL_CHANCE: u32 = 25;
+
+/// `enemy` one frame later.  Everything moves on the wave's move interval
+/// except divers, which keep their own accelerating pace once diving.
+/// Only formation members draw from `rng` (to decide whether to peel off).
+fn move_enemy(
+    enemy: &Enemy,
+    frame: u64,
+    move_interval: u64,
+    player_x: i32,
+    width: i32,
+    rng: &mut impl Rng,
+) -> Enemy {
+    // Keep the 3-column sprite inside the walls.
+    let (min_x, max_x) = (2, width - 3);
+    let step = frame.is_multiple_of(move_interval);
+    match &enemy.behavior {
+        EnemyBehavior::Diver { dive } if *dive > 0 => {
+            let dive = dive + 1;
+            let interval = DIVE_START_INTERVAL
+                .saturating_sub(dive / DIVE_ACCEL_FRAMES)
+                .max(1);
+            let behavior = EnemyBehavior::Diver { dive };
+            if !dive.is_multiple_of(interval) {
+                return Enemy {
+                    behavior,
+                    ..enemy.clone()
+                };
+            }
+            Enemy {
+                x: (enemy.x + (player_x - enemy.x).signum()).clamp(min_x, max_x),
+                y: enemy.y + 1,
+                behavior,
+                ..enemy.clone()
+            }
+        }
+        _ if !step => enemy.clone(),
+        EnemyBehavior::Straight => Enemy {
+            y: enemy.y + 1,
+            ..enemy.clone()
+        },
+        EnemyBehavior::Zigzag { dir, steps } => {
+            let dir = if (min_x..=max_x).contains(&(enemy.x + dir)) {
+                *dir
+            } else {
+                -dir
+            };
+            let (next_dir, steps) = if steps + 1 >= ZIGZAG_LEG {
+                (-dir, 0)
+            } else {
+                (dir, steps + 1)
+            };
+            Enemy {
+                x: (enemy.x + dir).clamp(min_x, max_x),
+                y: enemy.y + 1,
+                behavior: EnemyBehavior::Zigzag {
+                    dir: next_dir,
+                    steps,
+                },
+                ..enemy.clone()
+            }
+        }
+        EnemyBehavior::Diver { .. } => {
+            let y = enemy.y + 1;
+            let dive = (y >= ENEMY_SPAWN_Y + DIVE_AFTER_ROWS) as u32;
+            Enemy {
+                y,
+                behavior: EnemyBehavior::Diver { dive },
+                ..enemy.clone()
+            }
+        }
+        EnemyBehavior::Formation => {
+            // The sway direction depends only on the frame, so every
+            // member moves in lockstep without shared state.
+            let phase = (frame / move_interval) % (2 * FORMATION_SWAY);
+            let dx = if phase < FORMATION_SWAY { 1 } else { -1 };
+            let behavior = if rng.gen_ratio(1, FORMATION_PEEL_CHANCE) {
+                EnemyBehavior::Diver { dive: 1 }
+            } else {
+                EnemyBehavior::Formation
+            };
+            Enemy {
+                x: (enemy.x + dx).clamp(min_x, max_x),
+                y: enemy.y + 1,
+                behavior,
+                ..enemy.clone()
+            }
+        }
+    }
+}
+
+/// The enemies produced by one spawn at column `x`.  `roll` (uniform in
+/// 0..1) picks the behaviour from `spec`'s shares.  A formation is centred
+/// as near `x` as leaves room to sway, and never exceeds `budget`; if the
+/// field is too narrow for one, a single straight enemy spawns instead.
+fn spawn_group(
+    spec: &WaveSpec,
+    roll: f64,
+    x: i32,
+    kind: EnemyKind,
+    budget: u32,
+    width: i32,
+) -> Vec<Enemy> {
+    let enemy = |x: i32, behavior: EnemyBehavior| Enemy {
+        x,
+        y: ENEMY_SPAWN_Y,
+        kind: kind.clone(),
+        hp: kind.max_hp(),
+        behavior,
+    };
+    let diver_cut = spec.formation_chance + spec.diver_chance;
+    let behavior = if roll < spec.formation_chance {
+        let n = FORMATION_SIZE.min(budget) as i32;
+        let half_span = (n - 1) * FORMATION_SPACING / 2;
+        let margin = half_span + FORMATION_SWAY as i32;
+        let (lo, hi) = (2 + margin, width - 3 - margin);
+        if lo <= hi {
+            let left = x.clamp(lo, hi) - half_span;
+            return (0..n)
+                .map(|i| enemy(left + i * FORMATION_SPACING, EnemyBehavior::Formation))
+                .collect();
+        }
+        EnemyBehavior::Straight
+    } else if roll < diver_cut {
+        EnemyBehavior::Diver { dive: 0 }
+    } else if roll < diver_cut + spec.zigzag_chance {
+        let dir = if x < width / 2 { 1 } else { -1 };
+        EnemyBehavior::Zigzag { dir, steps: 0 }
+    } else {
+        EnemyBehavior::Straight
+    };
+    vec![enemy(x, behavior)]
+}
+
+// ── Enemy fire ────────────────────────────────────────────────────────────────
+
+/// Angles of an Octopus spread, in degrees from straight down.
+const ENEMY_SPREAD_ANGLES: [f32; 3] = [-25.0, 0.0, 25.0];
+
+/// An `EnemyShot` leaving `(x, y)` at `deg` degrees from straight down
+/// (positive = rightward).
+fn enemy_shot(x: i32, y: i32, deg: f32) -> EnemyShot {
+    let rad = deg.to_radians();
+    EnemyShot {
+        x: x as f32,
+        y: y as f32,
+        vx: rad.sin() * ENEMY_SHOT_SPEED,
+        vy: rad.cos() * ENEMY_SHOT_SPEED,
+    }
+}
+
+/// Angle from `(x, y)` to `player`, in degrees from straight down.
+fn aim_at(x: i32, y: i32, player: &Player) -> f32 {
+    let dx = (player.x - x) as f32;
+    let dy = (player.y - y) as f32;
+    dx.atan2(dy).to_degrees()
+}
+
+// ── Boss behaviour ────────────────────────────────────────────────────────────
+
+/// A fresh boss for wave `wave`, entering at the top centre.
+fn new_boss(wave: u32, width: u16) -> Boss {
+    let hp = BOSS_BASE_HP + BOSS_HP_PER_WAVE * wave;
+    Boss {
+        x: width as i32 / 2,
+        y: 2,
+        hp,
+        max_hp: hp,
+        dir: 1,
+        pattern: BossPattern::Aimed,
+        pattern_frame: 0,
+    }
+}
+
+fn boss_banner() -> (String, u32) {
+    ("WARNING: BOSS APPROACHING".to_string(), CHEER_DURATION)
+}
+
+/// Squared distance from `(x, y)` to the nearest cell of the boss's hitbox
+/// (0 inside it).
+fn boss_dist_sq(boss: &Boss, x: i32, y: i32) -> i32 {
+    let dx = x - x.clamp(boss.x - BOSS_HALF_WIDTH, boss.x + BOSS_HALF_WIDTH);
+    let dy = y - y.clamp(boss.y, boss.y + BOSS_HEIGHT - 1);
+    dx * dx + dy * dy
+}
+
+/// A shot leaving the boss's underside at `deg` degrees from straight down.
+fn boss_shot(boss: &Boss, deg: f32) -> EnemyShot {
+    enemy_shot(boss.x, boss.y + BOSS_HEIGHT, deg)
+}
+
+/// Advance the boss one frame: patrol sideways, bouncing off the walls, and
+/// fire according to the current pattern.  Patterns rotate
+/// Aimed → Fan → Sweep every `BOSS_PATTERN_FRAMES`.
+fn boss_step(boss: &Boss, frame: u64, player: &Player, width: i32) -> (Boss, Vec<EnemyShot>) {
+    let min_x = 1 + BOSS_HALF_WIDTH;
+    let max_x = (width - 2 - BOSS_HALF_WIDTH).max(min_x);
+    let (mut x, mut dir) = (boss.x, boss.dir);
+    if frame.is_multiple_of(BOSS_MOVE_INTERVAL) {
+        if !(min_x..=max_x).contains(&(x + dir)) {
+            dir = -dir;
+        }
+        x = (x + dir).clamp(min_x, max_x);
+    }
+
+    let (pattern, pattern_frame) = if boss.pattern_frame + 1 >= BOSS_PATTERN_FRAMES {
+        let next = match boss.pattern {
+            BossPattern::Aimed => BossPattern::Fan,
+            BossPattern::Fan => BossPattern::Sweep,
+            BossPattern::Sweep => BossPattern::Aimed,
+        };
+        (next, 0)
+    } else {
+        (boss.pattern.clone(), boss.pattern_frame + 1)
+    };
+    let boss = Boss {
+        x,
+        dir,
+        pattern,
+        pattern_frame,
+        ..boss.clone()
+    };
+
+    let t = boss.pattern_frame;
+    let shots = match boss.pattern {
+        BossPattern::Aimed if t.is_multiple_of(BOSS_AIMED_INTERVAL) => {
+            vec![boss_shot(
+                &boss,
+                aim_at(boss.x, boss.y + BOSS_HEIGHT, player),
+            )]
+        }
+        BossPattern::Fan if t.is_multiple_of(BOSS_FAN_INTERVAL) => BOSS_FAN_ANGLES
+            .iter()
+            .map(|&deg| boss_shot(&boss, deg))
+            .collect(),
+        BossPattern::Sweep if t.is_multiple_of(BOSS_SWEEP_INTERVAL) => {
+            let progress = t as f32 / BOSS_PATTERN_FRAMES as f32;
+            vec![boss_shot(
+                &boss,
+                -BOSS_SWEEP_ARC + 2.0 * BOSS_SWEEP_ARC * progress,
+            )]
+        }
+        _ => Vec::new(),
+    };
+    (boss, shots)
+}
+
+// ── Players ───────────────────────────────────────────────────────────────────
+
+/// `id`'s ship.  Asking for player two in a solo game gives player one's.
+pub fn ship(state: &EntireGameStateInfo, id: PlayerId) -> &Player {
+    match (id, &state.player2) {
+        (PlayerId::Two, Some(p2)) => &p2.ship,
+        _ => &state.player,
+    }
+}
+
+/// The players still in the game.  In co-op a player with no lives left
+/// sits the rest of the game out; solo, player one is always listed.
+pub fn players_in_play(state: &EntireGameStateInfo) -> Vec<PlayerId> {
+    match &state.player2 {
+        None => vec![PlayerId::One],
+        Some(p2) => [(PlayerId::One, &state.player), (PlayerId::Two, &p2.ship)]
+            .into_iter()
+            .filter(|(_, ship)| ship.lives > 0)
+            .map(|(id, _)| id)
+            .collect(),
+    }
+}
+
+/// The ship in play nearest column `x`, which aimed fire and divers go
+/// after.  Ties go to player one.
+fn nearest_ship(state: &EntireGameStateInfo, x: i32) -> &Player {
+    players_in_play(state)
+        .into_iter()
+        .map(|id| ship(state, id))
+        .min_by_key(|p| (p.x - x).abs())
+        .unwrap_or(&state.player)
+}
+
+/// `id`'s share of the score; player one's is everything player two has
+/// not scored.
+pub fn player_score(state: &EntireGameStateInfo, id: PlayerId) -> u32 {
+    let p2 = state.player2.as_ref().map_or(0, |p2| p2.score);
+    match id {
+        PlayerId::One => state.score.saturating_sub(p2),
+        PlayerId::Two => p2,
+    }
+}
+
 // ── Constructors ─────────────────────────────────────────────────────────────
 
-/// Build the initial game state for a given level and terminal dimensions.
+/// Build the initial game state for a given level and terminal dimensions,
+/// using the level's built-in difficulty preset.
 pub fn init_state(level: Level, width: u16, height: u16, high_score: u32) -> EntireGameStateInfo {
+    let difficulty = Difficulty::preset(&level);
+    init_state_with(level, difficulty, width, height, high_score)
+}
+
+/// Like `init_state`, but with explicit difficulty parameters (custom profiles).
+pub fn init_state_with(
+    level: Level,
+    difficulty: Difficulty,
+    width: u16,
+    height: u16,
+    high_score: u32,
+) -> EntireGameStateInfo {
+    let wave = start_wave(&level, &difficulty, 1);
     EntireGameStateInfo {
         player: Player {
             x: (width / 2) as i32,
             y: (height - 4) as i32, // one row higher to fit the 2-row sprite
             lives: 3,
+            invulnerable: 0,
         },
         enemies: Vec::new(),
         bullets: Vec::new(),
+        enemy_shots: Vec::new(),
+        boss: None,
         flame_bullets: Vec::new(),
         firebombs: Vec::new(),
         explosions: Vec::new(),
@@ -105,6 +539,7 @@ pub fn init_state(level: Level, width: u16, height: u16, high_score: u32) -> Ent
         score: 0,
         high_score,
         level,
+        difficulty,
         status: GameStatus::Playing,
         frame: 0,
         width,
@@ -112,8 +547,34 @@ pub fn init_state(level: Level, width: u16, height: u16, high_score: u32) -> Ent
         debug_mode: false,
         god_mode: false,
         slow_mo: false,
+        player2: None,
         muzzle_flash: 0,
-        cheer_msg: None,
+        cheer_msg: Some(wave_banner(wave.number)),
+        wave,
+        events: Vec::new(),
+        stats: RunStats::default(),
+    }
+}
+
+/// Turn a fresh solo game into a co-op one: player two joins with three
+/// lives of their own, and the ships start a third of the way in from
+/// either wall.
+pub fn with_player_two(state: &EntireGameStateInfo) -> EntireGameStateInfo {
+    let w = state.width as i32;
+    EntireGameStateInfo {
+        player: Player {
+            x: w / 3,
+            ..state.player.clone()
+        },
+        player2: Some(Player2 {
+            ship: Player {
+                x: w * 2 / 3,
+                ..state.player.clone()
+            },
+            active_power_up: None,
+            score: 0,
+        }),
+        ..state.clone()
     }
 }
 
@@ -149,22 +610,88 @@ pub fn move_player_right_n(state: &EntireGameStateInfo, n: i32) -> EntireGameSta
     }
 }
 
-/// Fire a weapon based on the active power-up.
+/// Move player two `dx` columns (negative = left).  Does nothing in a solo
+/// game or once player two is out.
+pub fn move_player_two(state: &EntireGameStateInfo, dx: i32) -> EntireGameStateInfo {
+    let Some(p2) = state.player2.as_ref().filter(|p2| p2.ship.lives > 0) else {
+        return state.clone();
+    };
+    EntireGameStateInfo {
+        player2: Some(Player2 {
+            ship: Player {
+                x: (p2.ship.x + dx).clamp(1, state.width as i32 - 2),
+                ..p2.ship.clone()
+            },
+            ..p2.clone()
+        }),
+        ..state.clone()
+    }
+}
+
+/// Fire player one's weapon based on their active power-up.
 pub fn player_shoot(state: &EntireGameStateInfo) -> EntireGameStateInfo {
-    match &state.active_power_up {
+    shoot(state, PlayerId::One)
+}
+
+/// Fire player two's weapon.  Does nothing in a solo game or once player
+/// two is out.
+pub fn player_two_shoot(state: &EntireGameStateInfo) -> EntireGameStateInfo {
+    match &state.player2 {
+        Some(p2) if p2.ship.lives > 0 => shoot(state, PlayerId::Two),
+        _ => state.clone(),
+    }
+}
+
+/// Take player two out for the rest of the run, e.g. when their network
+/// connection drops.  Their points stay on the team score.
+pub fn player_two_leaves(state: &EntireGameStateInfo) -> EntireGameStateInfo {
+    match &state.player2 {
+        Some(p2) if p2.ship.lives > 0 => EntireGameStateInfo {
+            player2: Some(Player2 {
+                ship: Player {
+                    lives: 0,
+                    invulnerable: 0,
+                    ..p2.ship.clone()
+                },
+                active_power_up: None,
+                ..p2.clone()
+            }),
+            cheer_msg: Some(("PLAYER 2 LEFT".to_string(), CHEER_DURATION)),
+            ..state.clone()
+        },
+        _ => state.clone(),
+    }
+}
+
+/// Fire `id`'s weapon.  Each player's bullets count against their own cap;
+/// the muzzle flash is player one's alone.
+fn shoot(state: &EntireGameStateInfo, id: PlayerId) -> EntireGameStateInfo {
+    let p = ship(state, id);
+    let power_up = match (id, &state.player2) {
+        (PlayerId::Two, Some(p2)) => &p2.active_power_up,
+        _ => &state.active_power_up,
+    };
+    let muzzle_flash = if id == PlayerId::One {
+        MUZZLE_FLASH_DURATION
+    } else {
+        state.muzzle_flash
+    };
+    match power_up {
         // ── FlameBurst: 4 angled flame bullets ───────────────────────────────
         Some((BonusKind::FlameBurst, _)) => {
             let mut flames = state.flame_bullets.clone();
             for &vx in &[-FLAME_VX_FAR, -FLAME_VX_NEAR, FLAME_VX_NEAR, FLAME_VX_FAR] {
                 flames.push(FlameBullet {
-                    x: state.player.x as f32,
-                    y: (state.player.y - 1) as f32,
+                    x: p.x as f32,
+                    y: (p.y - 1) as f32,
                     vx,
+                    owner: id,
                 });
             }
             EntireGameStateInfo {
                 flame_bullets: flames,
-                muzzle_flash: MUZZLE_FLASH_DURATION,
+                muzzle_flash,
+                stats: fired(&state.stats, 4),
                 ..state.clone()
             }
         }
@@ -176,13 +703,15 @@ pub fn player_shoot(state: &EntireGameStateInfo) -> EntireGameStateInfo {
             }
             let mut bombs = state.firebombs.clone();
             bombs.push(FirebombProj {
-                x: state.player.x,
-                y: state.player.y - 1,
+                x: p.x,
+                y: p.y - 1,
                 fuse: FIREBOMB_FUSE,
+                owner: id,
             });
             EntireGameStateInfo {
                 firebombs: bombs,
-                muzzle_flash: MUZZLE_FLASH_DURATION,
+                muzzle_flash,
+                stats: fired(&state.stats, 1),
                 ..state.clone()
             }
         }
@@ -196,7 +725,7 @@ pub fn player_shoot(state: &EntireGameStateInfo) -> EntireGameStateInfo {
             let active = state
                 .bullets
                 .iter()
-                .filter(|b| b.owner == BulletOwner::Player)
+                .filter(|b| b.owner == BulletOwner::Player(id))
                 .count();
 
             if active >= cap {
@@ -204,53 +733,268 @@ pub fn player_shoot(state: &EntireGameStateInfo) -> EntireGameStateInfo {
             }
 
             let mut bullets = state.bullets.clone();
+            let before = bullets.len();
 
             if is_spread {
                 if active > 0 {
                     return state.clone();
                 }
                 for &dx in &[-2_i32, 0, 2] {
-                    let bx = (state.player.x + dx).clamp(1, state.width as i32 - 2);
+                    let bx = (p.x + dx).clamp(1, state.width as i32 - 2);
                     bullets.push(Bullet {
                         x: bx,
-                        y: state.player.y - 1,
-                        owner: BulletOwner::Player,
+                        y: p.y - 1,
+                        owner: BulletOwner::Player(id),
                     });
                 }
             } else {
                 bullets.push(Bullet {
-                    x: state.player.x,
-                    y: state.player.y - 1,
-                    owner: BulletOwner::Player,
+                    x: p.x,
+                    y: p.y - 1,
+                    owner: BulletOwner::Player(id),
                 });
             }
 
+            let stats = fired(&state.stats, (bullets.len() - before) as u32);
             EntireGameStateInfo {
                 bullets,
-                muzzle_flash: MUZZLE_FLASH_DURATION,
+                muzzle_flash,
+                stats,
                 ..state.clone()
             }
         }
     }
 }
 
+fn fired(stats: &RunStats, shots: u32) -> RunStats {
+    RunStats {
+        shots_fired: stats.shots_fired + shots,
+        ..stats.clone()
+    }
+}
+
+/// Apply one recorded player input.  The game loop, the demo bot and replay
+/// playback all route their inputs through here so a run can be reproduced
+/// from its action log alone.
+pub fn apply_action(state: &EntireGameStateInfo, action: &PlayerAction) -> EntireGameStateInfo {
+    let paused = matches!(state.status, GameStatus::Paused(_));
+    match action {
+        // The ships stay put while the game is paused.
+        PlayerAction::MoveLeft(_)
+        | PlayerAction::MoveRight(_)
+        | PlayerAction::Shoot
+        | PlayerAction::P2MoveLeft(_)
+        | PlayerAction::P2MoveRight(_)
+        | PlayerAction::P2Shoot
+            if paused =>
+        {
+            state.clone()
+        }
+        PlayerAction::MoveLeft(n) => move_player_left_n(state, *n),
+        PlayerAction::MoveRight(n) => move_player_right_n(state, *n),
+        PlayerAction::Shoot => player_shoot(state),
+        PlayerAction::P2MoveLeft(n) => move_player_two(state, -*n),
+        PlayerAction::P2MoveRight(n) => move_player_two(state, *n),
+        PlayerAction::P2Shoot => player_two_shoot(state),
+        PlayerAction::P2Leave => player_two_leaves(state),
+        PlayerAction::ToggleDebug => EntireGameStateInfo {
+            debug_mode: !state.debug_mode,
+            ..state.clone()
+        },
+        PlayerAction::ToggleGodMode => EntireGameStateInfo {
+            god_mode: !state.god_mode,
+            ..state.clone()
+        },
+        PlayerAction::ToggleSlowMo => EntireGameStateInfo {
+            slow_mo: !state.slow_mo,
+            ..state.clone()
+        },
+        PlayerAction::Resize(width, height) => resize(state, *width, *height),
+    }
+}
+
+// ── Pause ───────────────────────────────────────────────────────────────────
+
+/// Pause a running game, with the menu cursor on "Resume".  Any other state
+/// is returned unchanged.
+pub fn pause(state: &EntireGameStateInfo) -> EntireGameStateInfo {
+    if state.status != GameStatus::Playing {
+        return state.clone();
+    }
+    EntireGameStateInfo {
+        status: GameStatus::Paused(PauseItem::Resume),
+        ..state.clone()
+    }
+}
+
+/// Leave the pause menu and carry on playing.
+pub fn resume(state: &EntireGameStateInfo) -> EntireGameStateInfo {
+    if !matches!(state.status, GameStatus::Paused(_)) {
+        return state.clone();
+    }
+    EntireGameStateInfo {
+        status: GameStatus::Playing,
+        ..state.clone()
+    }
+}
+
+/// Move the pause-menu cursor by `delta` items, wrapping at either end.
+pub fn pause_select(state: &EntireGameStateInfo, delta: i32) -> EntireGameStateInfo {
+    let GameStatus::Paused(current) = &state.status else {
+        return state.clone();
+    };
+    let n = PauseItem::ALL.len() as i32;
+    let index = PauseItem::ALL
+        .iter()
+        .position(|i| i == current)
+        .unwrap_or(0) as i32;
+    let next = PauseItem::ALL[(index + delta).rem_euclid(n) as usize].clone();
+    EntireGameStateInfo {
+        status: GameStatus::Paused(next),
+        ..state.clone()
+    }
+}
+
+// ── Resize ────────────────────────────────────────────────────────────────────
+
+/// `true` when a `width` × `height` terminal is big enough to play in.
+pub fn playfield_fits(width: u16, height: u16) -> bool {
+    width >= MIN_WIDTH && height >= MIN_HEIGHT
+}
+
+/// Rescale the playfield to a `width` × `height` terminal and pull everything
+/// back inside the new walls.  The player keeps to the row above the bottom
+/// border and enemies stay above the player, so shrinking the window never
+/// costs a life by itself.  Sizes below `MIN_WIDTH` × `MIN_HEIGHT` are raised
+/// to the minimum.
+pub fn resize(state: &EntireGameStateInfo, width: u16, height: u16) -> EntireGameStateInfo {
+    let (width, height) = (width.max(MIN_WIDTH), height.max(MIN_HEIGHT));
+    let (w, h) = (width as i32, height as i32);
+    // Columns 1..=w-2 and rows 2..=h-3 lie inside the border.
+    let clamp_x = |x: i32| x.clamp(1, w - 2);
+    let clamp_y = |y: i32| y.clamp(2, h - 3);
+    let clamp_xf = |x: f32| x.clamp(1.0, (w - 2) as f32);
+    let clamp_yf = |y: f32| y.clamp(2.0, (h - 3) as f32);
+    let player_y = h - 4;
+
+    EntireGameStateInfo {
+        player: Player {
+            x: clamp_x(state.player.x),
+            y: player_y,
+            ..state.player.clone()
+        },
+        player2: state.player2.as_ref().map(|p2| Player2 {
+            ship: Player {
+                x: clamp_x(p2.ship.x),
+                y: player_y,
+                ..p2.ship.clone()
+            },
+            ..p2.clone()
+        }),
+        enemies: state
+            .enemies
+            .iter()
+            .map(|e| Enemy {
+                x: e.x.clamp(2, w - 3),
+                y: e.y.clamp(2, player_y - 1),
+                ..e.clone()
+            })
+            .collect(),
+        bullets: state
+            .bullets
+            .iter()
+            .map(|b| Bullet {
+                x: clamp_x(b.x),
+                y: clamp_y(b.y),
+                ..b.clone()
+            })
+            .collect(),
+        enemy_shots: state
+            .enemy_shots
+            .iter()
+            .map(|s| EnemyShot {
+                x: clamp_xf(s.x),
+                y: clamp_yf(s.y),
+                ..*s
+            })
+            .collect(),
+        boss: state.boss.as_ref().map(|b| Boss {
+            x: b.x.clamp(
+                1 + BOSS_HALF_WIDTH,
+                (w - 2 - BOSS_HALF_WIDTH).max(1 + BOSS_HALF_WIDTH),
+            ),
+            ..b.clone()
+        }),
+        flame_bullets: state
+            .flame_bullets
+            .iter()
+            .map(|fb| FlameBullet {
+                x: clamp_xf(fb.x),
+                y: clamp_yf(fb.y),
+                ..fb.clone()
+            })
+            .collect(),
+        firebombs: state
+            .firebombs
+            .iter()
+            .map(|fb| FirebombProj {
+                x: clamp_x(fb.x),
+                y: clamp_y(fb.y),
+                ..fb.clone()
+            })
+            .collect(),
+        explosions: state
+            .explosions
+            .iter()
+            .map(|e| Explosion {
+                x: clamp_x(e.x),
+                y: clamp_y(e.y),
+                ..e.clone()
+            })
+            .collect(),
+        bonus_items: state
+            .bonus_items
+            .iter()
+            .map(|b| BonusItem {
+                x: clamp_x(b.x),
+                y: clamp_y(b.y),
+                ..b.clone()
+            })
+            .collect(),
+        width,
+        height,
+        ..state.clone()
+    }
+}
+
 // ── Per-frame tick (nearly pure — RNG is injected) ──────────────────────────
 
 /// Advance the simulation by one frame.  All randomness comes through `rng`
 /// so callers control determinism (useful for tests with a seeded RNG).
+/// A paused game does not advance: every timer, power-up included, stays frozen.
 pub fn tick(state: &EntireGameStateInfo, rng: &mut impl Rng) -> EntireGameStateInfo {
+    if matches!(state.status, GameStatus::Paused(_)) {
+        return state.clone();
+    }
     let frame = state.frame + 1;
+    let mut events: Vec<GameEvent> = Vec::new();
 
     let w = state.width as i32;
     let h = state.height as i32;
 
+    let spec = wave_spec(&state.level, &state.difficulty, state.wave.number);
+    let mut wave = WaveState {
+        breather: state.wave.breather.saturating_sub(1),
+        ..state.wave.clone()
+    };
+
     // ── 1. Move standard bullets ─────────────────────────────────────────────
     let bullets: Vec<Bullet> = state
         .bullets
         .iter()
         .filter_map(|b| {
             let new_y = match b.owner {
-                BulletOwner::Player => b.y - 1,
+                BulletOwner::Player(_) => b.y - 1,
                 BulletOwner::Enemy => b.y + 1,
             };
             if new_y < 2 || new_y > h - 3 {
@@ -277,84 +1021,123 @@ pub fn tick(state: &EntireGameStateInfo, rng: &mut impl Rng) -> EntireGameStateI
                 Some(FlameBullet {
                     x: nx,
                     y: ny,
-                    vx: fb.vx,
+                    ..fb.clone()
                 })
             }
         })
         .collect();
 
-    // ── 3. Move enemies down on their interval ───────────────────────────────
-    let move_interval = enemy_move_interval(&state.level);
-    let enemies: Vec<Enemy> = if frame.is_multiple_of(move_interval) {
-        state
-            .enemies
-            .iter()
-            .map(|e| Enemy {
-                y: e.y + 1,
-                ..e.clone()
-            })
-            .collect()
-    } else {
-        state.enemies.clone()
-    };
+    // ── 2b. Move enemy shots (float velocity) ────────────────────────────────
+    let enemy_shots: Vec<EnemyShot> = state
+        .enemy_shots
+        .iter()
+        .filter_map(|s| {
+            let nx = s.x + s.vx;
+            let ny = s.y + s.vy;
+            if nx < 1.0 || nx > (w - 2) as f32 || ny < 2.0 || ny > (h - 3) as f32 {
+                None
+            } else {
+                Some(EnemyShot { x: nx, y: ny, ..*s })
+            }
+        })
+        .collect();
 
-    // ── 3. Spawn a new enemy ─────────────────────────────────────────────────
-    let spawn_rate = enemy_spawn_rate(&state.level);
+    // ── 3. Move enemies (each by its own behaviour) ──────────────────────────
+    // Divers home in on the nearest ship.
+    let enemies: Vec<Enemy> = state
+        .enemies
+        .iter()
+        .map(|e| {
+            let target_x = nearest_ship(state, e.x).x;
+            move_enemy(e, frame, spec.enemy_move_interval, target_x, w, rng)
+        })
+        .collect();
+
+    // ── 4. Spawn new enemies (while the wave has budget left) ────────────────
     let mut enemies = enemies;
-    if frame.is_multiple_of(spawn_rate) {
+    if wave.breather == 0 && wave.remaining > 0 && frame.is_multiple_of(spec.enemy_spawn_rate) {
         let x = rng.gen_range(2..(state.width as i32 - 2));
-        let kind = if rng.gen_bool(0.6) {
+        // Wave 1 never rolls for armour, so its RNG sequence is unchanged.
+        let kind = if spec.armored_chance > 0.0 && rng.gen_bool(spec.armored_chance) {
+            EnemyKind::Armored
+        } else if rng.gen_bool(spec.spacecraft_chance) {
             EnemyKind::Spacecraft
         } else {
             EnemyKind::Octopus
         };
-        enemies.push(Enemy { x, y: 2, kind });
+        // Likewise no behaviour roll until one is unlocked.
+        let roll = if spec.formation_chance + spec.diver_chance + spec.zigzag_chance > 0.0 {
+            rng.gen::<f64>()
+        } else {
+            1.0
+        };
+        let spawned = spawn_group(&spec, roll, x, kind, wave.remaining, w);
+        wave.remaining -= spawned.len() as u32;
+        enemies.extend(spawned);
     }
 
-    // ── 4. Enemies randomly shoot ────────────────────────────────────────────
+    // ── 5. Enemies randomly shoot (pattern set by their kind) ────────────────
     let mut bullets = bullets;
+    let mut enemy_shots = enemy_shots;
     for enemy in &enemies {
-        if rng.gen_ratio(1, 220) {
-            bullets.push(Bullet {
-                x: enemy.x,
-                y: enemy.y + 1,
+        let profile = enemy.kind.fire_profile();
+        let chance = spec.enemy_fire_chance.saturating_mul(profile.rarity);
+        if !rng.gen_ratio(1, chance) {
+            continue;
+        }
+        let (x, y) = (enemy.x, enemy.y + 1);
+        match profile.pattern {
+            FirePattern::Straight => bullets.push(Bullet {
+                x,
+                y,
                 owner: BulletOwner::Enemy,
-            });
+            }),
+            FirePattern::Aimed => {
+                enemy_shots.push(enemy_shot(x, y, aim_at(x, y, nearest_ship(state, x))))
+            }
+            FirePattern::Spread => {
+                enemy_shots.extend(ENEMY_SPREAD_ANGLES.iter().map(|&deg| enemy_shot(x, y, deg)))
+            }
         }
     }
 
-    // ── 5. Collision: player bullets ↔ enemies ───────────────────────────────
-    let mut killed_enemies: Vec<usize> = Vec::new();
+    // ── 5b. Boss patrols and attacks ─────────────────────────────────────────
+    let boss: Option<Boss> = state.boss.as_ref().map(|b| {
+        let (boss, shots) = boss_step(b, frame, nearest_ship(state, b.x), w);
+        enemy_shots.extend(shots);
+        boss
+    });
+
+    // ── 6. Collision: player bullets ↔ enemies ───────────────────────────────
+    // Each hit costs the enemy one HP; it is destroyed — and scores — only
+    // when its HP runs out.  A bullet skips enemies already finished off
+    // this frame.  Points go to whichever player's shot finished it.
+    let mut score_gain: [u32; 2] = [0; 2];
+    let mut shots_hit: u32 = 0;
+    let mut damage: Vec<u32> = vec![0; enemies.len()];
+    let mut hit_by: Vec<PlayerId> = vec![PlayerId::One; enemies.len()];
     let mut used_bullets: Vec<usize> = Vec::new();
 
     for (bi, bullet) in bullets.iter().enumerate() {
-        if bullet.owner != BulletOwner::Player {
+        let BulletOwner::Player(owner) = bullet.owner else {
             continue;
-        }
+        };
         for (ei, enemy) in enemies.iter().enumerate() {
             // Hit if bullet lands within the 3-wide, 2-tall enemy bounding box
             if (bullet.x - enemy.x).abs() <= 1
                 && (bullet.y == enemy.y || bullet.y == enemy.y + 1)
-                && !killed_enemies.contains(&ei)
+                && damage[ei] < enemy.hp
             {
-                killed_enemies.push(ei);
+                damage[ei] += 1;
+                hit_by[ei] = owner;
                 used_bullets.push(bi);
                 break;
             }
         }
     }
 
-    let mut score_gain: u32 = killed_enemies
-        .iter()
-        .map(|&i| score_for(&enemies[i].kind))
-        .sum();
-
-    let enemies: Vec<Enemy> = enemies
-        .iter()
-        .enumerate()
-        .filter(|(i, _)| !killed_enemies.contains(i))
-        .map(|(_, e)| e.clone())
-        .collect();
+    let enemies = resolve_enemy_damage(enemies, &damage, &hit_by, &mut score_gain, &mut events);
+    shots_hit += used_bullets.len() as u32;
 
     let bullets: Vec<Bullet> = bullets
         .iter()
@@ -363,31 +1146,24 @@ pub fn tick(state: &EntireGameStateInfo, rng: &mut impl Rng) -> EntireGameStateI
         .map(|(_, b)| b.clone())
         .collect();
 
-    // ── 6. Collision: flame bullets ↔ enemies ────────────────────────────────
-    let mut flame_killed: Vec<usize> = Vec::new();
+    // ── 7. Collision: flame bullets ↔ enemies ────────────────────────────────
+    let mut damage: Vec<u32> = vec![0; enemies.len()];
+    let mut hit_by: Vec<PlayerId> = vec![PlayerId::One; enemies.len()];
     let mut used_flames: Vec<usize> = Vec::new();
     for (fi, fb) in flame_bullets.iter().enumerate() {
         let bx = fb.x.round() as i32;
         let by = fb.y.round() as i32;
         for (ei, e) in enemies.iter().enumerate() {
-            if (bx - e.x).abs() <= 1 && (by == e.y || by == e.y + 1) && !flame_killed.contains(&ei)
-            {
-                flame_killed.push(ei);
+            if (bx - e.x).abs() <= 1 && (by == e.y || by == e.y + 1) && damage[ei] < e.hp {
+                damage[ei] += 1;
+                hit_by[ei] = fb.owner;
                 used_flames.push(fi);
                 break;
             }
         }
     }
-    score_gain += flame_killed
-        .iter()
-        .map(|&i| score_for(&enemies[i].kind))
-        .sum::<u32>();
-    let enemies: Vec<Enemy> = enemies
-        .iter()
-        .enumerate()
-        .filter(|(i, _)| !flame_killed.contains(i))
-        .map(|(_, e)| e.clone())
-        .collect();
+    let enemies = resolve_enemy_damage(enemies, &damage, &hit_by, &mut score_gain, &mut events);
+    shots_hit += used_flames.len() as u32;
     let flame_bullets: Vec<FlameBullet> = flame_bullets
         .iter()
         .enumerate()
@@ -395,18 +1171,69 @@ pub fn tick(state: &EntireGameStateInfo, rng: &mut impl Rng) -> EntireGameStateI
         .map(|(_, fb)| fb.clone())
         .collect();
 
-    // ── 7. Collision: enemy bullets ↔ player ─────────────────────────────────
-    let mut player_hit = false;
+    // ── 7b. Collision: player bullets & flames ↔ boss ────────────────────────
+    // Each projectile that lands inside the boss's hitbox costs it one HP.
+    // Damage is totalled here and resolved after the firebomb pass (8b),
+    // along with who hit it last.
+    let mut boss_damage: u32 = 0;
+    let mut boss_hit_by = PlayerId::One;
+    let (bullets, flame_bullets) = match &boss {
+        Some(b) => {
+            let bullets: Vec<Bullet> = bullets
+                .into_iter()
+                .filter(|bl| match bl.owner {
+                    BulletOwner::Player(owner) if boss_dist_sq(b, bl.x, bl.y) == 0 => {
+                        boss_damage += 1;
+                        boss_hit_by = owner;
+                        false
+                    }
+                    _ => true,
+                })
+                .collect();
+            let flame_bullets: Vec<FlameBullet> = flame_bullets
+                .into_iter()
+                .filter(|fb| {
+                    let hit = boss_dist_sq(b, fb.x.round() as i32, fb.y.round() as i32) == 0;
+                    if hit {
+                        boss_damage += 1;
+                        boss_hit_by = fb.owner;
+                    }
+                    !hit
+                })
+                .collect();
+            (bullets, flame_bullets)
+        }
+        None => (bullets, flame_bullets),
+    };
+
+    shots_hit += boss_damage;
+
+    // ── 8. Collision: enemy bullets ↔ players ────────────────────────────────
+    // First thing that hit each ship this frame; only one life is lost per
+    // ship per frame.  While invulnerable, enemy fire passes through a ship
+    // and contact enemies crash harmlessly.  A ship that is out of the game
+    // cannot be hit at all.
+    let in_play = players_in_play(state);
+    let exposed: Vec<PlayerId> = in_play
+        .iter()
+        .copied()
+        .filter(|&id| ship(state, id).invulnerable == 0)
+        .collect();
+    let hits_ship = |x: i32, y: i32| {
+        exposed.iter().copied().find(|&id| {
+            let p = ship(state, id);
+            (x - p.x).abs() <= 1 && (y == p.y || y == p.y + 1)
+        })
+    };
+    let mut player_hit: [Option<HitCause>; 2] = [None, None];
     let mut used_bullets2: Vec<usize> = Vec::new();
 
     for (bi, bullet) in bullets.iter().enumerate() {
         if bullet.owner != BulletOwner::Enemy {
             continue;
         }
-        if (bullet.x - state.player.x).abs() <= 1
-            && (bullet.y == state.player.y || bullet.y == state.player.y + 1)
-        {
-            player_hit = true;
+        if let Some(id) = hits_ship(bullet.x, bullet.y) {
+            player_hit[id.index()].get_or_insert(HitCause::EnemyBullet);
             used_bullets2.push(bi);
         }
     }
@@ -418,12 +1245,30 @@ pub fn tick(state: &EntireGameStateInfo, rng: &mut impl Rng) -> EntireGameStateI
         .map(|(_, b)| b.clone())
         .collect();
 
-    // Enemies that reach the player's row crash into the player (1 life lost)
-    // and are removed from the field — no per-frame repeated damage.
+    let enemy_shots: Vec<EnemyShot> = enemy_shots
+        .into_iter()
+        .filter(|s| {
+            let hit = hits_ship(s.x.round() as i32, s.y.round() as i32);
+            if let Some(id) = hit {
+                player_hit[id.index()].get_or_insert(HitCause::EnemyBullet);
+            }
+            hit.is_none()
+        })
+        .collect();
+
+    // Enemies that reach the ships' row crash into the nearest ship (1 life
+    // lost) and are removed from the field — no per-frame repeated damage.
     let mut contact_indices: Vec<usize> = Vec::new();
     for (i, e) in enemies.iter().enumerate() {
         if e.y >= state.player.y {
-            player_hit = true;
+            let target = in_play
+                .iter()
+                .copied()
+                .min_by_key(|&id| (ship(state, id).x - e.x).abs())
+                .unwrap_or(PlayerId::One);
+            if exposed.contains(&target) {
+                player_hit[target.index()].get_or_insert(HitCause::EnemyContact);
+            }
             contact_indices.push(i);
         }
     }
@@ -437,7 +1282,7 @@ pub fn tick(state: &EntireGameStateInfo, rng: &mut impl Rng) -> EntireGameStateI
     // Remove enemies that have gone past the bottom border
     let enemies: Vec<Enemy> = enemies.into_iter().filter(|e| e.y < h - 2).collect();
 
-    // ── 8. Move firebombs + detect detonation ────────────────────────────────
+    // ── 9. Move firebombs + detect detonation ────────────────────────────────
     let firebombs_moved: Vec<FirebombProj> = state
         .firebombs
         .iter()
@@ -455,7 +1300,7 @@ pub fn tick(state: &EntireGameStateInfo, rng: &mut impl Rng) -> EntireGameStateI
         })
         .collect();
 
-    let mut detonation_points: Vec<(i32, i32)> = Vec::new();
+    let mut blasts: Vec<(i32, i32, PlayerId)> = Vec::new();
     let firebombs: Vec<FirebombProj> = firebombs_moved
         .into_iter()
         .filter(|bomb| {
@@ -463,37 +1308,65 @@ pub fn tick(state: &EntireGameStateInfo, rng: &mut impl Rng) -> EntireGameStateI
                 let dx = e.x - bomb.x;
                 let dy = e.y - bomb.y;
                 dx * dx + dy * dy <= EXPLOSION_TRIGGER_RADIUS_SQ
-            });
+            }) || boss
+                .as_ref()
+                .is_some_and(|b| boss_dist_sq(b, bomb.x, bomb.y) <= EXPLOSION_TRIGGER_RADIUS_SQ);
             let should_detonate = proximity_hit || bomb.fuse == 0 || bomb.y <= 2;
             if should_detonate {
-                detonation_points.push((bomb.x, bomb.y));
+                blasts.push((bomb.x, bomb.y, bomb.owner));
             }
             !should_detonate
         })
         .collect();
 
-    let mut bomb_killed: Vec<usize> = Vec::new();
-    for &(bx, by) in &detonation_points {
+    // Every blast that reaches an enemy deals EXPLOSION_DAMAGE; overlapping
+    // blasts stack.  A blast that reaches anything counts as one hit.
+    let mut damage: Vec<u32> = vec![0; enemies.len()];
+    let mut hit_by: Vec<PlayerId> = vec![PlayerId::One; enemies.len()];
+    let mut blast_hit: Vec<bool> = vec![false; blasts.len()];
+    for (i, &(bx, by, owner)) in blasts.iter().enumerate() {
         for (ei, e) in enemies.iter().enumerate() {
             let dx = e.x - bx;
             let dy = e.y - by;
-            if dx * dx + dy * dy <= EXPLOSION_KILL_RADIUS_SQ && !bomb_killed.contains(&ei) {
-                bomb_killed.push(ei);
+            if dx * dx + dy * dy <= EXPLOSION_KILL_RADIUS_SQ {
+                damage[ei] += EXPLOSION_DAMAGE;
+                hit_by[ei] = owner;
+                blast_hit[i] = true;
             }
         }
     }
-    score_gain += bomb_killed
-        .iter()
-        .map(|&i| score_for(&enemies[i].kind))
-        .sum::<u32>();
-    let enemies: Vec<Enemy> = enemies
-        .iter()
-        .enumerate()
-        .filter(|(i, _)| !bomb_killed.contains(i))
-        .map(|(_, e)| e.clone())
-        .collect();
+    let enemies = resolve_enemy_damage(enemies, &damage, &hit_by, &mut score_gain, &mut events);
+
+    // ── 9b. Resolve boss damage ──────────────────────────────────────────────
+    if let Some(b) = &boss {
+        for (i, &(bx, by, owner)) in blasts.iter().enumerate() {
+            if boss_dist_sq(b, bx, by) <= EXPLOSION_KILL_RADIUS_SQ {
+                boss_damage += BOSS_BLAST_DAMAGE;
+                boss_hit_by = owner;
+                blast_hit[i] = true;
+            }
+        }
+    }
+    shots_hit += blast_hit.iter().filter(|&&hit| hit).count() as u32;
+    let mut detonation_points: Vec<(i32, i32)> = blasts.iter().map(|&(x, y, _)| (x, y)).collect();
+    let mut boss_defeated = false;
+    let boss: Option<Boss> = match boss {
+        Some(b) if boss_damage >= b.hp => {
+            score_gain[boss_hit_by.index()] += b.max_hp * BOSS_POINTS_PER_HP;
+            events.push(GameEvent::BossDefeated);
+            boss_defeated = true;
+            // Go out with a bang: a row of explosion visuals across the hull.
+            detonation_points.extend([(b.x - 2, b.y + 1), (b.x, b.y + 1), (b.x + 2, b.y + 1)]);
+            None
+        }
+        Some(b) => Some(Boss {
+            hp: b.hp - boss_damage,
+            ..b
+        }),
+        None => None,
+    };
 
-    // ── 9. Tick down existing explosions; add new ones ────────────────────────
+    // ── 10. Tick down existing explosions; add new ones ───────────────────────
     let explosions: Vec<Explosion> = state
         .explosions
         .iter()
@@ -514,7 +1387,7 @@ pub fn tick(state: &EntireGameStateInfo, rng: &mut impl Rng) -> EntireGameStateI
         }))
         .collect();
 
-    // ── 10. Move bonus items ──────────────────────────────────────────────────
+    // ── 11. Move bonus items ──────────────────────────────────────────────────
     let bonus_items: Vec<BonusItem> = if frame.is_multiple_of(BONUS_MOVE_INTERVAL) {
         state
             .bonus_items
@@ -535,9 +1408,9 @@ pub fn tick(state: &EntireGameStateInfo, rng: &mut impl Rng) -> EntireGameStateI
         state.bonus_items.clone()
     };
 
-    // ── 11. Spawn a new bonus item ────────────────────────────────────────────
+    // ── 12. Spawn a new bonus item ────────────────────────────────────────────
     let mut bonus_items = bonus_items;
-    if frame.is_multiple_of(BONUS_SPAWN_INTERVAL) {
+    if frame.is_multiple_of(state.difficulty.bonus_spawn_interval) {
         let x = rng.gen_range(2..(w - 2));
         let kind = match rng.gen_range(0..5u32) {
             0 => BonusKind::SpreadShot,
@@ -549,73 +1422,123 @@ pub fn tick(state: &EntireGameStateInfo, rng: &mut impl Rng) -> EntireGameStateI
         bonus_items.push(BonusItem { x, y: 2, kind });
     }
 
-    // ── 12. Tick down the active power-up ────────────────────────────────────
-    let active_power_up = state.active_power_up.as_ref().and_then(|(kind, frames)| {
-        if *frames > 1 {
-            Some((kind.clone(), frames - 1))
-        } else {
-            None
-        }
-    });
+    // ── 13. Tick down the active power-ups ───────────────────────────────────
+    let tick_power_up = |slot: &Option<(BonusKind, u32)>| {
+        slot.as_ref().and_then(|(kind, frames)| {
+            if *frames > 1 {
+                Some((kind.clone(), frames - 1))
+            } else {
+                None
+            }
+        })
+    };
+    let mut new_power_up = [
+        tick_power_up(&state.active_power_up),
+        state
+            .player2
+            .as_ref()
+            .and_then(|p2| tick_power_up(&p2.active_power_up)),
+    ];
 
-    // ── 13. Collision: player catches bonus items ─────────────────────────────
-    let mut extra_lives: u32 = 0;
-    let mut new_power_up = active_power_up;
+    // ── 14. Collision: players catch bonus items ──────────────────────────────
+    // When both ships are under an item, player one gets it.
+    let mut extra_lives: [u32; 2] = [0; 2];
 
     let bonus_items: Vec<BonusItem> = bonus_items
         .into_iter()
         .filter(|b| {
-            let caught = (b.x - state.player.x).abs() <= 1
-                && (b.y == state.player.y || b.y == state.player.y + 1);
-            if caught {
-                match &b.kind {
-                    BonusKind::ExtraLife => {
-                        extra_lives += 1;
-                    }
-                    BonusKind::SpreadShot => {
-                        new_power_up = Some((BonusKind::SpreadShot, POWER_UP_DURATION));
-                    }
-                    BonusKind::RapidFire => {
-                        new_power_up = Some((BonusKind::RapidFire, POWER_UP_DURATION));
-                    }
-                    BonusKind::FlameBurst => {
-                        new_power_up = Some((BonusKind::FlameBurst, POWER_UP_DURATION));
-                    }
-                    BonusKind::Firebomb => {
-                        new_power_up = Some((BonusKind::Firebomb, POWER_UP_DURATION));
-                    }
+            let catcher = in_play.iter().copied().find(|&id| {
+                let p = ship(state, id);
+                (b.x - p.x).abs() <= 1 && (b.y == p.y || b.y == p.y + 1)
+            });
+            let Some(id) = catcher else {
+                return true;
+            };
+            events.push(GameEvent::PowerUpCaught(b.kind.clone()));
+            match &b.kind {
+                BonusKind::ExtraLife => {
+                    extra_lives[id.index()] += 1;
+                }
+                kind => {
+                    new_power_up[id.index()] = Some((kind.clone(), POWER_UP_DURATION));
                 }
             }
-            !caught
+            false
         })
         .collect();
 
-    // ── 11. Update player & status ────────────────────────────────────────────
-    let hit_lives = if player_hit && !state.god_mode {
-        state.player.lives.saturating_sub(1)
+    // ── 15. Update players & status ───────────────────────────────────────────
+    // Losing a life starts the ship's invulnerability window and, depending
+    // on the difficulty, wipes enemy fire off the field and ends its power-up.
+    let mut any_life_lost = false;
+    let mut ships: Vec<Player> = Vec::new();
+    for id in PlayerId::ALL {
+        let p = match (id, &state.player2) {
+            (PlayerId::One, _) => &state.player,
+            (PlayerId::Two, Some(p2)) => &p2.ship,
+            (PlayerId::Two, None) => break,
+        };
+        let life_lost = match player_hit[id.index()].take() {
+            Some(cause) if !state.god_mode => {
+                events.push(GameEvent::PlayerHit(cause));
+                true
+            }
+            _ => false,
+        };
+        let hit_lives = p.lives.saturating_sub(life_lost as u32);
+        let invulnerable = if life_lost {
+            INVULNERABLE_FRAMES
+        } else {
+            p.invulnerable.saturating_sub(1)
+        };
+        if life_lost && state.difficulty.power_up_loss == PowerUpLoss::Lose {
+            new_power_up[id.index()] = None;
+        }
+        any_life_lost |= life_lost;
+        ships.push(Player {
+            lives: (hit_lives + extra_lives[id.index()]).min(MAX_LIVES),
+            invulnerable,
+            ..p.clone()
+        });
+    }
+    let (bullets, enemy_shots) = if any_life_lost && state.difficulty.clear_shots_on_hit {
+        let player_bullets = bullets
+            .into_iter()
+            .filter(|b| b.owner != BulletOwner::Enemy)
+            .collect();
+        (player_bullets, Vec::new())
     } else {
-        state.player.lives
+        (bullets, enemy_shots)
     };
-    let new_lives = (hit_lives + extra_lives).min(MAX_LIVES);
 
-    let status = if new_lives == 0 {
+    // The game is over once every ship is out of lives.
+    let status = if ships.iter().all(|p| p.lives == 0) {
         GameStatus::GameOver
     } else {
         GameStatus::Playing
     };
 
-    let player = Player {
-        lives: new_lives,
-        ..state.player.clone()
-    };
+    let [p1_power_up, p2_power_up] = new_power_up;
+    let mut ships = ships.into_iter();
+    let player = ships.next().unwrap_or_else(|| state.player.clone());
+    let player2 = state
+        .player2
+        .as_ref()
+        .zip(ships.next())
+        .map(|(p2, ship)| Player2 {
+            ship,
+            active_power_up: p2_power_up,
+            score: p2.score.saturating_add(score_gain[1]),
+        });
 
+    let score_gain = score_gain[0] + score_gain[1];
     let new_score = state.score.saturating_add(score_gain);
     let new_high_score = state.high_score.max(new_score);
 
-    // ── 12. Tick muzzle flash ─────────────────────────────────────────────────
+    // ── 16. Tick muzzle flash ─────────────────────────────────────────────────
     let muzzle_flash = state.muzzle_flash.saturating_sub(1);
 
-    // ── 13. Score milestone cheer ─────────────────────────────────────────────
+    // ── 17. Score milestone cheer ─────────────────────────────────────────────
     // A new milestone message always overrides the current one.
     let cheer_msg = SCORE_MILESTONES
         .iter()
@@ -632,21 +1555,80 @@ pub fn tick(state: &EntireGameStateInfo, rng: &mut impl Rng) -> EntireGameStateI
             })
         });
 
+    // ── 18. Wave progression and boss arrival ────────────────────────────────
+    // Once the budget is spent and the field is clear, a boss wave brings on
+    // its boss; any other wave (or a boss wave whose boss is beaten) hands
+    // over to the next wave after a breather.  Crossing a multiple of
+    // BOSS_SCORE_INTERVAL also summons a boss mid-wave.  The newest banner
+    // takes over the cheer slot.
+    let mut cheer_msg = cheer_msg;
+    let mut boss = boss;
+    let wave_done =
+        wave.remaining == 0 && wave.breather == 0 && enemies.is_empty() && boss.is_none();
+    if wave_done && wave.boss_due {
+        wave.boss_due = false;
+        boss = Some(new_boss(wave.number, state.width));
+        cheer_msg = Some(boss_banner());
+    } else if wave_done {
+        events.push(GameEvent::WaveCleared(wave.number));
+        wave = start_wave(&state.level, &state.difficulty, wave.number + 1);
+        cheer_msg = Some(wave_banner(wave.number));
+    } else if boss.is_none()
+        && !boss_defeated
+        && new_score / BOSS_SCORE_INTERVAL > state.score / BOSS_SCORE_INTERVAL
+    {
+        boss = Some(new_boss(wave.number, state.width));
+        cheer_msg = Some(boss_banner());
+    }
+
+    // ── 19. Run statistics ───────────────────────────────────────────────────
+    let stats = tally(&state.stats, &events, shots_hit, score_gain);
+
     EntireGameStateInfo {
         player,
         enemies,
         bullets,
+        enemy_shots,
+        boss,
         flame_bullets,
         firebombs,
         explosions,
         bonus_items,
-        active_power_up: new_power_up,
+        player2,
+        active_power_up: p1_power_up,
         score: new_score,
         high_score: new_high_score,
         status,
         frame,
         muzzle_flash,
         cheer_msg,
+        wave,
+        events,
+        stats,
         ..state.clone()
     }
 }
+
+/// Fold one tick's hits, points and events into the run's totals.
+fn tally(stats: &RunStats, events: &[GameEvent], hits: u32, points: u32) -> RunStats {
+    let mut stats = RunStats {
+        shots_hit: stats.shots_hit + hits,
+        frames: stats.frames + 1,
+        window_points: stats.window_points.saturating_add(points),
+        ..stats.clone()
+    };
+    for event in events {
+        match event {
+            GameEvent::EnemyKilled(kind) => stats.kills[kind.index()] += 1,
+            GameEvent::PowerUpCaught(kind) => stats.power_ups[kind.index()] += 1,
+            GameEvent::PlayerHit(_) => stats.lives_lost += 1,
+            GameEvent::BossDefeated => stats.bosses += 1,
+            GameEvent::WaveCleared(_) => {}
+        }
+    }
+    stats.peak_rate = stats.peak_rate.max(stats.window_points);
+    if stats.frames.is_multiple_of(SCORE_RATE_WINDOW) {
+        stats.window_points = 0;
+    }
+    stats
+}
@@ -1,28 +1,204 @@
-use rusqlite::{params, Connection, Result};
-use std::path::PathBuf;
+use rusqlite::{params, Connection, ErrorCode, OptionalExtension};
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
 
-use shooting_game::entities::Level;
+use crate::entities::RunStats;
 
-fn db_path() -> PathBuf {
-    PathBuf::from("shooting_game.db")
+/// Environment variable naming the database file, for when `--db` is not
+/// given.
+pub const DB_ENV: &str = "SHOOTING_GAME_DB";
+
+/// The database's file name.  Older versions kept it in the working
+/// directory; see `adopt_legacy`.
+pub const DB_FILE: &str = "shooting_game.db";
+
+/// Given as the path, selects an in-memory database (SQLite's own name).
+pub const MEMORY: &str = ":memory:";
+
+/// How long a write waits for another program's lock before giving up, so a
+/// locked database never stalls the game.
+const BUSY_TIMEOUT: Duration = Duration::from_millis(250);
+
+// ── Errors ────────────────────────────────────────────────────────────────────
+
+/// Why the database could not be read or written.
+#[derive(Clone, Debug, PartialEq)]
+pub enum DbError {
+    /// Another program holds a lock on the database.
+    Locked,
+    /// The file or its directory cannot be written.
+    ReadOnly,
+    /// The file is not an SQLite database, or it is damaged.
+    Corrupt,
+    /// The file or its directory cannot be created or opened.
+    Open(String),
+    /// Anything else SQLite reported.
+    Sqlite(String),
+}
+
+pub type Result<T> = std::result::Result<T, DbError>;
+
+impl fmt::Display for DbError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DbError::Locked => write!(f, "the database is locked by another program"),
+            DbError::ReadOnly => write!(f, "the database is read-only"),
+            DbError::Corrupt => write!(f, "the database file is damaged"),
+            DbError::Open(why) => write!(f, "cannot open the database: {}", why),
+            DbError::Sqlite(why) => write!(f, "database error: {}", why),
+        }
+    }
+}
+
+impl std::error::Error for DbError {}
+
+impl From<rusqlite::Error> for DbError {
+    fn from(e: rusqlite::Error) -> Self {
+        match e.sqlite_error_code() {
+            Some(ErrorCode::DatabaseBusy | ErrorCode::DatabaseLocked) => DbError::Locked,
+            Some(ErrorCode::ReadOnly) => DbError::ReadOnly,
+            Some(ErrorCode::NotADatabase | ErrorCode::DatabaseCorrupt) => DbError::Corrupt,
+            Some(ErrorCode::CannotOpen) => DbError::Open(e.to_string()),
+            _ => DbError::Sqlite(e.to_string()),
+        }
+    }
+}
+
+impl From<io::Error> for DbError {
+    fn from(e: io::Error) -> Self {
+        match e.kind() {
+            io::ErrorKind::PermissionDenied => DbError::ReadOnly,
+            _ => DbError::Open(e.to_string()),
+        }
+    }
 }
 
-fn level_str(level: &Level) -> &'static str {
-    match level {
-        Level::Easy => "easy",
-        Level::Medium => "medium",
-        Level::Hard => "hard",
-        Level::Extreme => "extreme",
+// ── Location ──────────────────────────────────────────────────────────────────
+
+/// Where the scores are kept.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Location {
+    /// A database file, created with its directory if need be.
+    File(PathBuf),
+    /// A fresh database that is gone when the connection closes; for tests
+    /// and the simulator, or to play without keeping scores.
+    Memory,
+}
+
+impl Location {
+    /// `:memory:` is `Memory`, anything else a file.
+    pub fn from_path(path: PathBuf) -> Location {
+        if path.as_os_str() == MEMORY {
+            Location::Memory
+        } else {
+            Location::File(path)
+        }
+    }
+}
+
+impl fmt::Display for Location {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Location::File(path) => write!(f, "{}", path.display()),
+            Location::Memory => f.write_str("memory"),
+        }
+    }
+}
+
+/// The default database file: `shooting_game/shooting_game.db` in the
+/// user's data directory (`$XDG_DATA_HOME`, usually `~/.local/share`).
+pub fn default_path() -> PathBuf {
+    default_path_in(dirs::data_dir())
+}
+
+/// `default_path` for a given data directory.  Without one (no home
+/// directory) the file stays in the working directory.
+pub fn default_path_in(data_dir: Option<PathBuf>) -> PathBuf {
+    match data_dir {
+        Some(dir) => dir.join("shooting_game").join(DB_FILE),
+        None => PathBuf::from(DB_FILE),
+    }
+}
+
+/// Move the database an older version left at `legacy` to `target`, once:
+/// nothing happens if there is no such file or `target` already exists.
+/// Returns whether it was moved.
+pub fn adopt_legacy(legacy: &Path, target: &Path) -> io::Result<bool> {
+    if !legacy.is_file() || target.exists() {
+        return Ok(false);
+    }
+    if let Some(dir) = target.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    // A rename cannot cross file systems; copy the file over instead.
+    if fs::rename(legacy, target).is_err() {
+        fs::copy(legacy, target)?;
+        fs::remove_file(legacy)?;
     }
+    Ok(true)
 }
 
-pub fn open() -> Option<Connection> {
-    let conn = Connection::open(db_path()).ok()?;
-    init(&conn).ok()?;
-    Some(conn)
+/// Open the database at `location`, creating or upgrading its tables.
+pub fn open(location: &Location) -> Result<Connection> {
+    let conn = match location {
+        Location::File(path) => {
+            if let Some(dir) = path.parent().filter(|d| !d.as_os_str().is_empty()) {
+                fs::create_dir_all(dir)?;
+            }
+            Connection::open(path)?
+        }
+        Location::Memory => Connection::open_in_memory()?,
+    };
+    conn.busy_timeout(BUSY_TIMEOUT)?;
+    init(&conn)?;
+    Ok(conn)
 }
 
-fn init(conn: &Connection) -> Result<()> {
+// ── Schema ────────────────────────────────────────────────────────────────────
+
+/// Migrations in order: `MIGRATIONS[i]` takes the schema from version `i` to
+/// `i + 1`.  Append new ones at the end and never edit a released one.
+const MIGRATIONS: &[fn(&Connection) -> Result<()>] =
+    &[create_tables, add_waves, add_coop, add_stats];
+
+/// The schema version this build creates and reads, kept in
+/// `PRAGMA user_version`.
+pub const SCHEMA_VERSION: u32 = MIGRATIONS.len() as u32;
+
+/// Create the tables, or bring an older database's up to date.
+pub fn init(conn: &Connection) -> Result<()> {
+    migrate(conn)
+}
+
+/// The database's schema version; 0 for a new database, and for one made
+/// before the schema was versioned.
+pub fn schema_version(conn: &Connection) -> Result<u32> {
+    Ok(conn.query_row("PRAGMA user_version", [], |row| row.get(0))?)
+}
+
+/// Run every migration the database has not had yet, each in its own
+/// transaction with the version bump, so a failed step leaves the database at
+/// the version before it.  A database from a newer build is left alone;
+/// migrations only ever add, so everything this build reads is there.
+pub fn migrate(conn: &Connection) -> Result<()> {
+    let current = schema_version(conn)? as usize;
+    for (version, step) in MIGRATIONS.iter().enumerate().skip(current) {
+        let tx = conn.unchecked_transaction()?;
+        step(&tx)?;
+        tx.pragma_update(None, "user_version", version as u32 + 1)?;
+        tx.commit()?;
+    }
+    Ok(())
+}
+
+// Databases from before versioning are at user_version 0 whatever columns
+// they have, so every migration up to `add_coop` must be safe to run again.
+
+/// Version 1: the original tables.
+fn create_tables(conn: &Connection) -> Result<()> {
     conn.execute_batch(
         "CREATE TABLE IF NOT EXISTS top_scores (
             id          INTEGER PRIMARY KEY AUTOINCREMENT,
@@ -42,55 +218,475 @@ fn init(conn: &Connection) -> Result<()> {
             created_at  TEXT    NOT NULL DEFAULT (datetime('now')),
             deleted_at  TEXT
         );",
-    )
+    )?;
+    Ok(())
+}
+
+/// Version 2: the wave each run ended on; 0 = unknown for older rows.
+fn add_waves(conn: &Connection) -> Result<()> {
+    ensure_column(conn, "top_scores", "wave", "INTEGER NOT NULL DEFAULT 0")?;
+    ensure_column(conn, "scores", "wave", "INTEGER NOT NULL DEFAULT 0")
+}
+
+/// Version 3: co-op runs record both players; older rows are solo games.
+fn add_coop(conn: &Connection) -> Result<()> {
+    ensure_column(conn, "scores", "players", "INTEGER NOT NULL DEFAULT 1")?;
+    ensure_column(conn, "scores", "p2_points", "INTEGER NOT NULL DEFAULT 0")
+}
+
+/// Version 4: per-game statistics, one row per `scores` row saved with them.
+/// Games recorded before this have none.
+fn add_stats(conn: &Connection) -> Result<()> {
+    conn.execute_batch(
+        "CREATE TABLE score_stats (
+            score_id            INTEGER PRIMARY KEY REFERENCES scores(id),
+            shots_fired         INTEGER NOT NULL DEFAULT 0,
+            shots_hit           INTEGER NOT NULL DEFAULT 0,
+            kills_spacecraft    INTEGER NOT NULL DEFAULT 0,
+            kills_octopus       INTEGER NOT NULL DEFAULT 0,
+            kills_armored       INTEGER NOT NULL DEFAULT 0,
+            bosses              INTEGER NOT NULL DEFAULT 0,
+            caught_spread_shot  INTEGER NOT NULL DEFAULT 0,
+            caught_extra_life   INTEGER NOT NULL DEFAULT 0,
+            caught_rapid_fire   INTEGER NOT NULL DEFAULT 0,
+            caught_flame_burst  INTEGER NOT NULL DEFAULT 0,
+            caught_firebomb     INTEGER NOT NULL DEFAULT 0,
+            lives_lost          INTEGER NOT NULL DEFAULT 0,
+            frames              INTEGER NOT NULL DEFAULT 0,
+            peak_rate           INTEGER NOT NULL DEFAULT 0
+        );",
+    )?;
+    Ok(())
+}
+
+/// `ALTER TABLE … ADD COLUMN` unless `table` already has `column`.
+fn ensure_column(conn: &Connection, table: &str, column: &str, decl: &str) -> Result<()> {
+    let mut stmt = conn.prepare(&format!("PRAGMA table_info({})", table))?;
+    let exists = stmt
+        .query_map([], |row| row.get::<_, String>(1))?
+        .any(|name| name.as_deref() == Ok(column));
+    if !exists {
+        conn.execute_batch(&format!(
+            "ALTER TABLE {} ADD COLUMN {} {}",
+            table, column, decl
+        ))?;
+    }
+    Ok(())
 }
 
-/// Insert one completed game into `scores`.
-pub fn insert_score(conn: &Connection, username: &str, level: &Level, points: u32) -> Result<()> {
+// ── Scores ────────────────────────────────────────────────────────────────────
+
+/// Insert one completed game into `scores`, with the wave it ended on.
+///
+/// `difficulty` is the key from `profiles::score_key`: a level name for the
+/// built-in presets, `custom:<name>:<hash>` for custom profiles, and either
+/// of those behind `coop:` for co-op runs.  `p2_points` is player two's
+/// share of `points` in co-op, `None` solo.  Returns the new row's id.
+pub fn insert_score(
+    conn: &Connection,
+    username: &str,
+    difficulty: &str,
+    points: u32,
+    wave: u32,
+    p2_points: Option<u32>,
+) -> Result<i64> {
+    let players = if p2_points.is_some() { 2 } else { 1 };
+    conn.execute(
+        "INSERT INTO scores (username, difficulty, points, wave, players, p2_points)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+        params![
+            username,
+            difficulty,
+            points,
+            wave,
+            players,
+            p2_points.unwrap_or(0)
+        ],
+    )?;
+    Ok(conn.last_insert_rowid())
+}
+
+/// Store the statistics of the game recorded as `scores` row `score_id`,
+/// replacing any already there.
+pub fn save_stats(conn: &Connection, score_id: i64, stats: &RunStats) -> Result<()> {
+    let [spacecraft, octopus, armored] = stats.kills;
+    let [spread_shot, extra_life, rapid_fire, flame_burst, firebomb] = stats.power_ups;
     conn.execute(
-        "INSERT INTO scores (username, difficulty, points) VALUES (?1, ?2, ?3)",
-        params![username, level_str(level), points],
+        "INSERT OR REPLACE INTO score_stats (
+             score_id, shots_fired, shots_hit,
+             kills_spacecraft, kills_octopus, kills_armored, bosses,
+             caught_spread_shot, caught_extra_life, caught_rapid_fire,
+             caught_flame_burst, caught_firebomb,
+             lives_lost, frames, peak_rate)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15)",
+        params![
+            score_id,
+            stats.shots_fired,
+            stats.shots_hit,
+            spacecraft,
+            octopus,
+            armored,
+            stats.bosses,
+            spread_shot,
+            extra_life,
+            rapid_fire,
+            flame_burst,
+            firebomb,
+            stats.lives_lost,
+            stats.frames as i64,
+            stats.peak_rate,
+        ],
     )?;
     Ok(())
 }
 
+/// The statistics saved with `scores` row `score_id`; `None` for a game
+/// recorded without them.
+pub fn load_stats(conn: &Connection, score_id: i64) -> Result<Option<RunStats>> {
+    let stats = conn
+        .query_row(
+            "SELECT shots_fired, shots_hit,
+                    kills_spacecraft, kills_octopus, kills_armored, bosses,
+                    caught_spread_shot, caught_extra_life, caught_rapid_fire,
+                    caught_flame_burst, caught_firebomb,
+                    lives_lost, frames, peak_rate
+             FROM score_stats WHERE score_id = ?1",
+            params![score_id],
+            |row| {
+                Ok(RunStats {
+                    shots_fired: row.get(0)?,
+                    shots_hit: row.get(1)?,
+                    kills: [row.get(2)?, row.get(3)?, row.get(4)?],
+                    bosses: row.get(5)?,
+                    power_ups: [
+                        row.get(6)?,
+                        row.get(7)?,
+                        row.get(8)?,
+                        row.get(9)?,
+                        row.get(10)?,
+                    ],
+                    lives_lost: row.get(11)?,
+                    frames: row.get::<_, i64>(12)? as u64,
+                    peak_rate: row.get(13)?,
+                    window_points: 0,
+                })
+            },
+        )
+        .optional()?;
+    Ok(stats)
+}
+
 /// Upsert into `top_scores`: insert on first game, update only if the new score is higher.
+/// `wave` is kept from whichever run holds the top score.
 pub fn upsert_top_score(
     conn: &Connection,
     username: &str,
-    level: &Level,
+    difficulty: &str,
     points: u32,
+    wave: u32,
 ) -> Result<()> {
     conn.execute(
-        "INSERT INTO top_scores (username, difficulty, points) VALUES (?1, ?2, ?3)
+        "INSERT INTO top_scores (username, difficulty, points, wave) VALUES (?1, ?2, ?3, ?4)
          ON CONFLICT(username, difficulty) DO UPDATE SET
              points     = MAX(points, excluded.points),
+             wave       = CASE WHEN excluded.points > points
+                               THEN excluded.wave ELSE wave END,
              updated_at = CASE WHEN excluded.points > points
                                THEN datetime('now') ELSE updated_at END",
-        params![username, level_str(level), points],
+        params![username, difficulty, points, wave],
     )?;
     Ok(())
 }
 
 /// Best score for a specific difficulty (used for the in-game HUD).
-pub fn load_top_score(conn: &Connection, level: &Level) -> u32 {
-    conn.query_row(
+pub fn load_top_score(conn: &Connection, difficulty: &str) -> Result<u32> {
+    let best = conn.query_row(
         "SELECT COALESCE(MAX(points), 0) FROM top_scores
          WHERE difficulty = ?1 AND deleted_at IS NULL",
-        params![level_str(level)],
+        params![difficulty],
         |row| row.get::<_, i64>(0),
-    )
-    .map(|v| v as u32)
-    .unwrap_or(0)
+    )?;
+    Ok(best as u32)
 }
 
-/// Best score across all difficulties (used for the menu display).
-pub fn load_best_score(conn: &Connection) -> u32 {
-    conn.query_row(
-        "SELECT COALESCE(MAX(points), 0) FROM top_scores WHERE deleted_at IS NULL",
+/// Best score across the built-in difficulties (used for the menu display).
+/// Custom-profile and co-op runs are excluded so they can't top the official
+/// board.
+pub fn load_best_score(conn: &Connection) -> Result<u32> {
+    let best = conn.query_row(
+        "SELECT COALESCE(MAX(points), 0) FROM top_scores
+         WHERE deleted_at IS NULL AND difficulty NOT LIKE 'custom:%'
+           AND difficulty NOT LIKE 'coop:%'",
         [],
         |row| row.get::<_, i64>(0),
+    )?;
+    Ok(best as u32)
+}
+
+// ── Leaderboard ───────────────────────────────────────────────────────────────
+
+/// A player's best on one difficulty's leaderboard.
+#[derive(Clone, Debug, PartialEq)]
+pub struct RankedScore {
+    /// 1 for the top score; equal scores share a rank.
+    pub rank: u32,
+    pub username: String,
+    pub points: u32,
+    pub wave: u32,
+    /// The day the score was set, `YYYY-MM-DD`.
+    pub date: String,
+}
+
+/// One finished game from `scores`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct PastGame {
+    pub points: u32,
+    pub wave: u32,
+    /// Player two's share of `points` in a co-op game.
+    pub p2_points: Option<u32>,
+    /// When the game ended, `YYYY-MM-DD HH:MM` (UTC).
+    pub date: String,
+}
+
+/// `rows` from `offset` on, out of `total` rows in all.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Page<T> {
+    pub rows: Vec<T>,
+    pub offset: u32,
+    pub total: u32,
+}
+
+fn ranked_score(row: &rusqlite::Row) -> rusqlite::Result<RankedScore> {
+    Ok(RankedScore {
+        rank: row.get(0)?,
+        username: row.get(1)?,
+        points: row.get(2)?,
+        wave: row.get(3)?,
+        date: row.get(4)?,
+    })
+}
+
+/// Up to `limit` best scores on `difficulty` from the `offset`th on, best
+/// first.  Equal scores are listed in the order they were set.  Soft-deleted
+/// rows are left out and do not count towards anyone's rank.
+pub fn top_scores_page(
+    conn: &Connection,
+    difficulty: &str,
+    offset: u32,
+    limit: u32,
+) -> Result<Page<RankedScore>> {
+    let total = conn.query_row(
+        "SELECT COUNT(*) FROM top_scores WHERE difficulty = ?1 AND deleted_at IS NULL",
+        params![difficulty],
+        |row| row.get(0),
+    )?;
+    let mut stmt = conn.prepare(
+        "SELECT RANK() OVER (ORDER BY points DESC), username, points, wave, date(updated_at)
+         FROM top_scores
+         WHERE difficulty = ?1 AND deleted_at IS NULL
+         ORDER BY points DESC, updated_at, id
+         LIMIT ?2 OFFSET ?3",
+    )?;
+    let rows = stmt
+        .query_map(params![difficulty, limit, offset], ranked_score)?
+        .collect::<rusqlite::Result<_>>()?;
+    Ok(Page {
+        rows,
+        offset,
+        total,
+    })
+}
+
+/// `username`'s best on `difficulty` and its rank, or `None` if they have
+/// no (undeleted) score there.
+pub fn player_rank(
+    conn: &Connection,
+    difficulty: &str,
+    username: &str,
+) -> Result<Option<RankedScore>> {
+    conn.query_row(
+        "SELECT 1 + (SELECT COUNT(*) FROM top_scores AS better
+                     WHERE better.difficulty = mine.difficulty
+                       AND better.deleted_at IS NULL
+                       AND better.points > mine.points),
+                username, points, wave, date(updated_at)
+         FROM top_scores AS mine
+         WHERE difficulty = ?1 AND username = ?2 AND deleted_at IS NULL",
+        params![difficulty, username],
+        ranked_score,
     )
-    .map(|v| v as u32)
-    .unwrap_or(0)
+    .optional()
+    .map_err(DbError::from)
+}
+
+/// Up to `limit` of `username`'s games on `difficulty` from the `offset`th
+/// on, most recent first, leaving out soft-deleted ones.
+pub fn recent_games_page(
+    conn: &Connection,
+    difficulty: &str,
+    username: &str,
+    offset: u32,
+    limit: u32,
+) -> Result<Page<PastGame>> {
+    let total = conn.query_row(
+        "SELECT COUNT(*) FROM scores
+         WHERE difficulty = ?1 AND username = ?2 AND deleted_at IS NULL",
+        params![difficulty, username],
+        |row| row.get(0),
+    )?;
+    let mut stmt = conn.prepare(
+        "SELECT points, wave, players, p2_points, strftime('%Y-%m-%d %H:%M', created_at)
+         FROM scores
+         WHERE difficulty = ?1 AND username = ?2 AND deleted_at IS NULL
+         ORDER BY created_at DESC, id DESC
+         LIMIT ?3 OFFSET ?4",
+    )?;
+    let rows = stmt
+        .query_map(params![difficulty, username, limit, offset], |row| {
+            let players: u32 = row.get(2)?;
+            Ok(PastGame {
+                points: row.get(0)?,
+                wave: row.get(1)?,
+                p2_points: if players > 1 { Some(row.get(3)?) } else { None },
+                date: row.get(4)?,
+            })
+        })?
+        .collect::<rusqlite::Result<_>>()?;
+    Ok(Page {
+        rows,
+        offset,
+        total,
+    })
+}
+
+// ── Saving with retries ───────────────────────────────────────────────────────
+
+/// One game's result, waiting to be written.
+#[derive(Clone, Debug, PartialEq)]
+pub struct GameResult {
+    pub username: String,
+    /// The score key, as for `insert_score`.
+    pub difficulty: String,
+    pub points: u32,
+    pub wave: u32,
+    /// Player two's share of `points` in co-op.
+    pub p2_points: Option<u32>,
+    /// The game reached game over, so it goes into `scores` too; one left
+    /// early only counts towards `top_scores`.
+    pub finished: bool,
+    /// Saved with the `scores` row of a finished game.
+    pub stats: RunStats,
+}
+
+/// Write one game's result: its `scores` row with its statistics and its
+/// `top_scores` upsert together or not at all, so that retrying never
+/// records a game twice.
+pub fn save_result(conn: &Connection, result: &GameResult) -> Result<()> {
+    let tx = conn.unchecked_transaction()?;
+    if result.finished {
+        let id = insert_score(
+            &tx,
+            &result.username,
+            &result.difficulty,
+            result.points,
+            result.wave,
+            result.p2_points,
+        )?;
+        save_stats(&tx, id, &result.stats)?;
+    }
+    upsert_top_score(
+        &tx,
+        &result.username,
+        &result.difficulty,
+        result.points,
+        result.wave,
+    )?;
+    tx.commit()?;
+    Ok(())
+}
+
+/// The scores database for a session, which keeps trying: a database that
+/// could not be opened is opened again on `retry`, and results that could
+/// not be written wait in a queue until they can be.  `error` is the latest
+/// problem, for the menu to show.
+pub struct ScoreStore {
+    location: Location,
+    conn: Option<Connection>,
+    queue: Vec<GameResult>,
+    error: Option<DbError>,
+}
+
+impl ScoreStore {
+    pub fn open(location: Location) -> ScoreStore {
+        let mut store = ScoreStore {
+            location,
+            conn: None,
+            queue: Vec::new(),
+            error: None,
+        };
+        store.retry();
+        store
+    }
+
+    /// The connection, while the database is open.
+    pub fn conn(&self) -> Option<&Connection> {
+        self.conn.as_ref()
+    }
+
+    pub fn error(&self) -> Option<&DbError> {
+        self.error.as_ref()
+    }
+
+    /// Results not written yet, oldest first.
+    pub fn pending(&self) -> &[GameResult] {
+        &self.queue
+    }
+
+    /// Run `query`, keeping its error for the menu.  `None` if it failed or
+    /// the database is not open.
+    pub fn read<T>(&mut self, query: impl FnOnce(&Connection) -> Result<T>) -> Option<T> {
+        let conn = self.conn.as_ref()?;
+        match query(conn) {
+            Ok(value) => {
+                if self.queue.is_empty() {
+                    self.error = None;
+                }
+                Some(value)
+            }
+            Err(e) => {
+                self.error = Some(e);
+                None
+            }
+        }
+    }
+
+    /// Queue `result` and write all that is queued.
+    pub fn save(&mut self, result: GameResult) {
+        self.queue.push(result);
+        self.retry();
+    }
+
+    /// Open the database if it is not open, then write the queued results
+    /// oldest first, stopping at the first that fails.  Once everything is
+    /// written the error is cleared.
+    pub fn retry(&mut self) {
+        if self.conn.is_none() {
+            match open(&self.location) {
+                Ok(conn) => self.conn = Some(conn),
+                Err(e) => {
+                    self.error = Some(e);
+                    return;
+                }
+            }
+        }
+        let Some(conn) = &self.conn else { return };
+        while !self.queue.is_empty() {
+            if let Err(e) = save_result(conn, &self.queue[0]) {
+                self.error = Some(e);
+                return;
+            }
+            self.queue.remove(0);
+        }
+        self.error = None;
+    }
 }
@@ -1,23 +1,52 @@
-use crate::compute::{move_player_left, move_player_right, player_shoot};
-use crate::entities::{BulletOwner, EntireGameStateInfo};
+use crate::compute::apply_action;
+use crate::entities::{
+    BulletOwner, Enemy, EnemyBehavior, EntireGameStateInfo, PlayerAction, BOSS_HALF_WIDTH,
+};
 
 pub fn update_demo(state: &EntireGameStateInfo) -> EntireGameStateInfo {
-    let mut current_state = state.clone();
+    demo_actions(state)
+        .iter()
+        .fold(state.clone(), |s, action| apply_action(&s, action))
+}
+
+/// The bot's inputs for this frame, in the order they should be applied.
+/// Every decision is made from `state` as it stands at the start of the frame.
+pub fn demo_actions(state: &EntireGameStateInfo) -> Vec<PlayerAction> {
+    let mut actions = Vec::new();
 
     // 1. Simple heuristic: find the lowest enemy and align with it.
-    // If no enemies, stay put or move to center.
-    let target_x = if let Some(target) = state.enemies.iter().max_by_key(|e| e.y) {
+    // Divers home in on the player, so a diving one comes first.
+    // Otherwise go for the boss, then bonus items, then the center.
+    let diving = |e: &&Enemy| matches!(e.behavior, EnemyBehavior::Diver { dive } if dive > 0);
+    let target_x = if let Some(target) = state
+        .enemies
+        .iter()
+        .filter(diving)
+        .max_by_key(|e| e.y)
+        .or_else(|| state.enemies.iter().max_by_key(|e| e.y))
+    {
         target.x
+    } else if let Some(boss) = &state.boss {
+        boss.x
     } else if let Some(bonus) = state.bonus_items.iter().max_by_key(|b| b.y) {
         bonus.x
     } else {
         (state.width / 2) as i32
     };
 
-    if current_state.player.x < target_x {
-        current_state = move_player_right(&current_state);
-    } else if current_state.player.x > target_x {
-        current_state = move_player_left(&current_state);
+    // Boss shots come in fast and at angles, so while one is close the bot
+    // stops chasing its target and sidesteps two columns instead of one.
+    let dangerous_shot = state.enemy_shots.iter().find(|s| {
+        let (sx, sy) = (s.x.round() as i32, s.y.round() as i32);
+        (sx - state.player.x).abs() <= 2 && sy < state.player.y && sy > state.player.y - 6
+    });
+
+    if dangerous_shot.is_none() {
+        if state.player.x < target_x {
+            actions.push(PlayerAction::MoveRight(1));
+        } else if state.player.x > target_x {
+            actions.push(PlayerAction::MoveLeft(1));
+        }
     }
 
     // 2. Simple heuristic: Avoid enemy bullets that are directly above.
@@ -31,9 +60,19 @@ pub fn update_demo(state: &EntireGameStateInfo) -> EntireGameStateInfo {
     if let Some(bullet) = dangerous_bullet {
         // Try to dodge
         if state.player.x <= bullet.x && state.player.x > 1 {
-            current_state = move_player_left(&current_state);
+            actions.push(PlayerAction::MoveLeft(1));
         } else if state.player.x >= bullet.x && state.player.x < (state.width as i32 - 2) {
-            current_state = move_player_right(&current_state);
+            actions.push(PlayerAction::MoveRight(1));
+        }
+    } else if let Some(shot) = dangerous_shot {
+        // Step away from the side the shot is drifting towards.
+        let drifting_left = shot.vx < 0.0;
+        let room_left = state.player.x > 2;
+        let room_right = state.player.x < state.width as i32 - 3;
+        if (drifting_left || !room_left) && room_right {
+            actions.push(PlayerAction::MoveRight(2));
+        } else if room_left {
+            actions.push(PlayerAction::MoveLeft(2));
         }
     }
 
@@ -41,13 +80,17 @@ pub fn update_demo(state: &EntireGameStateInfo) -> EntireGameStateInfo {
     let enemy_in_front = state
         .enemies
         .iter()
-        .any(|e| (e.x - state.player.x).abs() <= 2);
+        .any(|e| (e.x - state.player.x).abs() <= 2)
+        || state
+            .boss
+            .as_ref()
+            .is_some_and(|b| (b.x - state.player.x).abs() <= BOSS_HALF_WIDTH);
     #[allow(clippy::manual_is_multiple_of)]
     let should_shoot = enemy_in_front || state.frame % 5 == 0;
 
     if should_shoot {
-        current_state = player_shoot(&current_state);
+        actions.push(PlayerAction::Shoot);
     }
 
-    current_state
+    actions
 }
@@ -1,20 +1,21 @@
-//! Rendering layer — all terminal I/O lives here.
+//! Rendering layer — translates game state into a frame.
 //!
-//! Each function receives a mutable writer and an immutable view of the
-//! game state.  No game logic is performed; this module only translates
-//! state into terminal commands.
+//! Each `draw_*` function receives the frame's back buffer and an immutable
+//! view of the game state, and writes cells into the buffer.  No game logic
+//! is performed here, and no I/O either: `render` hands the finished buffer
+//! to a [`Renderer`], which shows it on the terminal or as plain text.
 
-use std::io::Write;
+use crate::bindings::{Action, Bindings};
+use crate::buffer::Buffer;
+use crate::compute::player_score;
+use crate::renderer::Renderer;
 
 use crate::entities::{
-    BonusItem, BonusKind, Bullet, BulletOwner, Enemy, EnemyKind, EntireGameStateInfo, Explosion,
-    FirebombProj, FlameBullet, GameStatus, Level,
-};
-use crossterm::{
-    cursor,
-    style::{self, Color, Print},
-    terminal, QueueableCommand,
+    BonusItem, BonusKind, Boss, Bullet, BulletOwner, Enemy, EnemyKind, EnemyShot,
+    EntireGameStateInfo, Explosion, FirebombProj, FlameBullet, GameStatus, Level, PauseItem,
+    Player, Player2, PlayerId, RunStats, BOSS_HALF_WIDTH, MIN_HEIGHT, MIN_WIDTH, SCORE_RATE_WINDOW,
 };
+use crossterm::style::Color;
 
 // ── Colour palette ────────────────────────────────────────────────────────────
 
@@ -22,11 +23,20 @@ const C_BORDER: Color = Color::DarkBlue;
 const C_HUD_SCORE: Color = Color::Yellow;
 const C_HUD_LIVES: Color = Color::Red;
 const C_PLAYER: Color = Color::White;
+const C_PLAYER2: Color = Color::Cyan;
 const C_ENEMY_SPACECRAFT: Color = Color::Green;
 const C_ENEMY_OCTOPUS: Color = Color::Red;
+const C_ENEMY_ARMORED: Color = Color::Grey;
+const C_ENEMY_DAMAGED: Color = Color::Yellow;
+const C_ENEMY_CRITICAL: Color = Color::DarkRed;
+const C_BOSS: Color = Color::Magenta;
+const C_BOSS_HURT: Color = Color::Yellow;
+const C_BOSS_CRITICAL: Color = Color::Red;
 const C_BULLET_PLAYER: Color = Color::Cyan;
+const C_BULLET_PLAYER2: Color = Color::Green;
 const C_BULLET_ENEMY: Color = Color::Magenta;
 const C_HINT: Color = Color::DarkGrey;
+const C_STATS: Color = Color::Grey;
 const C_BONUS_SPREAD: Color = Color::Yellow;
 const C_BONUS_LIFE: Color = Color::Magenta;
 const C_BONUS_RAPID: Color = Color::Cyan;
@@ -49,164 +59,190 @@ const C_EXPLOSION: Color = Color::Rgb {
 };
 const C_POWERUP_ACTIVE: Color = Color::Yellow;
 
+/// Half-period of the invulnerable player's blink, in frames.
+const BLINK_FRAMES: u32 = 4;
+
 // ── Public entry point ────────────────────────────────────────────────────────
 
-/// ## Why `full_redraw`?
-///
-/// `terminal::Clear(All)` repaints the entire terminal viewport every frame,
-/// causing visible flicker and forcing the GPU to process every cell — even
-/// the border and controls hint that never change.
-///
-/// On the **first frame** (`full_redraw = true`) we still do a full clear so
-/// the screen starts clean, then draw the border and controls hint.
-///
-/// On every **subsequent frame** (`full_redraw = false`) we only erase:
-/// - Row 0 (HUD) — score, timer, and lives change every frame.
-/// - Rows 2 → h−3 (play area) — where all moving entities live.
+/// Draw `state` and show it through `renderer`.  The on-screen hints name
+/// the keys in `bindings`.
 ///
-/// The border (rows 1, h−2) and controls hint (row h−1) are **left in place**:
-/// game entities are clamped inside the play area so they can never overwrite
-/// those rows.  This cuts the terminal's per-frame work from O(viewport) to
-/// O(play area), eliminating flicker on static regions entirely.
-pub fn render<W: Write>(
-    out: &mut W,
+/// `full_redraw` tells the renderer to assume nothing about what it showed
+/// before — needed on the first frame and whenever something else (a
+/// resize, another screen) has touched the terminal since.
+pub fn render<R: Renderer + ?Sized>(
+    renderer: &mut R,
     state: &EntireGameStateInfo,
+    bindings: &Bindings,
     full_redraw: bool,
 ) -> std::io::Result<()> {
-    let w = state.width;
-    let h = state.height;
+    renderer.present(draw(state, bindings), full_redraw)
+}
 
-    if full_redraw {
-        // First frame: clear everything and paint the static chrome.
-        out.queue(terminal::Clear(terminal::ClearType::All))?;
-        draw_border(out, state)?;
-        draw_controls_hint(out, state)?;
-    } else {
-        // Subsequent frames: erase only the two dynamic regions.
-
-        // Row 0 — HUD (score, power-up countdown, lives all change each frame)
-        out.queue(cursor::MoveTo(0, 0))?;
-        out.queue(Print(" ".repeat(w as usize)))?;
-
-        // Rows 2 … h-3 — clear play area and redraw side walls each frame.
-        // Redrawing walls prevents ghost sprites when entities move through
-        // col 0 or col w-1 (outside the cols-1..w-2 blank region).
-        let blank = " ".repeat(w.saturating_sub(2) as usize);
-        for row in 2u16..h.saturating_sub(2) {
-            out.queue(style::SetForegroundColor(C_BORDER))?;
-            out.queue(cursor::MoveTo(0, row))?;
-            out.queue(Print("│"))?;
-            out.queue(style::ResetColor)?;
-            out.queue(cursor::MoveTo(1, row))?;
-            out.queue(Print(&blank))?;
-            out.queue(style::SetForegroundColor(C_BORDER))?;
-            out.queue(cursor::MoveTo(w.saturating_sub(1), row))?;
-            out.queue(Print("│"))?;
-        }
-    }
+/// Draw one frame of `state` into a buffer the size of the playfield.
+pub fn draw(state: &EntireGameStateInfo, bindings: &Bindings) -> Buffer {
+    let mut buf = Buffer::new(state.width, state.height);
+    let h = state.height;
 
-    // Always repaint dynamic content.
-    draw_hud(out, state)?;
+    draw_border(&mut buf, state);
+    draw_controls_hint(&mut buf, state, bindings);
+    draw_hud(&mut buf, state);
 
     for enemy in &state.enemies {
-        draw_enemy(out, enemy, h as i32 - 2)?;
+        draw_enemy(&mut buf, enemy, h as i32 - 2);
+    }
+    if let Some(boss) = &state.boss {
+        draw_boss(&mut buf, boss);
     }
     for bonus in &state.bonus_items {
-        draw_bonus_item(out, bonus)?;
+        draw_bonus_item(&mut buf, bonus);
     }
     for exp in &state.explosions {
-        draw_explosion(out, exp)?;
+        draw_explosion(&mut buf, exp);
     }
     for fb in &state.flame_bullets {
-        draw_flame_bullet(out, fb)?;
+        draw_flame_bullet(&mut buf, fb);
     }
     for bomb in &state.firebombs {
-        draw_firebomb(out, bomb)?;
+        draw_firebomb(&mut buf, bomb);
     }
     for bullet in &state.bullets {
-        draw_bullet(out, bullet)?;
+        draw_bullet(&mut buf, bullet);
     }
-    draw_player(out, state)?;
+    for shot in &state.enemy_shots {
+        draw_enemy_shot(&mut buf, shot);
+    }
+    draw_players(&mut buf, state);
 
     if let Some((msg, _)) = &state.cheer_msg {
-        draw_cheer(out, state, msg)?;
+        draw_cheer(&mut buf, state, msg);
+    }
+
+    if let GameStatus::Paused(selected) = &state.status {
+        draw_pause_menu(&mut buf, state, selected, bindings);
     }
 
     if state.status == GameStatus::GameOver {
-        draw_game_over(out, state)?;
+        draw_game_over(&mut buf, state, bindings);
     }
 
     if state.debug_mode {
-        draw_debug_overlay(out, state)?;
+        draw_debug_overlay(&mut buf, state);
     }
 
-    // Park cursor in a harmless spot and flush
-    out.queue(style::ResetColor)?;
-    out.queue(cursor::MoveTo(0, h.saturating_sub(1)))?;
-    out.flush()?;
-    Ok(())
+    buf
+}
+
+/// Shown in place of the game while the terminal is smaller than
+/// `MIN_WIDTH` × `MIN_HEIGHT`.  `width` and `height` are the terminal's
+/// actual size.
+pub fn render_too_small<R: Renderer + ?Sized>(
+    renderer: &mut R,
+    width: u16,
+    height: u16,
+) -> std::io::Result<()> {
+    renderer.present(draw_too_small(width, height), false)
+}
+
+/// The "terminal too small" notice, centred in a `width` × `height` buffer.
+pub fn draw_too_small(width: u16, height: u16) -> Buffer {
+    let lines = [
+        ("Terminal too small".to_string(), Color::Red),
+        (
+            format!("{}×{}, need {}×{}", width, height, MIN_WIDTH, MIN_HEIGHT),
+            Color::White,
+        ),
+        ("Enlarge the window to continue".to_string(), C_HINT),
+    ];
+
+    let mut buf = Buffer::new(width, height);
+    let start_row = (height / 2).saturating_sub(lines.len() as u16 / 2);
+    for (i, (line, color)) in lines.iter().enumerate() {
+        let col = (width / 2).saturating_sub(line.chars().count() as u16 / 2);
+        buf.print(col, start_row + i as u16, line, *color);
+    }
+    buf
 }
 
 // ── Border ────────────────────────────────────────────────────────────────────
 
-fn draw_border<W: Write>(out: &mut W, state: &EntireGameStateInfo) -> std::io::Result<()> {
+fn draw_border(buf: &mut Buffer, state: &EntireGameStateInfo) {
     let w = state.width as usize;
     let h = state.height;
 
-    out.queue(style::SetForegroundColor(C_BORDER))?;
-
     // Row 1 — top bar
-    out.queue(cursor::MoveTo(0, 1))?;
-    out.queue(Print(format!("┌{}┐", "─".repeat(w.saturating_sub(2)))))?;
+    buf.print(
+        0,
+        1,
+        &format!("┌{}┐", "─".repeat(w.saturating_sub(2))),
+        C_BORDER,
+    );
 
     // Row h-2 — bottom bar
-    out.queue(cursor::MoveTo(0, h.saturating_sub(2)))?;
-    out.queue(Print(format!("└{}┘", "─".repeat(w.saturating_sub(2)))))?;
+    buf.print(
+        0,
+        h.saturating_sub(2),
+        &format!("└{}┘", "─".repeat(w.saturating_sub(2))),
+        C_BORDER,
+    );
 
     // Side walls
     for row in 2..h.saturating_sub(2) {
-        out.queue(cursor::MoveTo(0, row))?;
-        out.queue(Print("│"))?;
-        out.queue(cursor::MoveTo(state.width.saturating_sub(1), row))?;
-        out.queue(Print("│"))?;
+        buf.print(0, row, "│", C_BORDER);
+        buf.print(state.width.saturating_sub(1), row, "│", C_BORDER);
     }
-
-    Ok(())
 }
 
 // ── HUD (row 0) ───────────────────────────────────────────────────────────────
 
-fn draw_hud<W: Write>(out: &mut W, state: &EntireGameStateInfo) -> std::io::Result<()> {
+fn draw_hud(buf: &mut Buffer, state: &EntireGameStateInfo) {
     // Score and high score — left
-    out.queue(cursor::MoveTo(1, 0))?;
-    out.queue(style::SetForegroundColor(C_HUD_SCORE))?;
-    if state.high_score > 0 {
-        out.queue(Print(format!(
-            "Score:{:>6}  Hi:{:>6}",
-            state.score, state.high_score
-        )))?;
+    let score = if state.high_score > 0 {
+        format!("Score:{:>6}  Hi:{:>6}", state.score, state.high_score)
     } else {
-        out.queue(Print(format!("Score:{:>6}", state.score)))?;
-    }
-
-    // Level — centre
-    let level_str = match state.level {
-        Level::Easy => "[ EASY ]",
-        Level::Medium => "[ MEDIUM ]",
-        Level::Hard => "[ HARD ]",
-        Level::Extreme => "[ EXTREME ]",
+        format!("Score:{:>6}", state.score)
     };
-    let level_color = match state.level {
-        Level::Easy => Color::Green,
-        Level::Medium => Color::Yellow,
-        Level::Hard => Color::Red,
-        Level::Extreme => Color::Magenta,
+    buf.print(1, 0, &score, C_HUD_SCORE);
+    let left_end = 1 + score.chars().count() as u16;
+
+    let right_start = match &state.player2 {
+        Some(p2) => draw_coop_status(buf, state, p2),
+        None => draw_solo_status(buf, state),
     };
-    let lx = (state.width / 2).saturating_sub(level_str.len() as u16 / 2);
-    out.queue(cursor::MoveTo(lx, 0))?;
-    out.queue(style::SetForegroundColor(level_color))?;
-    out.queue(Print(level_str))?;
 
+    // Boss health bar replaces the level label while a boss is up
+    if let Some(boss) = &state.boss {
+        draw_boss_bar(buf, state, boss, left_end, right_start);
+    } else {
+        draw_level_label(buf, state, left_end, right_start);
+    }
+}
+
+/// Print the first of `candidates` that fits centred on the HUD row between
+/// the score (ending at `left_end`) and the right-hand block (starting at
+/// `right_start`), with a blank column on either side.  Prints nothing if
+/// none fits.
+fn draw_hud_centre(
+    buf: &mut Buffer,
+    state: &EntireGameStateInfo,
+    candidates: &[String],
+    color: Color,
+    left_end: u16,
+    right_start: u16,
+) {
+    for text in candidates {
+        let len = text.chars().count() as u16;
+        let x = (state.width / 2).saturating_sub(len / 2);
+        if x > left_end && x + len < right_start {
+            buf.print(x, 0, text, color);
+            return;
+        }
+    }
+}
+
+/// Power-up, bullet slots and lives, right-aligned on the HUD row.  Returns
+/// the column the block starts at.
+fn draw_solo_status(buf: &mut Buffer, state: &EntireGameStateInfo) -> u16 {
     // Active power-up indicator + lives — right side
     // Build the right-side string, right-aligned
     let power_tag = match &state.active_power_up {
@@ -221,7 +257,7 @@ fn draw_hud<W: Write>(out: &mut W, state: &EntireGameStateInfo) -> std::io::Resu
     let active_bullets = state
         .bullets
         .iter()
-        .filter(|b| b.owner == BulletOwner::Player)
+        .filter(|b| b.owner == BulletOwner::Player(PlayerId::One))
         .count();
     let bullet_slots: String = (0..bullet_cap)
         .map(|i| if i < active_bullets { '●' } else { '○' })
@@ -232,15 +268,15 @@ fn draw_hud<W: Write>(out: &mut W, state: &EntireGameStateInfo) -> std::io::Resu
     let lives_str = format!("Lives:{}", hearts);
     let right_str = format!("{}{}{}", power_tag, bullet_str, lives_str);
 
-    let rx = state
+    let start = state
         .width
         .saturating_sub(right_str.chars().count() as u16 + 1);
-    out.queue(cursor::MoveTo(rx, 0))?;
+    let mut rx = start;
 
     // Colour the power-up tag separately if present
     if !power_tag.is_empty() {
-        out.queue(style::SetForegroundColor(C_POWERUP_ACTIVE))?;
-        out.queue(Print(&power_tag))?;
+        buf.print(rx, 0, &power_tag, C_POWERUP_ACTIVE);
+        rx += power_tag.chars().count() as u16;
     }
     // Bullet slots: cyan when slots available, red when full
     let slot_color = if active_bullets >= bullet_cap {
@@ -248,35 +284,142 @@ fn draw_hud<W: Write>(out: &mut W, state: &EntireGameStateInfo) -> std::io::Resu
     } else {
         Color::Cyan
     };
-    out.queue(style::SetForegroundColor(slot_color))?;
-    out.queue(Print(&bullet_str))?;
-    out.queue(style::SetForegroundColor(C_HUD_LIVES))?;
-    out.queue(Print(&lives_str))?;
+    buf.print(rx, 0, &bullet_str, slot_color);
+    rx += bullet_str.chars().count() as u16;
+    buf.print(rx, 0, &lives_str, C_HUD_LIVES);
+    start
+}
 
-    Ok(())
+/// Co-op HUD, right side: each player's power-up and lives, e.g.
+/// `P1 [★12s] ♥♥♥  P2 ♥♥`.  A player who is out shows `OUT`.  Returns the
+/// column the block starts at.
+fn draw_coop_status(buf: &mut Buffer, state: &EntireGameStateInfo, p2: &Player2) -> u16 {
+    let status = |label: &str, ship: &Player, power_up: &Option<(BonusKind, u32)>| {
+        let tag = match power_up {
+            Some((BonusKind::SpreadShot, f)) => format!("[★{}s] ", f / 30 + 1),
+            Some((BonusKind::RapidFire, f)) => format!("[!{}s] ", f / 30 + 1),
+            Some((BonusKind::FlameBurst, f)) => format!("[~{}s] ", f / 30 + 1),
+            Some((BonusKind::Firebomb, f)) => format!("[o{}s] ", f / 30 + 1),
+            _ => String::new(),
+        };
+        let lives = if ship.lives == 0 {
+            "OUT".to_string()
+        } else {
+            "♥".repeat(ship.lives as usize)
+        };
+        (format!("{} ", label), tag, lives)
+    };
+    let parts = [
+        status("P1", &state.player, &state.active_power_up),
+        status("P2", &p2.ship, &p2.active_power_up),
+    ];
+    let len: usize = parts
+        .iter()
+        .map(|(l, t, h)| l.chars().count() + t.chars().count() + h.chars().count())
+        .sum::<usize>()
+        + 2;
+    let start = state.width.saturating_sub(len as u16 + 1);
+    let mut rx = start;
+    for (i, ((label, tag, lives), color)) in parts.iter().zip([C_PLAYER, C_PLAYER2]).enumerate() {
+        if i > 0 {
+            rx += 2;
+        }
+        for (text, color) in [
+            (label, color),
+            (tag, C_POWERUP_ACTIVE),
+            (lives, C_HUD_LIVES),
+        ] {
+            buf.print(rx, 0, text, color);
+            rx += text.chars().count() as u16;
+        }
+    }
+    start
+}
+
+/// Level (or custom profile name) and wave, centred on the HUD row.  Only
+/// the wave is shown when the name would run into the rest of the HUD.
+fn draw_level_label(
+    buf: &mut Buffer,
+    state: &EntireGameStateInfo,
+    left_end: u16,
+    right_start: u16,
+) {
+    let level_name = if state.difficulty.builtin {
+        state.level.as_str().to_uppercase()
+    } else {
+        state.difficulty.name.to_uppercase()
+    };
+    let labels = [
+        format!("[ {} · W{} ]", level_name, state.wave.number),
+        format!("[ W{} ]", state.wave.number),
+    ];
+    let level_color = match state.level {
+        _ if !state.difficulty.builtin => Color::Cyan,
+        Level::Easy => Color::Green,
+        Level::Medium => Color::Yellow,
+        Level::Hard => Color::Red,
+        Level::Extreme => Color::Magenta,
+    };
+    draw_hud_centre(buf, state, &labels, level_color, left_end, right_start);
+}
+
+/// `BOSS [██████░░░░]` centred on the HUD row, coloured by remaining health.
+fn draw_boss_bar(
+    buf: &mut Buffer,
+    state: &EntireGameStateInfo,
+    boss: &Boss,
+    left_end: u16,
+    right_start: u16,
+) {
+    const CELLS: u32 = 10;
+    let filled = (boss.hp * CELLS).div_ceil(boss.max_hp.max(1)) as usize;
+    let bar = format!(
+        "BOSS [{}{}]",
+        "█".repeat(filled),
+        "░".repeat(CELLS as usize - filled)
+    );
+    draw_hud_centre(buf, state, &[bar], boss_color(boss), left_end, right_start);
 }
 
 // ── Entities ──────────────────────────────────────────────────────────────────
 
-fn draw_player<W: Write>(out: &mut W, state: &EntireGameStateInfo) -> std::io::Result<()> {
-    let p = &state.player;
-    let flashing = state.muzzle_flash > 0;
+/// Both ships in co-op; a player who is out of lives leaves the field.
+fn draw_players(buf: &mut Buffer, state: &EntireGameStateInfo) {
+    let Some(p2) = &state.player2 else {
+        draw_ship(buf, state, &state.player, C_PLAYER, state.muzzle_flash > 0);
+        return;
+    };
+    if state.player.lives > 0 {
+        draw_ship(buf, state, &state.player, C_PLAYER, state.muzzle_flash > 0);
+    }
+    if p2.ship.lives > 0 {
+        draw_ship(buf, state, &p2.ship, C_PLAYER2, false);
+    }
+}
+
+fn draw_ship(
+    buf: &mut Buffer,
+    state: &EntireGameStateInfo,
+    p: &Player,
+    color: Color,
+    flashing: bool,
+) {
+    // Blink while invulnerable: hidden for BLINK_FRAMES, shown for BLINK_FRAMES.
+    if (p.invulnerable / BLINK_FRAMES) % 2 == 1 {
+        return;
+    }
 
     // Muzzle flash: bright burst one row above the tip
     if flashing {
         let flash_y = p.y - 1;
         if flash_y >= 2 {
-            out.queue(style::SetForegroundColor(Color::Yellow))?;
-            out.queue(cursor::MoveTo(p.x as u16, flash_y as u16))?;
-            out.queue(Print("*"))?;
+            buf.print(p.x as u16, flash_y as u16, "*", Color::Yellow);
         }
     }
 
     // Tip — yellow while firing, white otherwise
-    let tip_color = if flashing { Color::Yellow } else { C_PLAYER };
-    out.queue(style::SetForegroundColor(tip_color))?;
-    out.queue(cursor::MoveTo(p.x as u16, p.y as u16))?;
-    out.queue(Print("▲"))?;
+    let tip_color = if flashing { Color::Yellow } else { color };
+    buf.print(p.x as u16, p.y as u16, "▲", tip_color);
 
     // Fuselage — draw each column individually so the sprite clips at walls
     // instead of shifting. At p.x=1 the left "/" is behind the wall and skipped;
@@ -284,67 +427,97 @@ fn draw_player<W: Write>(out: &mut W, state: &EntireGameStateInfo) -> std::io::R
     // aligned with the tip.
     let wing_y = p.y + 1;
     if wing_y < state.height as i32 - 2 {
-        out.queue(style::SetForegroundColor(C_PLAYER))?;
         for (ch, cx) in [("/", p.x - 1), ("█", p.x), ("\\", p.x + 1)] {
             if cx >= 1 && cx < state.width as i32 - 1 {
-                out.queue(cursor::MoveTo(cx as u16, wing_y as u16))?;
-                out.queue(Print(ch))?;
+                buf.print(cx as u16, wing_y as u16, ch, color);
             }
         }
     }
-
-    Ok(())
 }
 
-fn draw_enemy<W: Write>(
-    out: &mut W,
+fn draw_enemy(
+    buf: &mut Buffer,
     enemy: &Enemy,
     play_bottom: i32, // bottom border row (= height - 2)
-) -> std::io::Result<()> {
+) {
     let lx = (enemy.x - 1).max(0) as u16;
-    match enemy.kind {
-        EnemyKind::Spacecraft => {
-            // Enhanced sprite:
-            //   «▼»    ← swept-back wings
-            //   ╚═╝    ← engine block
-            out.queue(style::SetForegroundColor(C_ENEMY_SPACECRAFT))?;
-            out.queue(cursor::MoveTo(lx, enemy.y as u16))?;
-            out.queue(Print("«▼»"))?;
-            if enemy.y + 1 < play_bottom {
-                out.queue(cursor::MoveTo(lx, (enemy.y + 1) as u16))?;
-                out.queue(Print("╚═╝"))?;
-            }
-        }
-        EnemyKind::Octopus => {
-            // Enhanced sprite:
-            //   (◎)    ← glowing eye
-            //   ╰─╯    ← tentacle arc
-            out.queue(style::SetForegroundColor(C_ENEMY_OCTOPUS))?;
-            out.queue(cursor::MoveTo(lx, enemy.y as u16))?;
-            out.queue(Print("(◎)"))?;
-            if enemy.y + 1 < play_bottom {
-                out.queue(cursor::MoveTo(lx, (enemy.y + 1) as u16))?;
-                out.queue(Print("╰─╯"))?;
-            }
-        }
+    let (top, bottom, base_color) = enemy_sprite(&enemy.kind);
+    let color = enemy_color(enemy, base_color);
+    buf.print(lx, enemy.y as u16, top, color);
+    if enemy.y + 1 < play_bottom {
+        buf.print(lx, (enemy.y + 1) as u16, bottom, color);
     }
-    Ok(())
 }
 
-fn draw_bullet<W: Write>(out: &mut W, bullet: &Bullet) -> std::io::Result<()> {
-    match bullet.owner {
-        BulletOwner::Player => {
-            out.queue(cursor::MoveTo(bullet.x as u16, bullet.y as u16))?;
-            out.queue(style::SetForegroundColor(C_BULLET_PLAYER))?;
-            out.queue(Print("║"))?;
-        }
-        BulletOwner::Enemy => {
-            out.queue(cursor::MoveTo(bullet.x as u16, bullet.y as u16))?;
-            out.queue(style::SetForegroundColor(C_BULLET_ENEMY))?;
-            out.queue(Print("↓"))?;
-        }
+/// An enemy kind's two sprite rows and colour.
+fn enemy_sprite(kind: &EnemyKind) -> (&'static str, &'static str, Color) {
+    match kind {
+        // Enhanced sprite:
+        //   «▼»    ← swept-back wings
+        //   ╚═╝    ← engine block
+        EnemyKind::Spacecraft => ("«▼»", "╚═╝", C_ENEMY_SPACECRAFT),
+        // Enhanced sprite:
+        //   (◎)    ← glowing eye
+        //   ╰─╯    ← tentacle arc
+        EnemyKind::Octopus => ("(◎)", "╰─╯", C_ENEMY_OCTOPUS),
+        //   [▼]    ← plated hull
+        //   ╚█╝    ← heavy engine
+        EnemyKind::Armored => ("[▼]", "╚█╝", C_ENEMY_ARMORED),
     }
-    Ok(())
+}
+
+/// Damage state of a multi-hit enemy: its own colour while unhurt, yellow
+/// once hit, red on its last hit point.
+fn enemy_color(enemy: &Enemy, base: Color) -> Color {
+    let max_hp = enemy.kind.max_hp();
+    if enemy.hp >= max_hp {
+        base
+    } else if enemy.hp <= 1 {
+        C_ENEMY_CRITICAL
+    } else {
+        C_ENEMY_DAMAGED
+    }
+}
+
+/// Boss colour by remaining health: full → hurt (≤ 50 %) → critical (≤ 25 %).
+fn boss_color(boss: &Boss) -> Color {
+    if boss.hp * 4 <= boss.max_hp {
+        C_BOSS_CRITICAL
+    } else if boss.hp * 2 <= boss.max_hp {
+        C_BOSS_HURT
+    } else {
+        C_BOSS
+    }
+}
+
+fn draw_boss(buf: &mut Buffer, boss: &Boss) {
+    // 7 × 3 mothership; (x, y) is the top-centre cell:
+    //   ╔═▼═▼═╗
+    //   ║(◉◉◉)║
+    //   ╚╦═╩═╦╝
+    const SPRITE: [&str; 3] = ["╔═▼═▼═╗", "║(◉◉◉)║", "╚╦═╩═╦╝"];
+    let lx = (boss.x - BOSS_HALF_WIDTH).max(0) as u16;
+    for (dy, row) in SPRITE.iter().enumerate() {
+        buf.print(lx, (boss.y + dy as i32) as u16, row, boss_color(boss));
+    }
+}
+
+fn draw_bullet(buf: &mut Buffer, bullet: &Bullet) {
+    let (ch, color) = match bullet.owner {
+        BulletOwner::Player(PlayerId::One) => ("║", C_BULLET_PLAYER),
+        BulletOwner::Player(PlayerId::Two) => ("║", C_BULLET_PLAYER2),
+        BulletOwner::Enemy => ("↓", C_BULLET_ENEMY),
+    };
+    buf.print(bullet.x as u16, bullet.y as u16, ch, color);
+}
+
+fn draw_enemy_shot(buf: &mut Buffer, shot: &EnemyShot) {
+    buf.print(
+        shot.x.round() as u16,
+        shot.y.round() as u16,
+        "•",
+        C_BULLET_ENEMY,
+    );
 }
 
 /// Draw a falling bonus item.
@@ -353,40 +526,26 @@ fn draw_bullet<W: Write>(out: &mut W, bullet: &Bullet) -> std::io::Result<()> {
 ///   ★  (yellow)  — SpreadShot: collect for 3-way spread fire
 ///   ♥  (magenta) — ExtraLife:  instantly restores one life
 ///   !  (cyan)    — RapidFire:  raises the bullet cap to 6
-fn draw_bonus_item<W: Write>(out: &mut W, bonus: &BonusItem) -> std::io::Result<()> {
-    out.queue(cursor::MoveTo(bonus.x as u16, bonus.y as u16))?;
-    match bonus.kind {
-        BonusKind::SpreadShot => {
-            out.queue(style::SetForegroundColor(C_BONUS_SPREAD))?;
-            out.queue(Print("★"))?;
-        }
-        BonusKind::ExtraLife => {
-            out.queue(style::SetForegroundColor(C_BONUS_LIFE))?;
-            out.queue(Print("♥"))?;
-        }
-        BonusKind::RapidFire => {
-            out.queue(style::SetForegroundColor(C_BONUS_RAPID))?;
-            out.queue(Print("!"))?;
-        }
-        BonusKind::FlameBurst => {
-            out.queue(style::SetForegroundColor(C_BONUS_FLAME))?;
-            out.queue(Print("~"))?;
-        }
-        BonusKind::Firebomb => {
-            out.queue(style::SetForegroundColor(C_BONUS_BOMB))?;
-            out.queue(Print("o"))?;
-        }
+fn draw_bonus_item(buf: &mut Buffer, bonus: &BonusItem) {
+    let (ch, color) = bonus_symbol(&bonus.kind);
+    buf.print(bonus.x as u16, bonus.y as u16, ch, color);
+}
+
+fn bonus_symbol(kind: &BonusKind) -> (&'static str, Color) {
+    match kind {
+        BonusKind::SpreadShot => ("★", C_BONUS_SPREAD),
+        BonusKind::ExtraLife => ("♥", C_BONUS_LIFE),
+        BonusKind::RapidFire => ("!", C_BONUS_RAPID),
+        BonusKind::FlameBurst => ("~", C_BONUS_FLAME),
+        BonusKind::Firebomb => ("o", C_BONUS_BOMB),
     }
-    Ok(())
 }
 
 // ── New weapon draw functions ─────────────────────────────────────────────────
 
-fn draw_flame_bullet<W: Write>(out: &mut W, fb: &FlameBullet) -> std::io::Result<()> {
+fn draw_flame_bullet(buf: &mut Buffer, fb: &FlameBullet) {
     let x = fb.x.round() as u16;
     let y = fb.y.round() as u16;
-    out.queue(cursor::MoveTo(x, y))?;
-    out.queue(style::SetForegroundColor(C_FLAME_BULLET))?;
     let ch = if fb.vx <= -0.7 {
         "╱"
     } else if fb.vx <= -0.1 {
@@ -398,70 +557,109 @@ fn draw_flame_bullet<W: Write>(out: &mut W, fb: &FlameBullet) -> std::io::Result
     } else {
         "╲"
     };
-    out.queue(Print(ch))?;
-    Ok(())
+    buf.print(x, y, ch, C_FLAME_BULLET);
 }
 
-fn draw_firebomb<W: Write>(out: &mut W, bomb: &FirebombProj) -> std::io::Result<()> {
-    out.queue(cursor::MoveTo(bomb.x as u16, bomb.y as u16))?;
+fn draw_firebomb(buf: &mut Buffer, bomb: &FirebombProj) {
     let ch = if bomb.fuse % 6 < 3 { "●" } else { "○" };
-    out.queue(style::SetForegroundColor(C_FIREBOMB))?;
-    out.queue(Print(ch))?;
-    Ok(())
+    buf.print(bomb.x as u16, bomb.y as u16, ch, C_FIREBOMB);
 }
 
-fn draw_explosion<W: Write>(out: &mut W, exp: &Explosion) -> std::io::Result<()> {
+fn draw_explosion(buf: &mut Buffer, exp: &Explosion) {
     const R: i32 = 3;
-    out.queue(style::SetForegroundColor(C_EXPLOSION))?;
     for dy in -R..=R {
         for dx in -R..=R {
             if dx * dx + dy * dy <= R * R {
                 let px = exp.x + dx;
                 let py = exp.y + dy;
                 if px > 0 && py > 1 {
-                    out.queue(cursor::MoveTo(px as u16, py as u16))?;
-                    out.queue(Print("*"))?;
+                    buf.print(px as u16, py as u16, "*", C_EXPLOSION);
                 }
             }
         }
     }
-    Ok(())
 }
 
 // ── Controls hint (last row) ──────────────────────────────────────────────────
 
-fn draw_controls_hint<W: Write>(out: &mut W, state: &EntireGameStateInfo) -> std::io::Result<()> {
-    out.queue(cursor::MoveTo(1, state.height.saturating_sub(1)))?;
-    out.queue(style::SetForegroundColor(C_HINT))?;
-    out.queue(Print(
-        "← → / A D : Move   F+dir : Fast   W+dir : Warp×10   SPACE : Shoot   Q : Quit",
-    ))?;
-    Ok(())
+fn draw_controls_hint(buf: &mut Buffer, state: &EntireGameStateInfo, bindings: &Bindings) {
+    let hint = format!(
+        "{}  {} Move  {}+dir Fast  {}+dir Warp  {} Shoot  {} Pause  {} Quit",
+        bindings.label(Action::MoveLeft),
+        bindings.label(Action::MoveRight),
+        bindings.hint(Action::Fast),
+        bindings.hint(Action::Warp),
+        bindings.hint(Action::Shoot),
+        bindings.hint(Action::Pause),
+        bindings.hint(Action::Quit),
+    );
+    buf.print(1, state.height.saturating_sub(1), &hint, C_HINT);
 }
 
 // ── Score-milestone cheer ─────────────────────────────────────────────────────
 
-fn draw_cheer<W: Write>(
-    out: &mut W,
-    state: &EntireGameStateInfo,
-    msg: &str,
-) -> std::io::Result<()> {
+fn draw_cheer(buf: &mut Buffer, state: &EntireGameStateInfo, msg: &str) {
     let cx = state.width / 2;
     let row = 2u16 + (state.height.saturating_sub(4)) / 4;
     let col = cx.saturating_sub(msg.chars().count() as u16 / 2);
-    out.queue(cursor::MoveTo(col, row))?;
-    out.queue(style::SetForegroundColor(Color::Cyan))?;
-    out.queue(Print(msg))?;
-    Ok(())
+    buf.print(col, row, msg, Color::Cyan);
+}
+
+// ── Pause menu ───────────────────────────────────────────────────────────────
+
+fn draw_pause_menu(
+    buf: &mut Buffer,
+    state: &EntireGameStateInfo,
+    selected: &PauseItem,
+    bindings: &Bindings,
+) {
+    let banner = [
+        "╔════════════════════╗",
+        "║       PAUSED       ║",
+        "╚════════════════════╝",
+    ];
+    let hint = format!(
+        "↑↓ Select  ENTER OK  {} Resume",
+        bindings.hint(Action::Pause)
+    );
+
+    let cx = state.width / 2;
+    let total_rows = banner.len() + PauseItem::ALL.len() + 1;
+    let start_row = (state.height / 2).saturating_sub(total_rows as u16 / 2);
+    let left = cx.saturating_sub(banner[0].chars().count() as u16 / 2);
+
+    for (i, line) in banner.iter().enumerate() {
+        buf.print(left, start_row + i as u16, line, Color::Cyan);
+    }
+
+    let items_row = start_row + banner.len() as u16;
+    for (i, item) in PauseItem::ALL.iter().enumerate() {
+        let (marker, color) = if item == selected {
+            ("▶ ", Color::Yellow)
+        } else {
+            ("  ", Color::White)
+        };
+        // Pad to the banner width so the play area behind doesn't show through.
+        let line = format!("{:<20}", format!("{}{}", marker, item.label()));
+        buf.print(left + 1, items_row + i as u16, &line, color);
+    }
+
+    let hint_row = items_row + PauseItem::ALL.len() as u16;
+    buf.print(
+        cx.saturating_sub(hint.chars().count() as u16 / 2),
+        hint_row,
+        &hint,
+        C_HINT,
+    );
 }
 
 // ── Debug overlay ────────────────────────────────────────────────────────────
 
-fn draw_debug_overlay<W: Write>(out: &mut W, state: &EntireGameStateInfo) -> std::io::Result<()> {
+fn draw_debug_overlay(buf: &mut Buffer, state: &EntireGameStateInfo) {
     let player_bullets = state
         .bullets
         .iter()
-        .filter(|b| b.owner == BulletOwner::Player)
+        .filter(|b| b.owner != BulletOwner::Enemy)
         .count();
     let enemy_bullets = state.bullets.len() - player_bullets;
 
@@ -490,45 +688,88 @@ fn draw_debug_overlay<W: Write>(out: &mut W, state: &EntireGameStateInfo) -> std
     ];
 
     for (i, line) in lines.iter().enumerate() {
-        out.queue(cursor::MoveTo(0, 2 + i as u16))?;
-        out.queue(style::SetForegroundColor(Color::DarkGrey))?;
-        out.queue(Print("█"))?;
-        out.queue(style::SetForegroundColor(Color::White))?;
-        out.queue(Print(line))?;
-        out.queue(style::ResetColor)?;
+        let row = 2 + i as u16;
+        buf.print(0, row, "█", Color::DarkGrey);
+        buf.print(1, row, line, Color::White);
     }
 
     // Collision boxes
-    draw_hitbox(out, state.player.x, state.player.y, Color::Cyan)?;
+    draw_hitbox(buf, state.player.x, state.player.y, Color::Cyan);
     for enemy in &state.enemies {
-        draw_hitbox(out, enemy.x, enemy.y, Color::Red)?;
+        draw_hitbox(buf, enemy.x, enemy.y, Color::Red);
     }
-
-    Ok(())
 }
 
 /// Draw a 3-wide × 2-tall bounding box around (cx, top_y).
-fn draw_hitbox<W: Write>(out: &mut W, cx: i32, top_y: i32, color: Color) -> std::io::Result<()> {
+fn draw_hitbox(buf: &mut Buffer, cx: i32, top_y: i32, color: Color) {
     let corners = [
         (cx - 1, top_y),
         (cx + 1, top_y),
         (cx - 1, top_y + 1),
         (cx + 1, top_y + 1),
     ];
-    out.queue(style::SetForegroundColor(color))?;
     for (x, y) in corners {
         if x >= 0 && y >= 0 {
-            out.queue(cursor::MoveTo(x as u16, y as u16))?;
-            out.queue(Print("·"))?;
+            buf.print(x as u16, y as u16, "·", color);
         }
     }
-    out.queue(style::ResetColor)?;
-    Ok(())
 }
 
 // ── Game-over overlay ─────────────────────────────────────────────────────────
 
-fn draw_game_over<W: Write>(out: &mut W, state: &EntireGameStateInfo) -> std::io::Result<()> {
+/// The run's statistics for the game-over screen, one line of coloured
+/// pieces per row: how long it lasted, how well it shot, what it destroyed,
+/// what it caught and how it went wrong.
+fn stats_lines(stats: &RunStats, wave: u32) -> Vec<Vec<(String, Color)>> {
+    let text = |t: String| (t, C_STATS);
+    let secs = stats.frames / 30; // 30 FPS
+    let accuracy = match stats.accuracy() {
+        Some(pct) => format!("{}%", pct),
+        None => "–".to_string(),
+    };
+
+    let mut kills = Vec::new();
+    for kind in EnemyKind::ALL {
+        let (sprite, _, color) = enemy_sprite(&kind);
+        kills.push((sprite.to_string(), color));
+        kills.push(text(format!(" {}  ", stats.kills[kind.index()])));
+    }
+    kills.push(("Boss".to_string(), C_BOSS));
+    kills.push(text(format!(" {}", stats.bosses)));
+
+    let mut caught = Vec::new();
+    for kind in BonusKind::ALL {
+        let (symbol, color) = bonus_symbol(&kind);
+        if !caught.is_empty() {
+            caught.push(text("  ".to_string()));
+        }
+        caught.push((symbol.to_string(), color));
+        caught.push(text(format!(" {}", stats.power_ups[kind.index()])));
+    }
+
+    vec![
+        vec![text(format!(
+            "Wave {} · {}:{:02} survived",
+            wave,
+            secs / 60,
+            secs % 60
+        ))],
+        vec![text(format!(
+            "Shots {} · Hits {} ({})",
+            stats.shots_fired, stats.shots_hit, accuracy
+        ))],
+        kills,
+        caught,
+        vec![text(format!(
+            "Lives lost {} · Peak {} pts/{}s",
+            stats.lives_lost,
+            stats.peak_rate,
+            SCORE_RATE_WINDOW / 30
+        ))],
+    ]
+}
+
+fn draw_game_over(buf: &mut Buffer, state: &EntireGameStateInfo, bindings: &Bindings) {
     let score_line = format!("Final Score: {:>6}", state.score);
     let best_score = state.high_score.max(state.score);
     let best_line = if state.score >= state.high_score && state.score > 0 {
@@ -550,36 +791,65 @@ fn draw_game_over<W: Write>(out: &mut W, state: &EntireGameStateInfo) -> std::io
     };
     let hint_color = Color::White;
 
+    let stats = stats_lines(&state.stats, state.wave.number);
+    let split = state.player2.is_some().then(|| {
+        format!(
+            "P1 {}  ·  P2 {}",
+            player_score(state, PlayerId::One),
+            player_score(state, PlayerId::Two)
+        )
+    });
+    let hint = format!(
+        "{} - Play Again  {} - Quit",
+        bindings.hint(Action::Restart),
+        bindings.hint(Action::Quit)
+    );
+
     let cx = state.width / 2;
-    let total_rows = lines.len() + 3; // 3 box lines + score + best + hint
+    // 3 box lines + score + best + P1/P2 split + stats + hint
+    let total_rows = lines.len() + 3 + split.is_some() as usize + stats.len();
     let start_row = (state.height / 2).saturating_sub(total_rows as u16 / 2);
+    let centred = |text: &str| cx.saturating_sub(text.chars().count() as u16 / 2);
+
+    // Blank the widest line's columns, plus a margin, first so the play area
+    // behind doesn't show through.  The walls stay.
+    let widest = stats
+        .iter()
+        .map(|segments| segments.iter().map(|(t, _)| t.chars().count()).sum())
+        .chain([lines[0].0, &hint].map(|t| t.chars().count()))
+        .chain(split.iter().map(|t| t.chars().count()))
+        .max()
+        .unwrap_or(0);
+    let blank = " ".repeat((widest + 2).min(state.width.saturating_sub(2) as usize));
+    for row in 0..total_rows as u16 {
+        buf.print(centred(&blank), start_row + row, &blank, Color::White);
+    }
 
     for (i, (msg, color)) in lines.iter().enumerate() {
-        let row = start_row + i as u16;
-        let col = cx.saturating_sub(msg.chars().count() as u16 / 2);
-        out.queue(cursor::MoveTo(col, row))?;
-        out.queue(style::SetForegroundColor(*color))?;
-        out.queue(Print(*msg))?;
+        buf.print(centred(msg), start_row + i as u16, msg, *color);
     }
 
     let score_row = start_row + lines.len() as u16;
-    let col = cx.saturating_sub(score_line.chars().count() as u16 / 2);
-    out.queue(cursor::MoveTo(col, score_row))?;
-    out.queue(style::SetForegroundColor(score_color))?;
-    out.queue(Print(&score_line))?;
+    buf.print(centred(&score_line), score_row, &score_line, score_color);
 
     let best_row = score_row + 1;
-    let col = cx.saturating_sub(best_line.chars().count() as u16 / 2);
-    out.queue(cursor::MoveTo(col, best_row))?;
-    out.queue(style::SetForegroundColor(best_color))?;
-    out.queue(Print(&best_line))?;
-
-    let hint = "R - Play Again  Q - Quit";
-    let hint_row = best_row + 1;
-    let col = cx.saturating_sub(hint.chars().count() as u16 / 2);
-    out.queue(cursor::MoveTo(col, hint_row))?;
-    out.queue(style::SetForegroundColor(hint_color))?;
-    out.queue(Print(hint))?;
-
-    Ok(())
+    buf.print(centred(&best_line), best_row, &best_line, best_color);
+
+    let mut hint_row = best_row + 1;
+    if let Some(split) = &split {
+        buf.print(centred(split), hint_row, split, Color::White);
+        hint_row += 1;
+    }
+
+    for segments in &stats {
+        let width: usize = segments.iter().map(|(t, _)| t.chars().count()).sum();
+        let mut x = cx.saturating_sub(width as u16 / 2);
+        for (text, color) in segments {
+            buf.print(x, hint_row, text, *color);
+            x += text.chars().count() as u16;
+        }
+        hint_row += 1;
+    }
+
+    buf.print(centred(&hint), hint_row, &hint, hint_color);
 }
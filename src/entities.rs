@@ -1,14 +1,74 @@
+use serde::{Deserialize, Serialize};
+
 /// All game entity types — pure data, no logic.
 
-#[derive(Clone, Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub enum EnemyKind {
     /// Shocking-green spacecraft
     Spacecraft,
     /// Red octopus
     Octopus,
+    /// Grey armoured gunship that takes several hits
+    Armored,
+}
+
+impl EnemyKind {
+    pub const ALL: [EnemyKind; 3] = [
+        EnemyKind::Spacecraft,
+        EnemyKind::Octopus,
+        EnemyKind::Armored,
+    ];
+
+    /// Position in `ALL`, e.g. for per-kind tallies.
+    pub fn index(&self) -> usize {
+        match self {
+            EnemyKind::Spacecraft => 0,
+            EnemyKind::Octopus => 1,
+            EnemyKind::Armored => 2,
+        }
+    }
+
+    /// Hit points an enemy of this kind spawns with.
+    pub fn max_hp(&self) -> u32 {
+        match self {
+            EnemyKind::Spacecraft | EnemyKind::Octopus => 1,
+            EnemyKind::Armored => 3,
+        }
+    }
+
+    /// How enemies of this kind fire.
+    pub fn fire_profile(&self) -> FireProfile {
+        let (pattern, rarity) = match self {
+            EnemyKind::Spacecraft => (FirePattern::Straight, 1),
+            EnemyKind::Octopus => (FirePattern::Spread, 2),
+            EnemyKind::Armored => (FirePattern::Aimed, 1),
+        };
+        FireProfile { pattern, rarity }
+    }
+}
+
+/// The projectiles an enemy releases each time it fires.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub enum FirePattern {
+    /// One straight-down `Bullet`.
+    Straight,
+    /// One `EnemyShot` aimed at the player's position.
+    Aimed,
+    /// A 3-way fan of `EnemyShot`s around straight down.
+    Spread,
+}
+
+/// Per-`EnemyKind` firing behaviour.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct FireProfile {
+    pub pattern: FirePattern,
+    /// Scales the wave's fire chance: the enemy fires with a
+    /// 1-in-(`enemy_fire_chance` × `rarity`) chance per frame, so patterns
+    /// that release more shots can fire less often.
+    pub rarity: u32,
 }
 
-#[derive(Clone, Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub enum Level {
     Easy,
     Medium,
@@ -16,13 +76,199 @@ pub enum Level {
     Extreme,
 }
 
-#[derive(Clone, Debug, PartialEq)]
+impl Level {
+    pub const ALL: [Level; 4] = [Level::Easy, Level::Medium, Level::Hard, Level::Extreme];
+
+    /// Lowercase name used in files, the database and on the command line.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Level::Easy => "easy",
+            Level::Medium => "medium",
+            Level::Hard => "hard",
+            Level::Extreme => "extreme",
+        }
+    }
+
+    pub fn parse(s: &str) -> Option<Level> {
+        Level::ALL.into_iter().find(|l| l.as_str() == s)
+    }
+}
+
+/// Tunable spawn / movement / fire parameters for a run.
+///
+/// The four `Level`s have built-in presets (`Difficulty::preset`); custom
+/// profiles are loaded from TOML by the `profiles` module.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Difficulty {
+    /// Preset level name, or the custom profile's name.
+    pub name: String,
+    /// `true` for the built-in presets.  Only these count towards the
+    /// official leaderboard.
+    pub builtin: bool,
+    /// Frames between each one-row step of the enemies.
+    pub enemy_move_interval: u64,
+    /// Frames between enemy spawns.
+    pub enemy_spawn_rate: u64,
+    /// Each enemy fires with a 1-in-`enemy_fire_chance` chance per frame
+    /// (scaled by its kind's `FireProfile::rarity`).
+    pub enemy_fire_chance: u32,
+    /// Probability that a spawned enemy is a Spacecraft (otherwise Octopus).
+    pub spacecraft_chance: f64,
+    /// Frames between bonus-item drops.
+    pub bonus_spawn_interval: u64,
+    /// Wipe every enemy bullet and shot off the field when a life is lost.
+    pub clear_shots_on_hit: bool,
+    /// What losing a life does to the active power-up.
+    pub power_up_loss: PowerUpLoss,
+}
+
+/// What happens to the active power-up when the player loses a life.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub enum PowerUpLoss {
+    /// It keeps running until it times out.
+    Keep,
+    /// It ends immediately.
+    Lose,
+}
+
+impl PowerUpLoss {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            PowerUpLoss::Keep => "keep",
+            PowerUpLoss::Lose => "lose",
+        }
+    }
+
+    pub fn parse(s: &str) -> Option<PowerUpLoss> {
+        match s {
+            "keep" => Some(PowerUpLoss::Keep),
+            "lose" => Some(PowerUpLoss::Lose),
+            _ => None,
+        }
+    }
+}
+
+impl Difficulty {
+    pub fn preset(level: &Level) -> Difficulty {
+        let (enemy_move_interval, enemy_spawn_rate) = match level {
+            Level::Easy => (22, 130),  // new — very relaxed, fewer opponents
+            Level::Medium => (14, 90), // old Easy
+            Level::Hard => (8, 55),    // old Medium
+            Level::Extreme => (4, 28), // old Hard
+        };
+        Difficulty {
+            name: level.as_str().to_string(),
+            builtin: true,
+            enemy_move_interval,
+            enemy_spawn_rate,
+            enemy_fire_chance: 220,
+            spacecraft_chance: 0.6,
+            bonus_spawn_interval: 150,
+            // Losing a life leaves the field and the power-up alone, as it
+            // always has; custom profiles can change that.
+            clear_shots_on_hit: false,
+            power_up_loss: PowerUpLoss::Keep,
+        }
+    }
+}
+
+/// Where the run is in its sequence of waves.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct WaveState {
+    /// Current wave, starting at 1.
+    pub number: u32,
+    /// Enemies this wave has yet to spawn.
+    pub remaining: u32,
+    /// Frames of breather left before the wave starts spawning (the
+    /// "WAVE N" banner is shown meanwhile).  0 once the wave is under way.
+    pub breather: u32,
+    /// A boss still has to be fought before this wave can end.
+    pub boss_due: bool,
+}
+
+/// Parameters of one wave, derived from the run's `Difficulty` by
+/// `compute::wave_spec`.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct WaveSpec {
+    /// Total enemies spawned during the wave.
+    pub budget: u32,
+    pub enemy_move_interval: u64,
+    pub enemy_spawn_rate: u64,
+    pub enemy_fire_chance: u32,
+    pub spacecraft_chance: f64,
+    /// Probability that a spawned enemy is Armored (rolled before the
+    /// Spacecraft/Octopus split).
+    pub armored_chance: f64,
+    /// Probability that a spawn is a whole formation rather than one enemy.
+    pub formation_chance: f64,
+    /// Probability that a single spawn is a diver.
+    pub diver_chance: f64,
+    /// Probability that a single spawn zigzags.
+    pub zigzag_chance: f64,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub enum GameStatus {
     Playing,
+    /// Frozen behind the pause menu, with the cursor on this item.
+    Paused(PauseItem),
     GameOver,
 }
 
-#[derive(Clone, Debug, PartialEq)]
+/// Pause-menu entries, top to bottom.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub enum PauseItem {
+    Resume,
+    /// Start a new game on the same level and difficulty.
+    Restart,
+    Settings,
+    QuitToMenu,
+}
+
+impl PauseItem {
+    pub const ALL: [PauseItem; 4] = [
+        PauseItem::Resume,
+        PauseItem::Restart,
+        PauseItem::Settings,
+        PauseItem::QuitToMenu,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            PauseItem::Resume => "Resume",
+            PauseItem::Restart => "Restart",
+            PauseItem::Settings => "Settings",
+            PauseItem::QuitToMenu => "Quit to menu",
+        }
+    }
+}
+
+/// One discrete player input, applied to the state before a tick.
+/// The game loop records these per frame so a run can be replayed exactly.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub enum PlayerAction {
+    /// Move left by this many columns (1 = step, 2 = fast, 10 = warp).
+    MoveLeft(i32),
+    /// Move right by this many columns.
+    MoveRight(i32),
+    Shoot,
+    ToggleDebug,
+    ToggleGodMode,
+    ToggleSlowMo,
+    /// The terminal was resized to this width and height.
+    Resize(u16, u16),
+    /// Co-op: player two moves left by this many columns.
+    P2MoveLeft(i32),
+    /// Co-op: player two moves right by this many columns.
+    P2MoveRight(i32),
+    /// Co-op: player two fires.
+    P2Shoot,
+    /// Co-op: player two left the game (their network connection dropped);
+    /// their ship is out for the rest of the run.
+    P2Leave,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub enum BonusKind {
     /// 3-way spread shot (straight up) for POWER_UP_DURATION frames.
     SpreadShot,
@@ -39,7 +285,49 @@ pub enum BonusKind {
     Firebomb,
 }
 
-#[derive(Clone, Debug)]
+impl BonusKind {
+    pub const ALL: [BonusKind; 5] = [
+        BonusKind::SpreadShot,
+        BonusKind::ExtraLife,
+        BonusKind::RapidFire,
+        BonusKind::FlameBurst,
+        BonusKind::Firebomb,
+    ];
+
+    /// Position in `ALL`, e.g. for per-kind tallies.
+    pub fn index(&self) -> usize {
+        match self {
+            BonusKind::SpreadShot => 0,
+            BonusKind::ExtraLife => 1,
+            BonusKind::RapidFire => 2,
+            BonusKind::FlameBurst => 3,
+            BonusKind::Firebomb => 4,
+        }
+    }
+}
+
+/// Why the player lost a life.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub enum HitCause {
+    EnemyBullet,
+    /// An enemy reached the player's row.
+    EnemyContact,
+}
+
+/// Something notable that happened during a tick.  Consumers such as the
+/// headless simulator read these instead of diffing consecutive states.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub enum GameEvent {
+    EnemyKilled(EnemyKind),
+    PowerUpCaught(BonusKind),
+    /// A life was lost (not raised while god mode absorbs the hit).
+    PlayerHit(HitCause),
+    /// Every enemy of this wave has been dealt with; the next one begins.
+    WaveCleared(u32),
+    BossDefeated,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct BonusItem {
     pub x: i32,
     pub y: i32,
@@ -48,26 +336,28 @@ pub struct BonusItem {
 
 /// A player bullet that travels diagonally (used by the FlameBurst power-up).
 /// Positions are stored as floats so sub-column angles stay smooth.
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct FlameBullet {
     pub x: f32,
     pub y: f32,
     /// Horizontal velocity added each frame (positive = rightward).
     /// Vertical velocity is always −1.0 (one row upward per frame).
     pub vx: f32,
+    pub owner: PlayerId,
 }
 
 /// A slow-moving explosive projectile (used by the Firebomb power-up).
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct FirebombProj {
     pub x: i32,
     pub y: i32,
     /// Frames until automatic detonation even without hitting anything.
     pub fuse: u32,
+    pub owner: PlayerId,
 }
 
 /// A brief visual explosion rendered for a few frames after a firebomb detonates.
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct Explosion {
     pub x: i32,
     pub y: i32,
@@ -75,41 +365,198 @@ pub struct Explosion {
     pub frames: u32,
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct Player {
     pub x: i32,
     pub y: i32,
+    /// 0 once the ship is out: in co-op the other player carries on alone.
     pub lives: u32,
+    /// Frames of post-hit invulnerability left; 0 when vulnerable.
+    pub invulnerable: u32,
+}
+
+/// Which ship, in a co-op game.  Solo games only have `One`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum PlayerId {
+    One,
+    Two,
+}
+
+impl PlayerId {
+    pub const ALL: [PlayerId; 2] = [PlayerId::One, PlayerId::Two];
+
+    /// 0 for player one, 1 for player two.
+    pub fn index(self) -> usize {
+        match self {
+            PlayerId::One => 0,
+            PlayerId::Two => 1,
+        }
+    }
+}
+
+/// Player two of a co-op game: a second ship with its own power-up slot
+/// and share of the score.  Player one's are `state.player`,
+/// `state.active_power_up` and whatever of `state.score` this does not claim.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Player2 {
+    pub ship: Player,
+    pub active_power_up: Option<(BonusKind, u32)>,
+    /// Points scored by player two; included in `state.score`.
+    pub score: u32,
 }
 
-#[derive(Clone, Debug)]
+/// How an enemy moves.  Every behaviour descends one row per move
+/// interval until it does something else.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub enum EnemyBehavior {
+    /// Straight down (the original pattern).
+    Straight,
+    /// Also steps one column sideways per row, turning after
+    /// `ZIGZAG_LEG` rows or at a wall.
+    Zigzag {
+        /// Current sideways direction: −1 (left) or 1 (right).
+        dir: i32,
+        /// Rows descended on the current leg.
+        steps: u32,
+    },
+    /// Descends normally for a few rows, then dives: accelerating every
+    /// frame and homing in on the player's column.
+    Diver {
+        /// Frames spent diving; 0 while still descending normally.
+        dive: u32,
+    },
+    /// Part of a formation that sways side to side in lockstep; members
+    /// occasionally peel off and turn into divers.
+    Formation,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct Enemy {
     pub x: i32,
     pub y: i32,
     pub kind: EnemyKind,
+    /// Hits left before it is destroyed; starts at `kind.max_hp()`.
+    pub hp: u32,
+    pub behavior: EnemyBehavior,
+}
+
+/// Boss attack patterns, cycled in this order.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub enum BossPattern {
+    /// Single shots aimed at the player's position.
+    Aimed,
+    /// Five-way fans spread around straight down.
+    Fan,
+    /// A steady stream whose angle sweeps from one side to the other.
+    Sweep,
 }
 
-#[derive(Clone, Debug, PartialEq)]
+/// Half the boss sprite's width: it spans `x ± BOSS_HALF_WIDTH`.
+pub const BOSS_HALF_WIDTH: i32 = 3;
+/// Boss sprite rows, `y` to `y + BOSS_HEIGHT - 1`.
+pub const BOSS_HEIGHT: i32 = 3;
+
+/// A large multi-hit enemy that patrols the top of the play area.
+/// The sprite and hitbox cover `x ± BOSS_HALF_WIDTH` × `BOSS_HEIGHT` rows.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Boss {
+    pub x: i32,
+    pub y: i32,
+    pub hp: u32,
+    pub max_hp: u32,
+    /// Horizontal patrol direction: −1 (left) or 1 (right).
+    pub dir: i32,
+    pub pattern: BossPattern,
+    /// Frames spent in the current pattern.
+    pub pattern_frame: u32,
+}
+
+/// An enemy projectile with a float position and velocity, so it can
+/// travel at any angle (aimed and spread fire, boss attacks).
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct EnemyShot {
+    pub x: f32,
+    pub y: f32,
+    /// Velocity added each frame (positive `vy` = downward).
+    pub vx: f32,
+    pub vy: f32,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub enum BulletOwner {
-    Player,
+    Player(PlayerId),
     Enemy,
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct Bullet {
     pub x: i32,
     pub y: i32,
     pub owner: BulletOwner,
 }
 
+/// Length of the stretch `RunStats::peak_rate` is measured over (10 s at
+/// 30 fps).
+pub const SCORE_RATE_WINDOW: u64 = 300;
+
+/// What happened over a whole run, tallied by `tick` for the game-over
+/// screen and the score database.  Both players count towards the same
+/// totals in co-op.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct RunStats {
+    /// Projectiles the players fired (a spread shot counts each bullet).
+    pub shots_fired: u32,
+    /// Player projectiles that struck an enemy or the boss; a firebomb
+    /// counts once however many enemies its blast reaches.
+    pub shots_hit: u32,
+    /// Enemies destroyed, indexed by `EnemyKind::index`.
+    pub kills: [u32; 3],
+    pub bosses: u32,
+    /// Power-ups caught, indexed by `BonusKind::index`.
+    pub power_ups: [u32; 5],
+    pub lives_lost: u32,
+    /// Frames played, not counting pauses.
+    pub frames: u64,
+    /// Most points scored within one `SCORE_RATE_WINDOW`-frame stretch.
+    pub peak_rate: u32,
+    /// Points scored so far in the current stretch.
+    pub window_points: u32,
+}
+
+impl RunStats {
+    /// Percentage of shots that hit, or `None` before the first shot.
+    pub fn accuracy(&self) -> Option<u32> {
+        (self.shots_hit.min(self.shots_fired) * 100).checked_div(self.shots_fired)
+    }
+
+    pub fn total_kills(&self) -> u32 {
+        self.kills.iter().sum()
+    }
+}
+
+/// The random number generator a game runs on.  The same algorithm and
+/// stream as `rand::rngs::StdRng`, but its state can be saved.
+pub type GameRng = rand_chacha::ChaCha12Rng;
+
+/// Smallest terminal the game is played in; below this it waits, paused,
+/// for the window to grow.
+pub const MIN_WIDTH: u16 = 40;
+pub const MIN_HEIGHT: u16 = 16;
+
 /// The entire game state.  Cloneable so pure update functions can
 /// return a new copy without mutating the original.
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct EntireGameStateInfo {
     pub player: Player,
+    /// The second ship in co-op games; `None` when playing solo.
+    pub player2: Option<Player2>,
     pub enemies: Vec<Enemy>,
     /// Standard (straight-moving) bullets from player and enemies.
     pub bullets: Vec<Bullet>,
+    /// Angled enemy projectiles (aimed and spread fire, boss attacks).
+    pub enemy_shots: Vec<EnemyShot>,
+    /// The boss currently on the field, if any.
+    pub boss: Option<Boss>,
     /// Diagonally-moving flame bullets fired during FlameBurst.
     pub flame_bullets: Vec<FlameBullet>,
     /// Slow firebomb projectiles fired during the Firebomb power-up.
@@ -118,12 +565,17 @@ pub struct EntireGameStateInfo {
     pub explosions: Vec<Explosion>,
     /// Bonus power-up items currently falling through the play area.
     pub bonus_items: Vec<BonusItem>,
-    /// Active power-up and the number of frames remaining, if any.
+    /// Player one's active power-up and the number of frames remaining, if any.
     pub active_power_up: Option<(BonusKind, u32)>,
+    /// The whole team's score in co-op.
     pub score: u32,
     /// The highest score seen so far (updated live during play).
     pub high_score: u32,
     pub level: Level,
+    /// Parameters in effect for this run (a preset of `level`, or a custom
+    /// profile based on it).
+    pub difficulty: Difficulty,
+    pub wave: WaveState,
     pub status: GameStatus,
     pub frame: u64,
     pub width: u16,
@@ -136,4 +588,8 @@ pub struct EntireGameStateInfo {
     pub muzzle_flash: u32,
     /// Active cheer message (milestone reached) and frames remaining.
     pub cheer_msg: Option<(String, u32)>,
+    /// Events raised by the most recent tick; replaced every tick.
+    pub events: Vec<GameEvent>,
+    /// Running totals for the whole run.
+    pub stats: RunStats,
 }
@@ -1,3 +1,16 @@
+//! Keyboard (and mouse) input: per-action held-key tracking and the
+//! `InputController` that turns raw terminal events into what the player
+//! means.
+
+use std::collections::HashMap;
+
+use crossterm::event::{
+    KeyCode, KeyEvent, KeyEventKind, KeyModifiers, MouseButton, MouseEvent, MouseEventKind,
+};
+
+use crate::bindings::{Action, Bindings};
+use crate::entities::{EntireGameStateInfo, GameStatus, PlayerAction};
+
 /// A key is considered "held" if its last press/repeat event arrived within
 /// this many frames.  3 frames (~100 ms) is enough to stay live between
 /// consecutive Repeat events while expiring quickly after physical release.
@@ -12,12 +25,12 @@ pub const HOLD_WINDOW: u64 = 5;
 /// Space tap; if Space is held longer the player briefly stops then resumes.
 pub const GRACE_PERIOD: u64 = 1;
 
-/// Per-key input state tracked in the game loop's key map.
+/// Input state of one action, tracked in the game loop's [`ActionKeys`].
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum KeyState {
-    /// Key was last pressed/repeated at this frame.
+    /// A key for it was last pressed/repeated at this frame.
     Held(u64),
-    /// Key was released at this frame; grace period may still keep it live.
+    /// Its key was released at this frame; grace period may still keep it live.
     Released(u64),
 }
 
@@ -40,3 +53,437 @@ impl KeyState {
         }
     }
 }
+
+/// `KeyState` per action.  Every key bound to an action feeds the same entry,
+/// so aliases such as Left and A need no special handling by the caller.
+#[derive(Clone, Debug, Default)]
+pub struct ActionKeys {
+    states: HashMap<Action, KeyState>,
+}
+
+impl ActionKeys {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// A Press or Repeat of one of `action`'s keys arrived at `frame`.
+    pub fn press(&mut self, action: Action, frame: u64) {
+        self.states.insert(action, KeyState::Held(frame));
+    }
+
+    /// One of `action`'s keys was released at `frame`.
+    pub fn release(&mut self, action: Action, frame: u64) {
+        self.states.insert(action, KeyState::Released(frame));
+    }
+
+    pub fn get(&self, action: Action) -> Option<KeyState> {
+        self.states.get(&action).copied()
+    }
+
+    /// Whether `action` should be treated as held at `frame`.
+    pub fn is_held(&self, action: Action, frame: u64) -> bool {
+        self.get(action).is_some_and(|s| s.is_held(frame))
+    }
+}
+
+// ── Input controller ──────────────────────────────────────────────────────────
+
+/// Min frames between player movements while a direction key is held.
+/// 1.0 resets to 0 after one decrement → player moves every frame (30 cols/sec).
+pub const MOVE_COOLDOWN: f64 = 0.1;
+/// Frames between warp jumps while Warp is held (≈3–4 warps/sec at 30 FPS).
+pub const WARP_COOLDOWN: f64 = 8.0;
+/// A second Press of a direction within this many frames counts as a Repeat.
+pub const RAPID_REPRESS: u64 = 4;
+/// Mouse mode: most columns the ship moves towards the pointer per frame.
+pub const MOUSE_MAX_SPEED: i32 = 2;
+/// Mouse mode: frames between shots while the left button is held.
+pub const AUTOFIRE_INTERVAL: u64 = 5;
+
+/// Tracks which direction the player is actively holding.
+/// A single ternary value instead of two independent bools — mutually exclusive
+/// by construction, so setting one side can never leave the other stale.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum HeldDir {
+    None,
+    Left,
+    Right,
+}
+
+/// What a key press asks of the game loop.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Intent {
+    /// Apply to the game state (and log it for the replay).
+    Act(PlayerAction),
+    Pause,
+    Resume,
+    /// Pause menu: move the selection by this many items.
+    Select(i32),
+    /// Pause menu: choose the selected item.
+    Confirm,
+    /// Game over: play again.
+    Restart,
+    Quit,
+}
+
+/// The keyboard state machine of the game loop.
+///
+/// Feed it every key event with [`key_event`](Self::key_event) as it is
+/// drained, then call [`end_frame`](Self::end_frame) once per frame for the
+/// movement that held keys produce.
+///
+/// Works on two classes of terminal:
+/// * **Keyboard-enhancement capable** (Ghostty, kitty, etc.): proper
+///   `Press` / `Repeat` / `Release` events → actions transition to Released on
+///   release.
+/// * **Classic terminals**: only `Press` events (OS key-repeat shows as
+///   repeated `Press`).  Actions expire naturally after `HOLD_WINDOW` frames
+///   of silence, which is shorter than the OS repeat interval, so the key stays
+///   live while it is actively generating repeats.
+#[derive(Clone, Debug)]
+pub struct InputController {
+    bindings: Bindings,
+    keys: ActionKeys,
+    // Set to Left/Right on Repeat events (or rapid re-Press), cleared to None
+    // on Release. Ternary so opposite-side conflicts are impossible by
+    // construction.
+    held_dir: HeldDir,
+    move_cooldown: f64,
+    warp_cooldown: f64,
+    // Co-op: player two's held direction and movement cooldown.  Player two
+    // has no Fast or Warp.
+    p2_held_dir: HeldDir,
+    p2_move_cooldown: f64,
+    // Release events are deferred to the end of the frame so that a straggler
+    // Repeat arriving in the same OS-queue flush cannot re-enable a key that
+    // was just released.
+    deferred_releases: Vec<Action>,
+    // Mouse mode: whether mouse events are listened to, the column the ship
+    // is heading for, and the frame of the last shot while the left button
+    // is down.
+    mouse: bool,
+    mouse_target: Option<i32>,
+    autofire: Option<u64>,
+}
+
+impl InputController {
+    pub fn new(bindings: Bindings) -> Self {
+        InputController {
+            bindings,
+            keys: ActionKeys::new(),
+            held_dir: HeldDir::None,
+            move_cooldown: 0.0,
+            warp_cooldown: 0.0,
+            p2_held_dir: HeldDir::None,
+            p2_move_cooldown: 0.0,
+            deferred_releases: Vec::new(),
+            mouse: false,
+            mouse_target: None,
+            autofire: None,
+        }
+    }
+
+    /// Turn mouse mode on or off.  Off drops any pending target and stops
+    /// autofire.
+    pub fn set_mouse(&mut self, enabled: bool) {
+        self.mouse = enabled;
+        if !enabled {
+            self.mouse_target = None;
+            self.autofire = None;
+        }
+    }
+
+    pub fn bindings(&self) -> &Bindings {
+        &self.bindings
+    }
+
+    pub fn held_dir(&self) -> HeldDir {
+        self.held_dir
+    }
+
+    /// Player two's held direction, in co-op.
+    pub fn p2_held_dir(&self) -> HeldDir {
+        self.p2_held_dir
+    }
+
+    /// Handle one key event received during `frame`, given the state it
+    /// arrived in.  Returns what a Press asks for; Repeat and Release only
+    /// update the held-key state.
+    pub fn key_event(
+        &mut self,
+        frame: u64,
+        event: KeyEvent,
+        state: &EntireGameStateInfo,
+    ) -> Vec<Intent> {
+        let bound = self.bindings.actions_for(event.code);
+        match event.kind {
+            KeyEventKind::Press => self.press(frame, event, &bound, state),
+            // Repeat: refresh timestamp and mark direction as held.
+            KeyEventKind::Repeat => {
+                for action in bound {
+                    match action {
+                        Action::MoveLeft => self.held_dir = HeldDir::Left,
+                        Action::MoveRight => self.held_dir = HeldDir::Right,
+                        Action::P2MoveLeft => self.p2_held_dir = HeldDir::Left,
+                        Action::P2MoveRight => self.p2_held_dir = HeldDir::Right,
+                        _ => {}
+                    }
+                    self.keys.press(action, frame);
+                }
+                Vec::new()
+            }
+            // Release: defer until all Press/Repeat events this frame are handled.
+            KeyEventKind::Release => {
+                self.deferred_releases.extend(bound);
+                Vec::new()
+            }
+        }
+    }
+
+    fn press(
+        &mut self,
+        frame: u64,
+        event: KeyEvent,
+        bound: &[Action],
+        state: &EntireGameStateInfo,
+    ) -> Vec<Intent> {
+        let on = |action: Action| bound.contains(&action);
+        let paused = matches!(state.status, GameStatus::Paused(_));
+        let playing = state.status == GameStatus::Playing;
+        let coop = playing && state.player2.is_some();
+        let ctrl_c =
+            event.code == KeyCode::Char('c') && event.modifiers.contains(KeyModifiers::CONTROL);
+        let intent = match event.code {
+            _ if ctrl_c => Intent::Quit,
+            // Pause: open or close the pause menu.
+            _ if on(Action::Pause) && playing => Intent::Pause,
+            _ if on(Action::Pause) && paused => Intent::Resume,
+            KeyCode::Up if paused => Intent::Select(-1),
+            KeyCode::Down if paused => Intent::Select(1),
+            KeyCode::Enter if paused => Intent::Confirm,
+            _ if on(Action::Quit) => Intent::Quit,
+            _ if on(Action::Restart) && state.status == GameStatus::GameOver => Intent::Restart,
+            // Shoot: single-shot on press — not tracked in `keys` so it
+            // never interferes with held movement keys.
+            _ if on(Action::Shoot) && playing => Intent::Act(PlayerAction::Shoot),
+            // Movement keys: move one step immediately on press.
+            // For classic terminals the OS sends repeated Press events
+            // instead of Repeat; treat a rapid second Press as a Repeat so
+            // continuous movement still works.
+            _ if on(Action::MoveLeft) && playing => {
+                return self.press_direction(frame, Action::MoveLeft);
+            }
+            _ if on(Action::MoveRight) && playing => {
+                return self.press_direction(frame, Action::MoveRight);
+            }
+            // Player two's keys do nothing in a solo game.
+            _ if on(Action::P2Shoot) && coop => Intent::Act(PlayerAction::P2Shoot),
+            _ if on(Action::P2MoveLeft) && coop => {
+                return self.press_direction(frame, Action::P2MoveLeft);
+            }
+            _ if on(Action::P2MoveRight) && coop => {
+                return self.press_direction(frame, Action::P2MoveRight);
+            }
+            // Warp: instant 10-step jump on keydown (if direction held).
+            // Fast: instant 2-step move on keydown (if direction held).
+            _ if (on(Action::Warp) || on(Action::Fast)) && playing => {
+                let (step, action) = if on(Action::Warp) {
+                    (10, Action::Warp)
+                } else {
+                    (2, Action::Fast)
+                };
+                self.keys.press(action, frame);
+                let moved = self.step(frame, step);
+                if action == Action::Warp && moved.is_some() {
+                    self.warp_cooldown = WARP_COOLDOWN;
+                }
+                return moved.map(Intent::Act).into_iter().collect();
+            }
+            _ if on(Action::Debug) => Intent::Act(PlayerAction::ToggleDebug),
+            // God mode and slow-mo only while debug is on.
+            _ if on(Action::GodMode) && state.debug_mode => {
+                Intent::Act(PlayerAction::ToggleGodMode)
+            }
+            _ if on(Action::SlowMo) && state.debug_mode => Intent::Act(PlayerAction::ToggleSlowMo),
+            _ => {
+                for action in bound {
+                    self.keys.press(*action, frame);
+                }
+                return Vec::new();
+            }
+        };
+        vec![intent]
+    }
+
+    fn press_direction(&mut self, frame: u64, action: Action) -> Vec<Intent> {
+        let rapid = self
+            .keys
+            .get(action)
+            .and_then(|s| s.as_held_frame())
+            .is_some_and(|last| frame.saturating_sub(last) <= RAPID_REPRESS);
+        self.keys.press(action, frame);
+        let (held_dir, dir, step) = match action {
+            Action::MoveLeft => (&mut self.held_dir, HeldDir::Left, PlayerAction::MoveLeft(1)),
+            Action::P2MoveLeft => (
+                &mut self.p2_held_dir,
+                HeldDir::Left,
+                PlayerAction::P2MoveLeft(1),
+            ),
+            Action::P2MoveRight => (
+                &mut self.p2_held_dir,
+                HeldDir::Right,
+                PlayerAction::P2MoveRight(1),
+            ),
+            _ => (
+                &mut self.held_dir,
+                HeldDir::Right,
+                PlayerAction::MoveRight(1),
+            ),
+        };
+        if rapid {
+            *held_dir = dir;
+            Vec::new()
+        } else {
+            *held_dir = HeldDir::None;
+            vec![Intent::Act(step)]
+        }
+    }
+
+    /// A move of `step` columns towards the held direction, if any.
+    ///
+    /// `held_dir` is the fallback: when Fast/Warp is pressed the terminal may
+    /// stop sending Repeat for direction keys, making `is_held()` expire
+    /// before the key is actually released.
+    fn step(&self, frame: u64, step: i32) -> Option<PlayerAction> {
+        if self.held_dir == HeldDir::Left || self.keys.is_held(Action::MoveLeft, frame) {
+            Some(PlayerAction::MoveLeft(step))
+        } else if self.held_dir == HeldDir::Right || self.keys.is_held(Action::MoveRight, frame) {
+            Some(PlayerAction::MoveRight(step))
+        } else {
+            None
+        }
+    }
+
+    /// Handle one mouse event received during `frame`.  Moving the pointer
+    /// sets the column the ship steers towards; a left click fires at once
+    /// and holding the button keeps firing.  Ignored unless mouse mode is on.
+    pub fn mouse_event(
+        &mut self,
+        frame: u64,
+        event: MouseEvent,
+        state: &EntireGameStateInfo,
+    ) -> Vec<Intent> {
+        if !self.mouse {
+            return Vec::new();
+        }
+        // A release always stops autofire, even one made while paused.
+        if event.kind == MouseEventKind::Up(MouseButton::Left) {
+            self.autofire = None;
+            return Vec::new();
+        }
+        if state.status != GameStatus::Playing {
+            return Vec::new();
+        }
+        if matches!(
+            event.kind,
+            MouseEventKind::Moved | MouseEventKind::Drag(_) | MouseEventKind::Down(_)
+        ) {
+            // The ship's centre cannot reach the walls.
+            let column = (event.column as i32).clamp(1, state.width as i32 - 2);
+            self.mouse_target = Some(column);
+        }
+        if event.kind == MouseEventKind::Down(MouseButton::Left) {
+            self.autofire = Some(frame);
+            vec![Intent::Act(PlayerAction::Shoot)]
+        } else {
+            Vec::new()
+        }
+    }
+
+    /// Close `frame`: apply the releases deferred during it, then return the
+    /// movement produced by held keys, and in mouse mode the steering towards
+    /// the pointer and autofire.
+    pub fn end_frame(&mut self, frame: u64, state: &EntireGameStateInfo) -> Vec<PlayerAction> {
+        for action in std::mem::take(&mut self.deferred_releases) {
+            match action {
+                Action::MoveLeft if self.held_dir == HeldDir::Left => {
+                    self.held_dir = HeldDir::None;
+                }
+                Action::MoveRight if self.held_dir == HeldDir::Right => {
+                    self.held_dir = HeldDir::None;
+                }
+                Action::P2MoveLeft if self.p2_held_dir == HeldDir::Left => {
+                    self.p2_held_dir = HeldDir::None;
+                }
+                Action::P2MoveRight if self.p2_held_dir == HeldDir::Right => {
+                    self.p2_held_dir = HeldDir::None;
+                }
+                _ => {}
+            }
+            self.keys.release(action, frame);
+        }
+
+        let mut actions = Vec::new();
+        if state.status == GameStatus::Playing {
+            // held_dir is the authoritative "direction held" signal: set on
+            // Repeat events, cleared only on Release. is_held() alone is
+            // unreliable here because terminals stop sending direction-key
+            // Repeat events while Fast or Warp is also held.
+            if self.keys.is_held(Action::Warp, frame) && self.warp_cooldown <= 0.0 {
+                if let Some(warp) = self.step(frame, 10) {
+                    actions.push(warp);
+                    self.warp_cooldown = WARP_COOLDOWN;
+                }
+            } else if self.keys.is_held(Action::Fast, frame) {
+                actions.extend(self.step(frame, 2));
+            } else if self.move_cooldown <= 0.0 {
+                let step = match self.held_dir {
+                    HeldDir::Left => Some(PlayerAction::MoveLeft(1)),
+                    HeldDir::Right => Some(PlayerAction::MoveRight(1)),
+                    HeldDir::None => None,
+                };
+                if let Some(step) = step {
+                    actions.push(step);
+                    self.move_cooldown = MOVE_COOLDOWN;
+                }
+            }
+            if state.player2.is_some() && self.p2_move_cooldown <= 0.0 {
+                let step = match self.p2_held_dir {
+                    HeldDir::Left => Some(PlayerAction::P2MoveLeft(1)),
+                    HeldDir::Right => Some(PlayerAction::P2MoveRight(1)),
+                    HeldDir::None => None,
+                };
+                if let Some(step) = step {
+                    actions.push(step);
+                    self.p2_move_cooldown = MOVE_COOLDOWN;
+                }
+            }
+        }
+
+        if state.status == GameStatus::Playing {
+            if let Some(target) = self.mouse_target {
+                let dx = (target - state.player.x).clamp(-MOUSE_MAX_SPEED, MOUSE_MAX_SPEED);
+                match dx {
+                    0 => {}
+                    dx if dx < 0 => actions.push(PlayerAction::MoveLeft(-dx)),
+                    dx => actions.push(PlayerAction::MoveRight(dx)),
+                }
+                // Once there, let go so the keyboard can move the ship again.
+                if (target - state.player.x).abs() <= MOUSE_MAX_SPEED {
+                    self.mouse_target = None;
+                }
+            }
+            if let Some(last) = self.autofire {
+                if frame.saturating_sub(last) >= AUTOFIRE_INTERVAL {
+                    actions.push(PlayerAction::Shoot);
+                    self.autofire = Some(frame);
+                }
+            }
+        }
+
+        self.move_cooldown = (self.move_cooldown - 1.0).max(0.0);
+        self.p2_move_cooldown = (self.p2_move_cooldown - 1.0).max(0.0);
+        self.warp_cooldown = (self.warp_cooldown - 1.0).max(0.0);
+        actions
+    }
+}
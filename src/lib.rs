@@ -1,5 +1,15 @@
+pub mod bindings;
+pub mod buffer;
 pub mod compute;
+pub mod db;
 pub mod demo;
 pub mod display;
 pub mod entities;
 pub mod input_keyboard;
+pub mod net;
+pub mod profiles;
+pub mod renderer;
+pub mod replay;
+pub mod savegame;
+pub mod settings;
+pub mod sim;
@@ -1,9 +1,8 @@
-mod db;
-
 use shooting_game::display;
 
-use std::collections::HashMap;
 use std::io::{stdout, BufWriter, Write};
+use std::net::TcpListener;
+use std::path::{Path, PathBuf};
 use std::sync::mpsc;
 use std::thread;
 use std::time::{Duration, Instant};
@@ -11,53 +10,124 @@ use std::time::{Duration, Instant};
 use crossterm::{
     cursor,
     event::{
-        self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers, KeyboardEnhancementFlags,
-        PopKeyboardEnhancementFlags, PushKeyboardEnhancementFlags,
+        self, DisableMouseCapture, EnableMouseCapture, Event, KeyCode, KeyEvent, KeyEventKind,
+        KeyModifiers, KeyboardEnhancementFlags, PopKeyboardEnhancementFlags,
+        PushKeyboardEnhancementFlags,
     },
     style::{self, Color, Print},
     terminal, ExecutableCommand, QueueableCommand,
 };
-use rand::thread_rng;
+use rand::SeedableRng;
+use rusqlite::Connection;
 
+use shooting_game::bindings::{self, Action, Bindings};
+use shooting_game::buffer::Buffer;
 use shooting_game::compute::{
-    init_state, move_player_left, move_player_left_n, move_player_right, move_player_right_n,
-    player_shoot, tick,
+    apply_action, init_state_with, pause, pause_select, player_score, playfield_fits, resize,
+    resume, tick, with_player_two,
+};
+use shooting_game::db;
+use shooting_game::entities::{
+    Difficulty, EntireGameStateInfo, GameRng, GameStatus, Level, PauseItem, PlayerAction, PlayerId,
+    MIN_HEIGHT, MIN_WIDTH,
 };
-use shooting_game::entities::{EntireGameStateInfo, GameStatus, Level};
-use shooting_game::input_keyboard::KeyState;
+use shooting_game::input_keyboard::{InputController, Intent};
+use shooting_game::net::{self, Broadcast, Client, Host, Viewer};
+use shooting_game::profiles::{self, Profile};
+use shooting_game::renderer::{Renderer, TerminalRenderer};
+use shooting_game::replay::{self, Replay, ReplayCheck, ReplayFrame};
+use shooting_game::savegame::{self, SaveGame};
+use shooting_game::settings::{self, Settings};
 
 const FRAME: Duration = Duration::from_millis(33); // ≈30 FPS
 
-// ── Simultaneous-input constants ──────────────────────────────────────────────
-
-/// Min frames between player movements while a direction key is held.
-/// 1.0 resets to 0 after one decrement → player moves every frame (30 cols/sec).
-const MOVE_COOLDOWN: f64 = 0.1;
-/// Frames between warp jumps while W is held (≈3–4 warps/sec at 30 FPS).
-const WARP_COOLDOWN: f64 = 8.0;
-
-/// Tracks which direction the player is actively holding.
-/// A single ternary value instead of two independent bools — mutually exclusive
-/// by construction, so setting one side can never leave the other stale.
-#[derive(Clone, Copy, PartialEq, Eq)]
-enum HeldDir {
-    None,
-    Left,
-    Right,
+/// How a game interacts with the replay system.
+enum ReplayMode<'a> {
+    /// Normal play, nothing captured.
+    Off,
+    /// Capture every ticked frame into the replay.
+    Record(&'a mut Replay),
+    /// Drive the game from a recording instead of the keyboard, checking each
+    /// frame's state hash.
+    Play(&'a Replay, &'a mut ReplayCheck),
+}
+
+/// Who follows a game over the network.
+#[derive(Default)]
+struct Peers {
+    /// Player two, when hosting.
+    host: Option<Host>,
+    /// Viewers, with `--broadcast`.
+    broadcast: Option<Broadcast>,
+}
+
+impl Peers {
+    fn any(&self) -> bool {
+        self.host.is_some() || self.broadcast.is_some()
+    }
+}
+
+/// How `game_loop` ended.
+enum LoopExit {
+    /// Quit the program.
+    Quit,
+    /// Back to the main menu.
+    Menu,
+    /// Start a new game on the same level and difficulty.
+    Restart,
+}
+
+/// The player's settings and key bindings, and the files they are saved to
+/// when changed.
+struct SettingsStore {
+    path: PathBuf,
+    settings: Settings,
+    bindings_path: PathBuf,
+    bindings: Bindings,
+}
+
+/// Apply `action` and log it for the current frame's replay record.
+fn perform(state: &mut EntireGameStateInfo, actions: &mut Vec<PlayerAction>, action: PlayerAction) {
+    *state = apply_action(state, &action);
+    actions.push(action);
 }
 
 // ── Menu ──────────────────────────────────────────────────────────────────────
 
+/// Custom profiles beyond this many are not listed (keys 5–9).
+const MAX_MENU_PROFILES: usize = 5;
+
 enum MenuResult {
-    Start(Level),
+    Start(Level, Difficulty),
+    /// Pick up the saved game where it was left.
+    Continue,
+    /// Open the key-rebinding screen.
+    Keys,
+    /// Open the leaderboard.
+    Leaderboard,
+    /// Switch mouse control on or off.
+    ToggleMouse,
+    /// Switch two-player co-op on or off for the next games.
+    ToggleCoop,
     Quit,
 }
 
+/// `saved` is the saved game's summary for "Continue", or why the save on
+/// disk cannot be continued.  `notice` is a problem with the scores
+/// database or the save file.
+#[allow(clippy::too_many_arguments)]
 fn show_menu<W: Write>(
     out: &mut W,
     rx: &mpsc::Receiver<Event>,
     high_score: u32,
+    profiles: &[Profile],
+    store: &SettingsStore,
+    coop: bool,
+    hosting: Option<u16>,
+    saved: Option<Result<String, String>>,
+    notice: Option<&str>,
 ) -> std::io::Result<MenuResult> {
+    let bindings = &store.bindings;
     out.queue(terminal::Clear(terminal::ClearType::All))?;
 
     let (width, height) = terminal::size()?;
@@ -83,6 +153,20 @@ fn show_menu<W: Write>(
         out.queue(Print(&hs_str))?;
     }
 
+    match &saved {
+        Some(Ok(summary)) => {
+            out.queue(cursor::MoveTo(cx.saturating_sub(10), cy.saturating_sub(4)))?;
+            out.queue(style::SetForegroundColor(Color::Green))?;
+            out.queue(Print(format!("[Enter] Continue — {}", summary)))?;
+        }
+        Some(Err(why)) => {
+            out.queue(cursor::MoveTo(cx.saturating_sub(10), cy.saturating_sub(4)))?;
+            out.queue(style::SetForegroundColor(Color::DarkGrey))?;
+            out.queue(Print(format!("Saved game can't be continued: {}", why)))?;
+        }
+        None => {}
+    }
+
     out.queue(cursor::MoveTo(cx.saturating_sub(10), cy.saturating_sub(3)))?;
     out.queue(style::SetForegroundColor(Color::White))?;
     out.queue(Print("Select difficulty:"))?;
@@ -110,6 +194,24 @@ fn show_menu<W: Write>(
         out.queue(Print(format!(" — {}", desc)))?;
     }
 
+    // Custom profiles continue the numbering after the built-in levels and
+    // push the rest of the menu down.
+    let profiles = &profiles[..profiles.len().min(MAX_MENU_PROFILES)];
+    for (i, profile) in profiles.iter().enumerate() {
+        let row = cy + 3 + i as u16;
+        out.queue(cursor::MoveTo(cx.saturating_sub(10), row))?;
+        out.queue(style::SetForegroundColor(Color::DarkGrey))?;
+        out.queue(Print(format!("[{}] ", i + 5)))?;
+        out.queue(style::SetForegroundColor(Color::Cyan))?;
+        out.queue(Print(format!("{:<8}", profile.difficulty.name)))?;
+        out.queue(style::SetForegroundColor(Color::DarkGrey))?;
+        out.queue(Print(format!(
+            " — custom, based on {}",
+            profile.level.as_str()
+        )))?;
+    }
+    let cy = cy + profiles.len() as u16;
+
     // Bonus item legend
     out.queue(cursor::MoveTo(cx.saturating_sub(10), cy + 3))?;
     out.queue(style::SetForegroundColor(Color::DarkGrey))?;
@@ -141,9 +243,43 @@ fn show_menu<W: Write>(
 
     out.queue(cursor::MoveTo(cx.saturating_sub(10), cy + 10))?;
     out.queue(style::SetForegroundColor(Color::DarkGrey))?;
-    out.queue(Print(
-        "← → / A D : Move   F+dir : Fast   W+dir : Warp×10   SPACE : Shoot   Q : Quit",
-    ))?;
+    out.queue(Print(format!(
+        "{}  {} : Move   {}+dir : Fast   {}+dir : Warp×10   {} : Shoot   {} : Pause   {} : Quit",
+        bindings.label(Action::MoveLeft),
+        bindings.label(Action::MoveRight),
+        bindings.hint(Action::Fast),
+        bindings.hint(Action::Warp),
+        bindings.hint(Action::Shoot),
+        bindings.hint(Action::Pause),
+        bindings.hint(Action::Quit),
+    )))?;
+    out.queue(cursor::MoveTo(cx.saturating_sub(10), cy + 11))?;
+    let on_off = |on: bool| if on { "ON " } else { "OFF" };
+    out.queue(Print(format!(
+        "L : Leaderboard   K : Key bindings   M : Mouse control [{}]   C : Co-op [{}]",
+        on_off(store.settings.mouse_control),
+        on_off(coop)
+    )))?;
+    if let Some(port) = hosting {
+        out.queue(cursor::MoveTo(cx.saturating_sub(10), cy + 12))?;
+        out.queue(Print(format!(
+            "Hosting on port {}: player 2 joins with --join <this machine>:{}",
+            port, port
+        )))?;
+    } else if coop {
+        out.queue(cursor::MoveTo(cx.saturating_sub(10), cy + 12))?;
+        out.queue(Print(format!(
+            "Player 2: {}  {} : Move   {} : Shoot",
+            bindings.label(Action::P2MoveLeft),
+            bindings.label(Action::P2MoveRight),
+            bindings.hint(Action::P2Shoot),
+        )))?;
+    }
+    if let Some(notice) = notice {
+        out.queue(cursor::MoveTo(cx.saturating_sub(10), cy + 14))?;
+        out.queue(style::SetForegroundColor(Color::Red))?;
+        out.queue(Print(notice))?;
+    }
 
     out.queue(style::ResetColor)?;
     out.flush()?;
@@ -151,313 +287,1183 @@ fn show_menu<W: Write>(
     // Block until the user makes a choice
     loop {
         if let Ok(Event::Key(KeyEvent { code, .. })) = rx.recv() {
+            let preset =
+                |level: Level| MenuResult::Start(level.clone(), Difficulty::preset(&level));
             match code {
-                KeyCode::Char('1') => return Ok(MenuResult::Start(Level::Easy)),
-                KeyCode::Char('2') => return Ok(MenuResult::Start(Level::Medium)),
-                KeyCode::Char('3') => return Ok(MenuResult::Start(Level::Hard)),
-                KeyCode::Char('4') => return Ok(MenuResult::Start(Level::Extreme)),
-                KeyCode::Char('q') | KeyCode::Char('Q') | KeyCode::Esc => {
-                    return Ok(MenuResult::Quit);
+                KeyCode::Char('1') => return Ok(preset(Level::Easy)),
+                KeyCode::Char('2') => return Ok(preset(Level::Medium)),
+                KeyCode::Char('3') => return Ok(preset(Level::Hard)),
+                KeyCode::Char('4') => return Ok(preset(Level::Extreme)),
+                KeyCode::Char(c @ '5'..='9') => {
+                    if let Some(p) = profiles.get(c as usize - '5' as usize) {
+                        return Ok(MenuResult::Start(p.level.clone(), p.difficulty.clone()));
+                    }
                 }
+                KeyCode::Enter if matches!(saved, Some(Ok(_))) => return Ok(MenuResult::Continue),
+                KeyCode::Char('l') | KeyCode::Char('L') => return Ok(MenuResult::Leaderboard),
+                KeyCode::Char('k') | KeyCode::Char('K') => return Ok(MenuResult::Keys),
+                KeyCode::Char('m') | KeyCode::Char('M') => return Ok(MenuResult::ToggleMouse),
+                KeyCode::Char('c') | KeyCode::Char('C') => return Ok(MenuResult::ToggleCoop),
+                _ if bindings.triggers(Action::Quit, code) => return Ok(MenuResult::Quit),
                 _ => {}
             }
         }
     }
 }
 
+/// The menu's line about a scores database that cannot be used, if any.
+fn score_notice(scores: &db::ScoreStore) -> Option<String> {
+    let error = scores.error()?;
+    Some(match scores.pending().len() {
+        0 => format!("Scores: {}", error),
+        1 => format!("Scores: {} — 1 game not saved yet, will retry", error),
+        n => format!("Scores: {} — {} games not saved yet, will retry", error, n),
+    })
+}
+
+// ── Settings ──────────────────────────────────────────────────────────────────
+
+/// The on/off entries of the settings screen, in display order.
+fn settings_toggles(settings: &mut Settings) -> Vec<(&'static str, &mut bool)> {
+    vec![
+        (
+            "Pause when the terminal loses focus",
+            &mut settings.pause_on_focus_loss,
+        ),
+        (
+            "Mouse control (ship follows the pointer)",
+            &mut settings.mouse_control,
+        ),
+    ]
+}
+
+/// Let the player edit their settings until Esc; changes are saved as they
+/// are made.
+fn show_settings<W: Write>(
+    out: &mut W,
+    rx: &mpsc::Receiver<Event>,
+    store: &mut SettingsStore,
+) -> std::io::Result<()> {
+    let mut selected = 0;
+    loop {
+        out.queue(terminal::Clear(terminal::ClearType::All))?;
+        let (width, height) = terminal::size()?;
+        let cx = width / 2;
+        let top = (height / 2).saturating_sub(4);
+        let left = cx.saturating_sub(22);
+
+        let title = "SETTINGS";
+        out.queue(cursor::MoveTo(
+            cx.saturating_sub(title.len() as u16 / 2),
+            top,
+        ))?;
+        out.queue(style::SetForegroundColor(Color::Cyan))?;
+        out.queue(Print(title))?;
+
+        let toggles = settings_toggles(&mut store.settings);
+        let count = toggles.len();
+        for (i, (label, value)) in toggles.into_iter().enumerate() {
+            let (marker, color) = if i == selected {
+                ("▶ ", Color::Yellow)
+            } else {
+                ("  ", Color::White)
+            };
+            out.queue(cursor::MoveTo(left, top + 2 + i as u16))?;
+            out.queue(style::SetForegroundColor(color))?;
+            out.queue(Print(format!(
+                "{}{:<38}{}",
+                marker,
+                label,
+                if *value { "[ON ]" } else { "[OFF]" }
+            )))?;
+        }
+
+        out.queue(cursor::MoveTo(left, top + 3 + count as u16))?;
+        out.queue(style::SetForegroundColor(Color::DarkGrey))?;
+        out.queue(Print("↑↓ Select   ENTER / SPACE Toggle   ESC Back"))?;
+        out.queue(style::ResetColor)?;
+        out.flush()?;
+
+        if let Ok(Event::Key(KeyEvent {
+            code,
+            kind: KeyEventKind::Press,
+            ..
+        })) = rx.recv()
+        {
+            match code {
+                KeyCode::Up => selected = (selected + count - 1) % count,
+                KeyCode::Down => selected = (selected + 1) % count,
+                KeyCode::Enter | KeyCode::Char(' ') => {
+                    if let Some((_, value)) = settings_toggles(&mut store.settings)
+                        .into_iter()
+                        .nth(selected)
+                    {
+                        *value = !*value;
+                    }
+                    let _ = settings::save_settings(&store.path, &store.settings);
+                }
+                KeyCode::Esc | KeyCode::Char('q') | KeyCode::Char('Q') => return Ok(()),
+                _ => {}
+            }
+        }
+    }
+}
+
+/// Report mouse events to the game — or stop doing so.
+fn set_mouse_capture<W: Write>(out: &mut W, on: bool) -> std::io::Result<()> {
+    if on {
+        out.execute(EnableMouseCapture)?;
+    } else {
+        out.execute(DisableMouseCapture)?;
+    }
+    Ok(())
+}
+
+// ── Key bindings ──────────────────────────────────────────────────────────────
+
+/// Let the player rebind actions until Esc; changes are saved as they are
+/// made.
+fn show_bindings<W: Write>(
+    out: &mut W,
+    rx: &mpsc::Receiver<Event>,
+    store: &mut SettingsStore,
+) -> std::io::Result<()> {
+    let count = Action::ALL.len();
+    let mut selected = 0;
+    // Waiting for the new key of the selected action.
+    let mut capturing = false;
+    loop {
+        out.queue(terminal::Clear(terminal::ClearType::All))?;
+        let (width, height) = terminal::size()?;
+        let cx = width / 2;
+        let top = (height / 2).saturating_sub(count as u16 / 2 + 2);
+        let left = cx.saturating_sub(22);
+
+        let title = "KEY BINDINGS";
+        out.queue(cursor::MoveTo(
+            cx.saturating_sub(title.len() as u16 / 2),
+            top,
+        ))?;
+        out.queue(style::SetForegroundColor(Color::Cyan))?;
+        out.queue(Print(title))?;
+
+        for (i, action) in Action::ALL.into_iter().enumerate() {
+            let (marker, color) = if i == selected {
+                ("▶ ", Color::Yellow)
+            } else {
+                ("  ", Color::White)
+            };
+            let keys = if capturing && i == selected {
+                "press a key…".to_string()
+            } else {
+                store.bindings.label(action)
+            };
+            out.queue(cursor::MoveTo(left, top + 2 + i as u16))?;
+            out.queue(style::SetForegroundColor(color))?;
+            out.queue(Print(format!("{}{:<26}{}", marker, action.label(), keys)))?;
+        }
+
+        out.queue(cursor::MoveTo(left, top + 3 + count as u16))?;
+        out.queue(style::SetForegroundColor(Color::DarkGrey))?;
+        out.queue(Print(if capturing {
+            "Press the new key   ESC Cancel"
+        } else {
+            "↑↓ Select   ENTER Rebind   BACKSPACE Default   ESC Back"
+        }))?;
+        out.queue(style::ResetColor)?;
+        out.flush()?;
+
+        let Ok(Event::Key(KeyEvent {
+            code,
+            kind: KeyEventKind::Press,
+            ..
+        })) = rx.recv()
+        else {
+            continue;
+        };
+        let action = Action::ALL[selected];
+        if capturing {
+            capturing = false;
+            // Keys without a name (media keys, modifiers) cannot be saved.
+            if code != KeyCode::Esc && bindings::key_name(code).is_some() {
+                store.bindings.rebind(action, code);
+                let _ = bindings::save_bindings(&store.bindings_path, &store.bindings);
+            }
+            continue;
+        }
+        match code {
+            KeyCode::Up => selected = (selected + count - 1) % count,
+            KeyCode::Down => selected = (selected + 1) % count,
+            KeyCode::Enter => capturing = true,
+            KeyCode::Backspace | KeyCode::Delete => {
+                store.bindings.reset(action);
+                let _ = bindings::save_bindings(&store.bindings_path, &store.bindings);
+            }
+            KeyCode::Esc | KeyCode::Char('q') | KeyCode::Char('Q') => return Ok(()),
+            _ => {}
+        }
+    }
+}
+
+// ── Leaderboard ───────────────────────────────────────────────────────────────
+
+/// Top scores shown per page of the leaderboard.
+const LEADERBOARD_PAGE: u32 = 10;
+/// Recent games shown per page under it.
+const HISTORY_PAGE: u32 = 5;
+
+/// Browse the best scores and `username`'s own games, one difficulty at a
+/// time: the built-in levels, then the custom profiles, each solo or co-op.
+fn show_leaderboard<W: Write>(
+    out: &mut W,
+    rx: &mpsc::Receiver<Event>,
+    conn: Option<&Connection>,
+    username: &str,
+    profiles: &[Profile],
+) -> std::io::Result<()> {
+    let boards: Vec<(Level, Difficulty)> =
+        [Level::Easy, Level::Medium, Level::Hard, Level::Extreme]
+            .into_iter()
+            .map(|level| (level.clone(), Difficulty::preset(&level)))
+            .chain(
+                profiles
+                    .iter()
+                    .map(|p| (p.level.clone(), p.difficulty.clone())),
+            )
+            .collect();
+    let mut board = 0;
+    let mut coop = false;
+    let mut offset = 0;
+    let mut history_offset = 0;
+    loop {
+        let (level, difficulty) = &boards[board];
+        let key = if coop {
+            profiles::coop_score_key(level, difficulty)
+        } else {
+            profiles::score_key(level, difficulty)
+        };
+        let top = conn.map(|c| db::top_scores_page(c, &key, offset, LEADERBOARD_PAGE));
+        let mine = conn.map(|c| db::player_rank(c, &key, username));
+        let history =
+            conn.map(|c| db::recent_games_page(c, &key, username, history_offset, HISTORY_PAGE));
+        let top_total = match &top {
+            Some(Ok(page)) => page.total,
+            _ => 0,
+        };
+        let history_total = match &history {
+            Some(Ok(page)) => page.total,
+            _ => 0,
+        };
+
+        let mut lines: Vec<(String, Color)> = Vec::new();
+        let players = if coop { "co-op" } else { "solo" };
+        lines.push((
+            format!("◀ {} ({}) ▶", difficulty.name, players),
+            Color::Yellow,
+        ));
+        lines.push((String::new(), Color::White));
+        match &top {
+            None => lines.push(("No score database.".to_string(), Color::DarkGrey)),
+            Some(Err(e)) => lines.push((format!("Could not read scores: {}", e), Color::Red)),
+            Some(Ok(page)) if page.rows.is_empty() => {
+                lines.push(("No scores yet.".to_string(), Color::DarkGrey));
+            }
+            Some(Ok(page)) => {
+                lines.push((
+                    format!(
+                        "{:>4}  {:<16}{:>8}{:>6}  {}",
+                        "#", "Player", "Points", "Wave", "Date"
+                    ),
+                    Color::DarkGrey,
+                ));
+                for score in &page.rows {
+                    let color = if score.username == username {
+                        Color::Yellow
+                    } else {
+                        Color::White
+                    };
+                    let name: String = score.username.chars().take(15).collect();
+                    lines.push((
+                        format!(
+                            "{:>4}  {:<16}{:>8}{:>6}  {}",
+                            score.rank, name, score.points, score.wave, score.date
+                        ),
+                        color,
+                    ));
+                }
+                lines.push((
+                    format!(
+                        "Page {} of {}",
+                        page.offset / LEADERBOARD_PAGE + 1,
+                        page.total.div_ceil(LEADERBOARD_PAGE)
+                    ),
+                    Color::DarkGrey,
+                ));
+            }
+        }
+        lines.push((String::new(), Color::White));
+        if let Some(Ok(Some(me))) = &mine {
+            lines.push((
+                format!(
+                    "Your best: #{} with {} pts (wave {})",
+                    me.rank, me.points, me.wave
+                ),
+                Color::Green,
+            ));
+        }
+        if let Some(Ok(history)) = &history {
+            if !history.rows.is_empty() {
+                lines.push((
+                    format!(
+                        "Your recent games ({}–{} of {}):",
+                        history.offset + 1,
+                        history.offset + history.rows.len() as u32,
+                        history.total
+                    ),
+                    Color::White,
+                ));
+                for game in &history.rows {
+                    let p2 = game
+                        .p2_points
+                        .map_or(String::new(), |p| format!("  (P2 {})", p));
+                    lines.push((
+                        format!(
+                            "  {}  {:>8} pts  wave {:<3}{}",
+                            game.date, game.points, game.wave, p2
+                        ),
+                        Color::DarkGrey,
+                    ));
+                }
+            }
+        }
+        lines.push((String::new(), Color::White));
+        lines.push((
+            "←→ Difficulty   ↑↓ Page   PgUp PgDn Games   C Co-op   ESC Back".to_string(),
+            Color::DarkGrey,
+        ));
+
+        out.queue(terminal::Clear(terminal::ClearType::All))?;
+        let (width, height) = terminal::size()?;
+        let cx = width / 2;
+        let top_row = (height / 2).saturating_sub(lines.len() as u16 / 2 + 2);
+        let left = cx.saturating_sub(24);
+        let title = "LEADERBOARD";
+        out.queue(cursor::MoveTo(
+            cx.saturating_sub(title.len() as u16 / 2),
+            top_row,
+        ))?;
+        out.queue(style::SetForegroundColor(Color::Cyan))?;
+        out.queue(Print(title))?;
+        for (i, (text, color)) in lines.iter().enumerate() {
+            out.queue(cursor::MoveTo(left, top_row + 2 + i as u16))?;
+            out.queue(style::SetForegroundColor(*color))?;
+            out.queue(Print(text))?;
+        }
+        out.queue(style::ResetColor)?;
+        out.flush()?;
+
+        let Ok(Event::Key(KeyEvent {
+            code,
+            kind: KeyEventKind::Press,
+            ..
+        })) = rx.recv()
+        else {
+            continue;
+        };
+        let count = boards.len();
+        match code {
+            KeyCode::Left | KeyCode::Right => {
+                board = if code == KeyCode::Left {
+                    (board + count - 1) % count
+                } else {
+                    (board + 1) % count
+                };
+                offset = 0;
+                history_offset = 0;
+            }
+            KeyCode::Char('c') | KeyCode::Char('C') => {
+                coop = !coop;
+                offset = 0;
+                history_offset = 0;
+            }
+            KeyCode::Up => offset = offset.saturating_sub(LEADERBOARD_PAGE),
+            KeyCode::Down if offset + LEADERBOARD_PAGE < top_total => offset += LEADERBOARD_PAGE,
+            KeyCode::PageUp => history_offset = history_offset.saturating_sub(HISTORY_PAGE),
+            KeyCode::PageDown if history_offset + HISTORY_PAGE < history_total => {
+                history_offset += HISTORY_PAGE
+            }
+            KeyCode::Esc | KeyCode::Char('q') | KeyCode::Char('Q') => return Ok(()),
+            _ => {}
+        }
+    }
+}
+
 // ── Game loop ─────────────────────────────────────────────────────────────────
 
-/// Returns `true` → quit program,  `false` → back to menu.
+/// Runs one game until the player quits, leaves it from the pause menu or
+/// asks for another after game over.
 ///
-/// Input model: instead of acting on each key event individually, we maintain
-/// a `keys` map that records each key's `KeyState` (Held or Released).  Each
-/// frame we check which keys are still "fresh" and apply all their effects
-/// simultaneously.  This allows Space + A/D to be held at the same time with
-/// no interference.
+/// Input model: key events go through an `InputController`, which maps them
+/// to bound actions and tracks which are held.  Presses come back as
+/// `Intent`s handled here; at the end of each frame the controller reports
+/// the movement held keys produce.
 ///
-/// Works on two classes of terminal:
-/// * **Keyboard-enhancement capable** (Ghostty, kitty, etc.): proper
-///   `Press` / `Repeat` / `Release` events → keys transition to Released on release.
-/// * **Classic terminals**: only `Press` events (OS key-repeat shows as
-///   repeated `Press`).  Keys expire naturally after `HOLD_WINDOW` frames of
-///   silence, which is shorter than the OS repeat interval, so the key stays
-///   live while it is actively generating repeats.
+/// `peers` are whoever follows the game over the network.  Player two's
+/// inputs, when hosting, are applied alongside the local ones; every frame is
+/// sent to them and to any viewers.  A broken link to player two takes their
+/// ship out and is dropped.
+#[allow(clippy::too_many_arguments)]
 fn game_loop<W: Write>(
     out: &mut W,
     state: &mut EntireGameStateInfo,
     rx: &mpsc::Receiver<Event>,
     demo_mode: bool,
-) -> std::io::Result<bool> {
-    let mut rng = thread_rng();
-
-    let mut keys: HashMap<KeyCode, KeyState> = HashMap::new();
-    let mut move_cooldown: f64 = 0.0;
-    let mut warp_cooldown: f64 = 0.0;
-    // Tracks which direction is actively held. Set to Left/Right on Repeat events
-    // (or rapid re-Press / F/W warp), cleared to None on Release. Ternary so
-    // opposite-side conflicts are impossible by construction.
-    let mut held_dir = HeldDir::None;
+    mut rng: GameRng,
+    mut replay_mode: ReplayMode,
+    peers: &mut Peers,
+    store: &mut SettingsStore,
+) -> std::io::Result<LoopExit> {
+    let mut input = InputController::new(store.bindings.clone());
+    // Replays take no input, and nobody holds the mouse in demo mode.
+    let mouse =
+        store.settings.mouse_control && !demo_mode && !matches!(replay_mode, ReplayMode::Play(..));
+    input.set_mouse(mouse);
     let mut frame: u64 = 0;
     let mut first_frame = true;
+    // Frames go through `term`; other screens borrow its writer.
+    let mut term = TerminalRenderer::new(out);
+    set_mouse_capture(term.writer(), mouse)?;
+    // Every input applied since the last tick, in order, for the replay log.
+    // Kept across paused frames so a resize made meanwhile is not lost.
+    let mut actions: Vec<PlayerAction> = Vec::new();
+
+    // The terminal may already be too small when the game starts; a replay
+    // keeps its recorded size regardless.
+    let mut term_size = terminal::size()?;
+    let mut too_small =
+        !matches!(replay_mode, ReplayMode::Play(..)) && !playfield_fits(term_size.0, term_size.1);
+    if too_small {
+        *state = pause(state);
+    }
 
     loop {
         let frame_start = Instant::now();
         frame += 1;
 
-        // ── Drain all pending input events (non-blocking) ─────────────────────
-        // Release events are deferred to the end of the drain so that a straggler
-        // Repeat arriving in the same OS-queue flush cannot re-enable a key that
-        // was just released.
-        let mut deferred_releases: Vec<KeyCode> = Vec::new();
+        if let ReplayMode::Play(recording, check) = &mut replay_mode {
+            // Playback: the keyboard may only quit; inputs come from the file.
+            while let Ok(ev) = rx.try_recv() {
+                if let Event::Resize(..) = ev {
+                    first_frame = true;
+                }
+                if let Event::Key(KeyEvent {
+                    code,
+                    kind: KeyEventKind::Press,
+                    modifiers,
+                    ..
+                }) = ev
+                {
+                    let ctrl_c =
+                        code == KeyCode::Char('c') && modifiers.contains(KeyModifiers::CONTROL);
+                    if ctrl_c || input.bindings().triggers(Action::Quit, code) {
+                        return Ok(LoopExit::Quit);
+                    }
+                }
+            }
 
-        while let Ok(Event::Key(KeyEvent {
-            code,
-            kind,
-            modifiers,
-            ..
-        })) = rx.try_recv()
-        {
-            match kind {
-                // Press: record key + handle one-shot actions
-                KeyEventKind::Press => {
-                    match code {
-                        KeyCode::Char('q') | KeyCode::Char('Q') | KeyCode::Esc => {
-                            return Ok(true);
-                        }
-                        KeyCode::Char('c') if modifiers.contains(KeyModifiers::CONTROL) => {
-                            return Ok(true);
-                        }
-                        KeyCode::Char('r') | KeyCode::Char('R')
-                            if state.status == GameStatus::GameOver =>
-                        {
-                            return Ok(false);
-                        }
-                        // Space: single-shot on press — not tracked in key_frame so it
-                        // never interferes with held movement keys.
-                        KeyCode::Char(' ') if state.status == GameStatus::Playing => {
-                            *state = player_shoot(state);
-                        }
-                        // Movement keys: move one step immediately on press.
-                        // For classic terminals the OS sends repeated Press events
-                        // instead of Repeat; treat a rapid second Press (within 4
-                        // frames) as a Repeat so continuous movement still works.
-                        KeyCode::Left | KeyCode::Char('a') | KeyCode::Char('A')
-                            if state.status == GameStatus::Playing =>
-                        {
-                            let rapid = keys
-                                .get(&code)
-                                .and_then(|s| s.as_held_frame())
-                                .is_some_and(|last| frame.saturating_sub(last) <= 4);
-                            if rapid {
-                                held_dir = HeldDir::Left;
-                            } else {
-                                *state = move_player_left(state);
-                                held_dir = HeldDir::None;
-                            }
-                            keys.insert(code, KeyState::Held(frame));
-                        }
-                        KeyCode::Right | KeyCode::Char('d') | KeyCode::Char('D')
-                            if state.status == GameStatus::Playing =>
-                        {
-                            let rapid = keys
-                                .get(&code)
-                                .and_then(|s| s.as_held_frame())
-                                .is_some_and(|last| frame.saturating_sub(last) <= 4);
-                            if rapid {
-                                held_dir = HeldDir::Right;
-                            } else {
-                                *state = move_player_right(state);
-                                held_dir = HeldDir::None;
-                            }
-                            keys.insert(code, KeyState::Held(frame));
+            let index = check.frames_checked;
+            if state.status == GameStatus::Playing {
+                if let Some(recorded) = recording.frames.get(index) {
+                    *state = replay::step(state, recorded, &mut rng);
+                    check.check(index, recorded, state);
+                } else if state.cheer_msg.is_none() {
+                    // Out of frames while still Playing: the recording ended
+                    // mid-run (Q pressed).  Freeze and say so.
+                    state.cheer_msg = Some(("Replay finished — Q to exit".to_string(), u32::MAX));
+                }
+            }
+
+            display::render(&mut term, state, input.bindings(), first_frame)?;
+            first_frame = false;
+
+            let target = if state.slow_mo { FRAME * 4 } else { FRAME };
+            let elapsed = frame_start.elapsed();
+            if elapsed < target {
+                std::thread::sleep(target - elapsed);
+            }
+            continue;
+        }
+
+        // ── Drain all pending input events (non-blocking) ─────────────────────
+        while let Ok(ev) = rx.try_recv() {
+            if ev == Event::FocusLost && store.settings.pause_on_focus_loss {
+                *state = pause(state);
+                continue;
+            }
+            if let Event::Resize(width, height) = ev {
+                let was_too_small = too_small;
+                term_size = (width, height);
+                too_small = !playfield_fits(width, height);
+                if too_small {
+                    *state = pause(state);
+                } else {
+                    if (width, height) != (state.width, state.height) {
+                        perform(state, &mut actions, PlayerAction::Resize(width, height));
+                    }
+                    // Nobody is at the keyboard in demo mode to pick "Resume".
+                    if was_too_small && demo_mode {
+                        *state = resume(state);
+                    }
+                }
+                first_frame = true;
+                continue;
+            }
+            let intents = match ev {
+                Event::Key(key) => input.key_event(frame, key, state),
+                Event::Mouse(mouse) => input.mouse_event(frame, mouse, state),
+                _ => continue,
+            };
+            for intent in intents {
+                match intent {
+                    // Only quitting works until the terminal is big enough.
+                    _ if too_small && intent != Intent::Quit => {}
+                    // Player two is at the other end of the network.
+                    Intent::Act(
+                        PlayerAction::P2MoveLeft(_)
+                        | PlayerAction::P2MoveRight(_)
+                        | PlayerAction::P2Shoot,
+                    ) if peers.host.is_some() => {}
+                    Intent::Act(action) => perform(state, &mut actions, action),
+                    Intent::Pause => *state = pause(state),
+                    Intent::Resume => *state = resume(state),
+                    Intent::Select(delta) => *state = pause_select(state, delta),
+                    Intent::Confirm => match &state.status {
+                        GameStatus::Paused(PauseItem::Restart) => {
+                            return Ok(LoopExit::Restart);
                         }
-                        // W: instant warp 10 steps on keydown (if direction held).
-                        KeyCode::Char('w') | KeyCode::Char('W')
-                            if state.status == GameStatus::Playing =>
-                        {
-                            // Use held_dir as fallback: when F/W is pressed the
-                            // terminal may stop sending Repeat for direction keys, making
-                            // is_held() expire before the key is actually released.
-                            let dir_left = held_dir == HeldDir::Left
-                                || keys.get(&KeyCode::Left).is_some_and(|s| s.is_held(frame))
-                                || keys
-                                    .get(&KeyCode::Char('a'))
-                                    .is_some_and(|s| s.is_held(frame))
-                                || keys
-                                    .get(&KeyCode::Char('A'))
-                                    .is_some_and(|s| s.is_held(frame));
-                            let dir_right = held_dir == HeldDir::Right
-                                || keys.get(&KeyCode::Right).is_some_and(|s| s.is_held(frame))
-                                || keys
-                                    .get(&KeyCode::Char('d'))
-                                    .is_some_and(|s| s.is_held(frame))
-                                || keys
-                                    .get(&KeyCode::Char('D'))
-                                    .is_some_and(|s| s.is_held(frame));
-                            if dir_left {
-                                *state = move_player_left_n(state, 10);
-                                warp_cooldown = WARP_COOLDOWN;
-                            } else if dir_right {
-                                *state = move_player_right_n(state, 10);
-                                warp_cooldown = WARP_COOLDOWN;
-                            }
-                            keys.insert(code, KeyState::Held(frame));
+                        GameStatus::Paused(PauseItem::Settings) => {
+                            show_settings(term.writer(), rx, store)?;
+                            let mouse = store.settings.mouse_control && !demo_mode;
+                            input.set_mouse(mouse);
+                            set_mouse_capture(term.writer(), mouse)?;
+                            first_frame = true;
                         }
-                        // F: instant 2-step move on keydown (if direction held).
-                        KeyCode::Char('f') | KeyCode::Char('F')
-                            if state.status == GameStatus::Playing =>
-                        {
-                            let dir_left = held_dir == HeldDir::Left
-                                || keys.get(&KeyCode::Left).is_some_and(|s| s.is_held(frame))
-                                || keys
-                                    .get(&KeyCode::Char('a'))
-                                    .is_some_and(|s| s.is_held(frame))
-                                || keys
-                                    .get(&KeyCode::Char('A'))
-                                    .is_some_and(|s| s.is_held(frame));
-                            let dir_right = held_dir == HeldDir::Right
-                                || keys.get(&KeyCode::Right).is_some_and(|s| s.is_held(frame))
-                                || keys
-                                    .get(&KeyCode::Char('d'))
-                                    .is_some_and(|s| s.is_held(frame))
-                                || keys
-                                    .get(&KeyCode::Char('D'))
-                                    .is_some_and(|s| s.is_held(frame));
-                            if dir_left {
-                                *state = move_player_left_n(state, 2);
-                            } else if dir_right {
-                                *state = move_player_right_n(state, 2);
+                        GameStatus::Paused(PauseItem::QuitToMenu) => {
+                            let offer = !demo_mode && peers.host.is_none();
+                            if leave_game(term.writer(), rx, state, &rng, offer)? {
+                                return Ok(LoopExit::Menu);
                             }
-                            keys.insert(code, KeyState::Held(frame));
-                        }
-                        // Backtick: toggle debug overlay.
-                        KeyCode::Char('`') => {
-                            state.debug_mode = !state.debug_mode;
+                            first_frame = true;
                         }
-                        // G: toggle god mode (only while debug is on).
-                        KeyCode::Char('g') | KeyCode::Char('G') if state.debug_mode => {
-                            state.god_mode = !state.god_mode;
-                        }
-                        // S: toggle slow-mo (only while debug is on).
-                        KeyCode::Char('s') | KeyCode::Char('S') if state.debug_mode => {
-                            state.slow_mo = !state.slow_mo;
-                        }
-                        _ => {
-                            keys.insert(code, KeyState::Held(frame));
+                        _ => *state = resume(state),
+                    },
+                    Intent::Restart => return Ok(LoopExit::Menu),
+                    Intent::Quit => {
+                        let offer = !demo_mode && peers.host.is_none();
+                        if leave_game(term.writer(), rx, state, &rng, offer)? {
+                            return Ok(LoopExit::Quit);
                         }
+                        first_frame = true;
                     }
                 }
-                // Repeat: refresh timestamp and mark direction as held.
-                KeyEventKind::Repeat => {
-                    match code {
-                        KeyCode::Left | KeyCode::Char('a') | KeyCode::Char('A') => {
-                            held_dir = HeldDir::Left;
-                        }
-                        KeyCode::Right | KeyCode::Char('d') | KeyCode::Char('D') => {
-                            held_dir = HeldDir::Right;
-                        }
-                        _ => {}
-                    }
-                    keys.insert(code, KeyState::Held(frame));
-                }
-                // Release: defer until all Press/Repeat events this frame are handled.
-                KeyEventKind::Release => {
-                    deferred_releases.push(code);
-                }
             }
         }
 
-        // Apply deferred releases — runs after all Repeat events this cycle.
-        for code in deferred_releases {
-            match code {
-                KeyCode::Left | KeyCode::Char('a') | KeyCode::Char('A')
-                    if held_dir == HeldDir::Left =>
-                {
-                    held_dir = HeldDir::None;
+        // ── Apply player two's inputs from the network ────────────────────────
+        if let Some(host) = peers.host.as_mut() {
+            match host.receive() {
+                // Inputs that arrive while the game stands still are dropped.
+                Ok(remote) if state.status == GameStatus::Playing => {
+                    for action in remote {
+                        perform(state, &mut actions, action);
+                    }
                 }
-                KeyCode::Right | KeyCode::Char('d') | KeyCode::Char('D')
-                    if held_dir == HeldDir::Right =>
-                {
-                    held_dir = HeldDir::None;
+                Ok(_) => {}
+                Err(_) => {
+                    peers.host = None;
+                    perform(state, &mut actions, PlayerAction::P2Leave);
                 }
-                _ => {}
             }
-            keys.insert(code, KeyState::Released(frame));
         }
 
         // ── Apply Demo Mode actions ───────────────────────────────────────────
         if demo_mode {
             if state.status == GameStatus::GameOver {
-                return Ok(false); // auto-restart: run() will loop back
+                return Ok(LoopExit::Menu); // auto-restart: run() will loop back
             }
             if state.status == GameStatus::Playing {
-                *state = shooting_game::demo::update_demo(state);
+                for action in shooting_game::demo::demo_actions(state) {
+                    perform(state, &mut actions, action);
+                }
             }
         }
 
         // ── Apply held-key actions every frame ────────────────────────────────
+        for action in input.end_frame(frame, state) {
+            perform(state, &mut actions, action);
+        }
+
+        let mut ticked = None;
         if state.status == GameStatus::Playing {
-            let held = |kc: &KeyCode| keys.get(kc).is_some_and(|s| s.is_held(frame));
-            let left =
-                held(&KeyCode::Left) || held(&KeyCode::Char('a')) || held(&KeyCode::Char('A'));
-            let right =
-                held(&KeyCode::Right) || held(&KeyCode::Char('d')) || held(&KeyCode::Char('D'));
-            let fast = held(&KeyCode::Char('f')) || held(&KeyCode::Char('F'));
-            let warp = held(&KeyCode::Char('w')) || held(&KeyCode::Char('W'));
-
-            // held_dir is the authoritative "direction held" signal: set on Repeat events,
-            // cleared only on Release. is_held() alone is unreliable here because terminals
-            // stop sending direction-key Repeat events while F or W is also held.
-            let dir_left = left || held_dir == HeldDir::Left;
-            let dir_right = right || held_dir == HeldDir::Right;
-
-            if warp && warp_cooldown <= 0.0 {
-                if dir_left {
-                    *state = move_player_left_n(state, 10);
-                    warp_cooldown = WARP_COOLDOWN;
-                } else if dir_right {
-                    *state = move_player_right_n(state, 10);
-                    warp_cooldown = WARP_COOLDOWN;
+            *state = tick(state, &mut rng);
+            let applied = std::mem::take(&mut actions);
+            if peers.any() {
+                ticked = Some(ReplayFrame {
+                    actions: applied.clone(),
+                    hash: replay::state_hash(state),
+                });
+            }
+            if let ReplayMode::Record(recording) = &mut replay_mode {
+                recording.record(applied, state);
+            }
+        }
+
+        // ── Keep the copies of the game over the network in step ─────────────
+        if let Some(host) = peers.host.as_mut() {
+            let sent = match &ticked {
+                Some(frame) => host.send_frame(frame),
+                None => host.send_idle(),
+            };
+            if sent.is_err() {
+                peers.host = None;
+                perform(state, &mut actions, PlayerAction::P2Leave);
+            }
+        }
+        if let Some(broadcast) = peers.broadcast.as_mut() {
+            match &ticked {
+                Some(frame) => broadcast.send_frame(frame, state, &rng),
+                None => broadcast.send_idle(),
+            }
+        }
+
+        if !too_small {
+            display::render(&mut term, state, input.bindings(), first_frame)?;
+        } else if first_frame {
+            display::render_too_small(&mut term, term_size.0, term_size.1)?;
+        }
+        first_frame = false;
+
+        let target = if state.slow_mo { FRAME * 4 } else { FRAME };
+        let elapsed = frame_start.elapsed();
+        if elapsed < target {
+            std::thread::sleep(target - elapsed);
+        }
+    }
+}
+
+// ── Saved games ───────────────────────────────────────────────────────────────
+
+/// Before leaving a game under way, ask whether to save it for "Continue" on
+/// the menu.  Returns false if the player would rather keep playing.
+/// `offer` is false where a save makes no sense (demo mode, a partner
+/// connected over the network); the game is then left straight away.
+/// A save that fails (read-only directory, full disk) is reported on the
+/// prompt, which stays up to try again, leave anyway or play on.
+fn leave_game<W: Write>(
+    out: &mut W,
+    rx: &mpsc::Receiver<Event>,
+    state: &EntireGameStateInfo,
+    rng: &GameRng,
+    offer: bool,
+) -> std::io::Result<bool> {
+    if !offer || !savegame::can_save(state) {
+        return Ok(true);
+    }
+    let mut error: Option<String> = None;
+    loop {
+        draw_save_prompt(out, error.as_deref())?;
+        let Ok(Event::Key(KeyEvent {
+            code,
+            kind: KeyEventKind::Press,
+            ..
+        })) = rx.recv()
+        else {
+            continue;
+        };
+        match code {
+            KeyCode::Char('y') | KeyCode::Char('Y') => {
+                let game = SaveGame::new(state, rng);
+                match savegame::save(Path::new(savegame::DEFAULT_SAVE_FILE), &game) {
+                    Ok(()) => return Ok(true),
+                    Err(e) => error = Some(format!("Could not save: {}", e)),
                 }
-            } else if fast {
-                if dir_left {
-                    *state = move_player_left_n(state, 2);
-                } else if dir_right {
-                    *state = move_player_right_n(state, 2);
+            }
+            KeyCode::Char('n') | KeyCode::Char('N') => return Ok(true),
+            KeyCode::Esc => return Ok(false),
+            _ => {}
+        }
+    }
+}
+
+fn draw_save_prompt<W: Write>(out: &mut W, error: Option<&str>) -> std::io::Result<()> {
+    out.queue(terminal::Clear(terminal::ClearType::All))?;
+    let (width, height) = terminal::size()?;
+    let mut lines = vec![
+        ("Save this run to continue later?", Color::Cyan),
+        (
+            "Y : Save and leave   N : Leave   Esc : Keep playing",
+            Color::DarkGrey,
+        ),
+    ];
+    if let Some(error) = error {
+        lines.push((error, Color::Red));
+        lines[1].0 = "Y : Try again   N : Leave without saving   Esc : Keep playing";
+    }
+    for (i, (text, color)) in lines.iter().enumerate() {
+        out.queue(cursor::MoveTo(
+            (width / 2).saturating_sub(text.chars().count() as u16 / 2),
+            (height / 2).saturating_sub(1) + i as u16 * 2,
+        ))?;
+        out.queue(style::SetForegroundColor(*color))?;
+        out.queue(Print(text))?;
+    }
+    out.queue(style::ResetColor)?;
+    out.flush()
+}
+
+// ── Network play ──────────────────────────────────────────────────────────────
+
+/// Show a waiting screen until a second player connects, then start `game`
+/// on their end.  Quit cancels and returns `None`.
+fn wait_for_player<W: Write>(
+    out: &mut W,
+    rx: &mpsc::Receiver<Event>,
+    listener: &TcpListener,
+    game: &Replay,
+    store: &SettingsStore,
+) -> std::io::Result<Option<Host>> {
+    let port = listener.local_addr()?.port();
+    out.queue(terminal::Clear(terminal::ClearType::All))?;
+    let (width, height) = terminal::size()?;
+    let lines = [
+        ("Waiting for player 2…".to_string(), Color::Cyan),
+        (
+            format!("On the other terminal: --join <this machine>:{}", port),
+            Color::White,
+        ),
+        (
+            format!("{} : Cancel", store.bindings.hint(Action::Quit)),
+            Color::DarkGrey,
+        ),
+    ];
+    for (i, (text, color)) in lines.iter().enumerate() {
+        out.queue(cursor::MoveTo(
+            (width / 2).saturating_sub(text.chars().count() as u16 / 2),
+            (height / 2).saturating_sub(1) + i as u16 * 2,
+        ))?;
+        out.queue(style::SetForegroundColor(*color))?;
+        out.queue(Print(text))?;
+    }
+    out.queue(style::ResetColor)?;
+    out.flush()?;
+
+    // Polled, so a key press can cancel the wait.
+    listener.set_nonblocking(true)?;
+    loop {
+        while let Ok(ev) = rx.try_recv() {
+            if let Event::Key(KeyEvent {
+                code,
+                kind: KeyEventKind::Press,
+                ..
+            }) = ev
+            {
+                if store.bindings.triggers(Action::Quit, code) {
+                    return Ok(None);
                 }
-            } else if move_cooldown <= 0.0 {
-                if held_dir == HeldDir::Left {
-                    *state = move_player_left(state);
-                    move_cooldown = MOVE_COOLDOWN;
-                } else if held_dir == HeldDir::Right {
-                    *state = move_player_right(state);
-                    move_cooldown = MOVE_COOLDOWN;
+            }
+        }
+        match listener.accept() {
+            // Something that is not a compatible client: keep waiting.
+            Ok((stream, _)) => {
+                if let Ok(mut host) = Host::accept(stream) {
+                    if host.start(game).is_ok() {
+                        return Ok(Some(host));
+                    }
                 }
             }
+            Err(e) if e.kind() == std::io::ErrorKind::WouldBlock => {
+                thread::sleep(Duration::from_millis(50));
+            }
+            Err(e) => return Err(e),
         }
+    }
+}
 
-        move_cooldown = (move_cooldown - 1.0).max(0.0);
-        warp_cooldown = (warp_cooldown - 1.0).max(0.0);
+/// Fly player two in a game hosted elsewhere until either side leaves.
+/// Returns what ended the session, for the report printed on exit.
+fn play_network<W: Write>(
+    out: &mut W,
+    rx: &mpsc::Receiver<Event>,
+    mut client: Client,
+    store: &SettingsStore,
+) -> std::io::Result<String> {
+    let mut input = InputController::new(store.bindings.clone());
+    // The pointer steers by player one's ship, which is not ours.
+    input.set_mouse(false);
+    let mut term = TerminalRenderer::new(out);
+    let mut first_frame = true;
+    let mut frame: u64 = 0;
+    let mut view = client.view();
+    let mut ended: Option<net::NetError> = None;
 
-        if state.status == GameStatus::Playing {
-            *state = tick(state, &mut rng);
+    loop {
+        let frame_start = Instant::now();
+        frame += 1;
+
+        // Everything but moving, shooting and leaving is the host's call.
+        let mut local = Vec::new();
+        while let Ok(ev) = rx.try_recv() {
+            let intents = match ev {
+                Event::Resize(..) => {
+                    first_frame = true;
+                    continue;
+                }
+                Event::Key(key) => input.key_event(frame, key, &view),
+                _ => continue,
+            };
+            for intent in intents {
+                match intent {
+                    Intent::Act(action) => local.push(action),
+                    Intent::Quit | Intent::Restart => {
+                        client.leave();
+                        return Ok(match ended {
+                            Some(e) => format!("network game ended: {}", e),
+                            None => "left the network game".to_string(),
+                        });
+                    }
+                    _ => {}
+                }
+            }
         }
+        local.extend(input.end_frame(frame, &view));
 
-        display::render(out, state, first_frame)?;
+        if ended.is_none() {
+            if let Err(e) = client.send(&local).and_then(|()| client.sync()) {
+                ended = Some(e);
+            }
+        }
+
+        view = client.view();
+        let (width, height) = terminal::size()?;
+        if playfield_fits(width, height) {
+            // Input goes by the host's playfield; only the picture is fitted.
+            let mut shown = if (width, height) == (view.width, view.height) {
+                view.clone()
+            } else {
+                resize(&view, width, height)
+            };
+            let notice = match &ended {
+                Some(net::NetError::Left) => Some("HOST LEFT — Q to quit"),
+                Some(net::NetError::Lost(_)) => Some("CONNECTION LOST — Q to quit"),
+                Some(net::NetError::Desync(_)) => Some("OUT OF SYNC WITH HOST — Q to quit"),
+                None if client.host_idle() && view.status == GameStatus::Playing => {
+                    Some("HOST PAUSED")
+                }
+                None => None,
+            };
+            if let Some(notice) = notice {
+                shown.cheer_msg = Some((notice.to_string(), u32::MAX));
+            }
+            display::render(&mut term, &shown, input.bindings(), first_frame)?;
+        } else {
+            display::render_too_small(&mut term, width, height)?;
+        }
         first_frame = false;
 
-        let target = if state.slow_mo { FRAME * 4 } else { FRAME };
         let elapsed = frame_start.elapsed();
-        if elapsed < target {
-            std::thread::sleep(target - elapsed);
+        if elapsed < FRAME {
+            std::thread::sleep(FRAME - elapsed);
+        }
+    }
+}
+
+/// Show the games broadcast elsewhere, fitted to this terminal, until Quit.
+/// Returns what ended the session, for the report printed on exit.
+fn watch<W: Write>(
+    out: &mut W,
+    rx: &mpsc::Receiver<Event>,
+    mut viewer: Viewer,
+    store: &SettingsStore,
+) -> std::io::Result<String> {
+    let mut term = TerminalRenderer::new(out);
+    let mut first_frame = true;
+    let mut ended: Option<net::NetError> = None;
+
+    loop {
+        let frame_start = Instant::now();
+
+        while let Ok(ev) = rx.try_recv() {
+            match ev {
+                Event::Resize(..) => first_frame = true,
+                Event::Key(KeyEvent {
+                    code,
+                    kind: KeyEventKind::Press,
+                    modifiers,
+                    ..
+                }) => {
+                    let ctrl_c =
+                        code == KeyCode::Char('c') && modifiers.contains(KeyModifiers::CONTROL);
+                    if ctrl_c || store.bindings.triggers(Action::Quit, code) {
+                        return Ok(match ended {
+                            Some(e) => format!("broadcast ended: {}", e),
+                            None => "stopped watching".to_string(),
+                        });
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        if ended.is_none() {
+            if let Err(e) = viewer.sync() {
+                ended = Some(e);
+            }
+        }
+
+        let (width, height) = terminal::size()?;
+        match viewer.state() {
+            _ if !playfield_fits(width, height) => {
+                display::render_too_small(&mut term, width, height)?;
+            }
+            Some(state) => {
+                // Drawn at our size the way a resize would place it.
+                let mut view = if (width, height) == (state.width, state.height) {
+                    state.clone()
+                } else {
+                    resize(state, width, height)
+                };
+                let notice = match &ended {
+                    Some(net::NetError::Desync(_)) => Some("OUT OF SYNC — Q to quit"),
+                    Some(_) => Some("BROADCAST ENDED — Q to quit"),
+                    None if viewer.idle() && view.status == GameStatus::Playing => Some("PAUSED"),
+                    None => None,
+                };
+                if let Some(notice) = notice {
+                    view.cheer_msg = Some((notice.to_string(), u32::MAX));
+                }
+                display::render(&mut term, &view, &store.bindings, first_frame)?;
+            }
+            None => {
+                let text = match &ended {
+                    Some(_) => "Broadcast ended — Q to quit",
+                    None => "Waiting for the next game…",
+                };
+                let mut buf = Buffer::new(width, height);
+                buf.print(
+                    (width / 2).saturating_sub(text.chars().count() as u16 / 2),
+                    height / 2,
+                    text,
+                    Color::Cyan,
+                );
+                term.present(buf, first_frame)?;
+            }
+        }
+        first_frame = false;
+
+        let elapsed = frame_start.elapsed();
+        if elapsed < FRAME {
+            std::thread::sleep(FRAME - elapsed);
         }
     }
 }
 
 // ── Entry point ───────────────────────────────────────────────────────────────
 
+/// Command-line options.
+struct Options {
+    demo_mode: bool,
+    /// `--record <file>`: save the most recent game as a replay.
+    record: Option<PathBuf>,
+    /// `--replay <file>`: play back a recording instead of showing the menu.
+    replay: Option<Replay>,
+    /// Custom difficulty profiles from `--profiles <file>` (default
+    /// `profiles.toml`), offered on the menu after the built-in levels.
+    profiles: Vec<Profile>,
+    /// Settings loaded from `settings.toml`.
+    settings: Settings,
+    /// Key bindings loaded from `keys.toml`.
+    bindings: Bindings,
+    /// `--host [port]`: every game is co-op with a player who joins over
+    /// the network.
+    host: Option<TcpListener>,
+    /// `--join <addr>`: fly player two in the game hosted at `addr`.
+    join: Option<String>,
+    /// `--broadcast <addr>`: show every game to viewers who connect to `addr`.
+    broadcast: Option<Broadcast>,
+    /// `--watch <addr>`: watch the games broadcast at `addr`.
+    watch: Option<String>,
+    /// `--db <file>`, else `$SHOOTING_GAME_DB`: where to keep the scores
+    /// (`:memory:` keeps none).  `None` is the default location.
+    db: Option<db::Location>,
+}
+
+impl Options {
+    fn from_args() -> Result<Options, String> {
+        let args: Vec<String> = std::env::args().skip(1).collect();
+        let value_of = |flag: &str| -> Result<Option<PathBuf>, String> {
+            match args.iter().position(|a| a == flag) {
+                None => Ok(None),
+                Some(i) => args
+                    .get(i + 1)
+                    .map(|v| Some(PathBuf::from(v)))
+                    .ok_or_else(|| format!("{} needs a file argument", flag)),
+            }
+        };
+
+        let replay = match value_of("--replay")? {
+            Some(path) => Some(
+                Replay::load(&path)
+                    .map_err(|e| format!("cannot load replay {}: {}", path.display(), e))?,
+            ),
+            None => None,
+        };
+
+        let profiles_path = value_of("--profiles")?
+            .unwrap_or_else(|| PathBuf::from(profiles::DEFAULT_PROFILES_FILE));
+        let profiles = profiles::load_profiles(&profiles_path)?;
+        let settings = settings::load_settings(Path::new(settings::DEFAULT_SETTINGS_FILE))?;
+        let bindings = bindings::load_bindings(Path::new(bindings::DEFAULT_BINDINGS_FILE))?;
+        let demo_mode = args.iter().any(|arg| arg == "--demo-play");
+
+        // The port is optional, so a following flag is not taken for it.
+        let host = match args.iter().position(|a| a == "--host") {
+            None => None,
+            Some(i) => {
+                let port = match args.get(i + 1).filter(|a| !a.starts_with("--")) {
+                    Some(p) => p.parse().map_err(|_| format!("--host: bad port {:?}", p))?,
+                    None => net::DEFAULT_PORT,
+                };
+                let listener = TcpListener::bind(("0.0.0.0", port))
+                    .map_err(|e| format!("cannot listen on port {}: {}", port, e))?;
+                Some(listener)
+            }
+        };
+        // Addresses without a port get the default one; a bare port means
+        // every interface of this machine.
+        let address_of = |flag: &str, port: u16| -> Result<Option<String>, String> {
+            match args.iter().position(|a| a == flag) {
+                None => Ok(None),
+                Some(i) => {
+                    let addr = args
+                        .get(i + 1)
+                        .ok_or_else(|| format!("{} needs an address", flag))?;
+                    Ok(Some(if addr.contains(':') {
+                        addr.clone()
+                    } else if addr.parse::<u16>().is_ok() {
+                        format!("0.0.0.0:{}", addr)
+                    } else {
+                        format!("{}:{}", addr, port)
+                    }))
+                }
+            }
+        };
+        let join = address_of("--join", net::DEFAULT_PORT)?;
+        let watch = address_of("--watch", net::DEFAULT_BROADCAST_PORT)?;
+        let broadcast = match address_of("--broadcast", net::DEFAULT_BROADCAST_PORT)? {
+            Some(addr) => Some(
+                Broadcast::bind(addr.as_str())
+                    .map_err(|e| format!("cannot broadcast on {}: {}", addr, e))?,
+            ),
+            None => None,
+        };
+
+        let modes = [
+            host.is_some(),
+            join.is_some(),
+            replay.is_some(),
+            watch.is_some(),
+            demo_mode,
+        ];
+        if modes.iter().filter(|on| **on).count() > 1 {
+            return Err(
+                "--host, --join, --replay, --watch and --demo-play cannot be combined".to_string(),
+            );
+        }
+        if broadcast.is_some() && (join.is_some() || replay.is_some() || watch.is_some()) {
+            return Err("--broadcast only shows games played here".to_string());
+        }
+
+        let db = value_of("--db")?
+            .or_else(|| std::env::var_os(db::DB_ENV).map(PathBuf::from))
+            .map(db::Location::from_path);
+
+        Ok(Options {
+            demo_mode,
+            record: value_of("--record")?,
+            replay,
+            profiles,
+            settings,
+            bindings,
+            host,
+            join,
+            broadcast,
+            watch,
+            db,
+        })
+    }
+}
+
+/// The default database location, taking over the file older versions kept
+/// in the working directory.  Reports on stderr, before the terminal is
+/// taken over.
+fn default_database() -> db::Location {
+    let legacy = Path::new(db::DB_FILE);
+    let path = db::default_path();
+    match db::adopt_legacy(legacy, &path) {
+        Ok(false) => {}
+        Ok(true) => eprintln!("Moved {} to {}", legacy.display(), path.display()),
+        Err(e) => {
+            eprintln!(
+                "shooting_game: cannot move {} to {}: {}; using it where it is",
+                legacy.display(),
+                path.display(),
+                e
+            );
+            return db::Location::File(legacy.to_path_buf());
+        }
+    }
+    db::Location::File(path)
+}
+
 fn main() -> std::io::Result<()> {
-    let demo_mode = std::env::args().any(|arg| arg == "--demo-play");
+    let mut opts = match Options::from_args() {
+        Ok(opts) => opts,
+        Err(msg) => {
+            eprintln!("shooting_game: {}", msg);
+            std::process::exit(2);
+        }
+    };
+
+    // Joining waits for the host to start a game, so it happens before the
+    // terminal is taken over and Ctrl-C still works.
+    let client = match &opts.join {
+        Some(addr) => {
+            eprintln!(
+                "Connecting to {} — waiting for the host to start a game…",
+                addr
+            );
+            match Client::join(addr.as_str()) {
+                Ok(client) => Some(client),
+                Err(e) => {
+                    eprintln!("shooting_game: cannot join {}: {}", addr, e);
+                    std::process::exit(1);
+                }
+            }
+        }
+        None => None,
+    };
+    let viewer = match &opts.watch {
+        Some(addr) => match Viewer::watch(addr.as_str()) {
+            Ok(viewer) => Some(viewer),
+            Err(e) => {
+                eprintln!("shooting_game: cannot watch {}: {}", addr, e);
+                std::process::exit(1);
+            }
+        },
+        None => None,
+    };
+    let broadcast = opts.broadcast.take();
+    let database = opts.db.take().unwrap_or_else(default_database);
 
     let raw_out = stdout();
     let mut out = BufWriter::new(raw_out);
@@ -473,6 +1479,8 @@ fn main() -> std::io::Result<()> {
             KeyboardEnhancementFlags::REPORT_EVENT_TYPES,
         ))
         .is_ok();
+    // Focus events let the game pause itself when the window is left.
+    let focus_events = out.execute(event::EnableFocusChange).is_ok();
 
     // Dedicate a thread exclusively to blocking event reads, sending them
     // through a channel so the game loop never has to block on I/O.
@@ -485,9 +1493,28 @@ fn main() -> std::io::Result<()> {
         }
     });
 
-    let result = run(&mut out, &rx, demo_mode);
+    let mut store = SettingsStore {
+        path: PathBuf::from(settings::DEFAULT_SETTINGS_FILE),
+        settings: opts.settings.clone(),
+        bindings_path: PathBuf::from(bindings::DEFAULT_BINDINGS_FILE),
+        bindings: opts.bindings.clone(),
+    };
+    let result = match (&opts.replay, client, viewer) {
+        (Some(recording), _, _) => {
+            play_replay(&mut out, &rx, recording, &mut store).map(|c| Some(c.to_string()))
+        }
+        (None, Some(client), _) => play_network(&mut out, &rx, client, &store).map(Some),
+        (None, None, Some(viewer)) => watch(&mut out, &rx, viewer, &store).map(Some),
+        (None, None, None) => {
+            run(&mut out, &rx, &opts, &mut store, broadcast, &database).map(|_| None)
+        }
+    };
 
     // Always restore the terminal
+    let _ = out.execute(DisableMouseCapture);
+    if focus_events {
+        let _ = out.execute(event::DisableFocusChange);
+    }
     if keyboard_enhanced {
         let _ = out.execute(PopKeyboardEnhancementFlags);
     }
@@ -495,55 +1522,257 @@ fn main() -> std::io::Result<()> {
     let _ = out.execute(terminal::LeaveAlternateScreen);
     let _ = terminal::disable_raw_mode();
 
-    result
+    // The replay drift report, or how a network game ended, is printed
+    // after leaving the alternate screen so it stays visible in the shell.
+    if let Ok(Some(report)) = &result {
+        eprintln!("{}", report);
+    }
+
+    result.map(|_| ())
+}
+
+/// Play back a recording in the terminal and report whether it stayed in sync.
+fn play_replay<W: Write>(
+    out: &mut W,
+    rx: &mpsc::Receiver<Event>,
+    recording: &Replay,
+    store: &mut SettingsStore,
+) -> std::io::Result<ReplayCheck> {
+    let (mut state, rng) = recording.start();
+    let mut check = ReplayCheck::default();
+    game_loop(
+        out,
+        &mut state,
+        rx,
+        false,
+        rng,
+        ReplayMode::Play(recording, &mut check),
+        &mut Peers::default(),
+        store,
+    )?;
+    Ok(check)
 }
 
-fn run<W: Write>(out: &mut W, rx: &mpsc::Receiver<Event>, demo_mode: bool) -> std::io::Result<()> {
+fn run<W: Write>(
+    out: &mut W,
+    rx: &mpsc::Receiver<Event>,
+    opts: &Options,
+    store: &mut SettingsStore,
+    broadcast: Option<Broadcast>,
+    database: &db::Location,
+) -> std::io::Result<()> {
+    let demo_mode = opts.demo_mode;
     let username = std::env::var("USER").unwrap_or_else(|_| "Player".to_string());
-    let db_conn = db::open();
-    let mut high_score = db_conn.as_ref().map_or(0, db::load_best_score);
+    // Scores that cannot be written are queued and tried again before each
+    // menu; the menu says what is wrong meanwhile.
+    let mut scores = db::ScoreStore::open(database.clone());
+    let mut high_score = scores.read(db::load_best_score).unwrap_or(0);
+    // Set by "Restart" on the pause menu: play the same game again, no menu.
+    let mut restart: Option<(Level, Difficulty)> = None;
+    // Two-player co-op, toggled on the menu; the demo bot always plays solo.
+    let mut coop = false;
+    let hosting = opts
+        .host
+        .as_ref()
+        .and_then(|l| l.local_addr().ok())
+        .map(|a| a.port());
+    // Player two's connection when hosting is kept when the game is
+    // restarted; viewers stay for every game.
+    let mut peers = Peers {
+        host: None,
+        broadcast,
+    };
+
+    // Nothing else can pick up a saved game mid-way: a partner or viewer
+    // would need the whole run from its start.
+    let may_continue = !demo_mode && hosting.is_none() && peers.broadcast.is_none();
+    let save_path = Path::new(savegame::DEFAULT_SAVE_FILE);
+    // A problem with the save file, shown on the next menu.
+    let mut save_notice: Option<String> = None;
 
     loop {
-        let menu_res = if demo_mode {
-            MenuResult::Start(Level::Hard)
+        let mut saved = None;
+        let menu_res = if let Some((level, difficulty)) = restart.take() {
+            MenuResult::Start(level, difficulty)
+        } else if demo_mode {
+            MenuResult::Start(Level::Hard, Difficulty::preset(&Level::Hard))
         } else {
-            show_menu(out, rx, high_score)?
+            let coop = coop || hosting.is_some();
+            scores.retry();
+            high_score = high_score.max(scores.read(db::load_best_score).unwrap_or(0));
+            if may_continue {
+                saved = savegame::load(save_path).transpose();
+            }
+            let summary = saved
+                .as_ref()
+                .map(|s| s.as_ref().map(SaveGame::summary).map_err(Clone::clone));
+            let notice = [save_notice.take(), score_notice(&scores)]
+                .into_iter()
+                .flatten()
+                .reduce(|a, b| format!("{}   {}", a, b));
+            show_menu(
+                out,
+                rx,
+                high_score,
+                &opts.profiles,
+                store,
+                coop,
+                hosting,
+                summary,
+                notice.as_deref(),
+            )?
         };
 
-        match menu_res {
+        // A new game comes with the recording of it; a continued one cannot
+        // be recorded, as a replay always starts from the beginning.
+        let (mut state, rng, mut recording) = match menu_res {
             MenuResult::Quit => break,
-            MenuResult::Start(level) => {
-                let difficulty_best = db_conn
-                    .as_ref()
-                    .map_or(0, |c| db::load_top_score(c, &level));
+            MenuResult::Keys => {
+                show_bindings(out, rx, store)?;
+                continue;
+            }
+            MenuResult::Leaderboard => {
+                show_leaderboard(out, rx, scores.conn(), &username, &opts.profiles)?;
+                continue;
+            }
+            MenuResult::ToggleMouse => {
+                store.settings.mouse_control = !store.settings.mouse_control;
+                let _ = settings::save_settings(&store.path, &store.settings);
+                continue;
+            }
+            MenuResult::ToggleCoop => {
+                coop = !coop;
+                continue;
+            }
+            MenuResult::Continue => {
+                let Some(Ok(game)) = saved else { continue };
+                // Continued once: quitting again offers a fresh save.  If the
+                // file cannot go, play on and say so on the menu.
+                if let Err(e) = savegame::remove(save_path) {
+                    save_notice = Some(format!("Could not remove the saved game: {}", e));
+                }
+                let mut state = game.state;
                 let (width, height) = terminal::size()?;
-                let mut state = init_state(level, width, height, difficulty_best);
-                let quit = game_loop(out, &mut state, rx, demo_mode)?;
-
-                if state.status == GameStatus::GameOver {
-                    if let Some(ref conn) = db_conn {
-                        let _ = db::insert_score(conn, &username, &state.level, state.score);
-                    }
+                if playfield_fits(width, height) && (width, height) != (state.width, state.height) {
+                    state = resize(&state, width, height);
                 }
-
-                if let Some(ref conn) = db_conn {
-                    let _ = db::upsert_top_score(conn, &username, &state.level, state.score);
+                // Back in the pause menu, so the player can get ready.
+                state = pause(&resume(&state));
+                (state, game.rng, None)
+            }
+            MenuResult::Start(level, difficulty) => {
+                let coop = (coop || hosting.is_some()) && !demo_mode;
+                let score_key = if coop {
+                    profiles::coop_score_key(&level, &difficulty)
+                } else {
+                    profiles::score_key(&level, &difficulty)
+                };
+                let difficulty_best = scores
+                    .read(|c| db::load_top_score(c, &score_key))
+                    .unwrap_or(0);
+                // Never start smaller than the minimum playfield; game_loop
+                // waits for a too-small terminal to grow.
+                let (width, height) = terminal::size()?;
+                let (width, height) = (width.max(MIN_WIDTH), height.max(MIN_HEIGHT));
+                let seed: u64 = rand::random();
+                let mut recording =
+                    Replay::new(seed, level.clone(), difficulty.clone(), width, height);
+                recording.coop = coop;
+                let mut state = init_state_with(level, difficulty, width, height, difficulty_best);
+                if coop {
+                    state = with_player_two(&state);
                 }
+                (state, GameRng::seed_from_u64(seed), Some(recording))
+            }
+        };
 
-                if state.score > high_score {
-                    high_score = state.score;
+        let coop = state.player2.is_some();
+        let score_key = if coop {
+            profiles::coop_score_key(&state.level, &state.difficulty)
+        } else {
+            profiles::score_key(&state.level, &state.difficulty)
+        };
+        if let (Some(listener), Some(recording)) = (&opts.host, &recording) {
+            // A restart keeps the same partner; otherwise wait for one.
+            let started = peers
+                .host
+                .as_mut()
+                .is_some_and(|h| h.start(recording).is_ok());
+            if !started {
+                peers.host = wait_for_player(out, rx, listener, recording, store)?;
+                if peers.host.is_none() {
+                    continue;
                 }
+            }
+        }
+        if let (Some(broadcast), Some(recording)) = (peers.broadcast.as_mut(), &recording) {
+            broadcast.start(recording);
+        }
+        let replay_mode = match recording.as_mut() {
+            Some(recording) if opts.record.is_some() => ReplayMode::Record(recording),
+            _ => ReplayMode::Off,
+        };
+        let exit = game_loop(
+            out,
+            &mut state,
+            rx,
+            demo_mode,
+            rng,
+            replay_mode,
+            &mut peers,
+            store,
+        );
+        // Menus have no use for the mouse.
+        set_mouse_capture(out, false)?;
+        let exit = exit?;
 
-                if quit {
-                    break;
-                }
+        // Each game overwrites the file, so it always holds the most
+        // recent run.
+        if let (Some(path), Some(recording)) = (&opts.record, &recording) {
+            recording.save(path)?;
+        }
 
-                if demo_mode {
-                    // Small delay before restarting AI play
-                    std::thread::sleep(Duration::from_millis(1500));
-                }
+        scores.save(db::GameResult {
+            username: username.clone(),
+            difficulty: score_key,
+            points: state.score,
+            wave: state.wave.number,
+            p2_points: state
+                .player2
+                .is_some()
+                .then(|| player_score(&state, PlayerId::Two)),
+            finished: state.status == GameStatus::GameOver,
+            stats: state.stats.clone(),
+        });
+
+        // The menu's best score is the official one; custom and co-op
+        // runs don't count.
+        if state.difficulty.builtin && !coop && state.score > high_score {
+            high_score = state.score;
+        }
+
+        if !matches!(exit, LoopExit::Restart) {
+            if let Some(host) = peers.host.take() {
+                host.leave();
             }
         }
+        if let Some(broadcast) = peers.broadcast.as_mut() {
+            broadcast.stop();
+        }
+
+        match exit {
+            LoopExit::Quit => break,
+            LoopExit::Restart => {
+                restart = Some((state.level.clone(), state.difficulty.clone()));
+                continue;
+            }
+            LoopExit::Menu => {}
+        }
+
+        if demo_mode {
+            // Small delay before restarting AI play
+            std::thread::sleep(Duration::from_millis(1500));
+        }
     }
     Ok(())
 }
@@ -0,0 +1,892 @@
+//! Network play over TCP: co-op between two terminals, and broadcasts to
+//! spectators.  There is no versus: both ships share the bottom row and fire
+//! upward, so neither could ever hit the other.
+//!
+//! The host runs the only authoritative `tick`.  The client flies player
+//! two: it sends its inputs to the host and gets back every ticked frame as
+//! a replay record — the inputs the host applied and the state hash after
+//! the tick — which it re-runs on its own copy of the game.  A hash
+//! mismatch means the two copies have diverged, and ends the session.
+//!
+//! Every message is one frame on the stream: a 4-byte big-endian length,
+//! then that many bytes of UTF-8 text using the replay format's tokens:
+//!
+//! ```text
+//! hello 1                      client → host: protocol version
+//! refuse <reason>              host → client: not today
+//! start                        host → client: a new game, followed by
+//! seed 12345 ...                 a replay header (see `replay`)
+//! input 42 2L1 2S              client → host: input #42
+//! frame 42 9c1d2e3f4a5b6c7d L1 2L1 2S
+//!                              host → client: one tick, with the client's
+//!                              inputs up to #42 applied
+//! idle                         host → client: nothing ticked (paused)
+//! snapshot 200                 broadcast → viewer: the game as it is now,
+//! {"version":2,"state":...}      200 frames in (see `savegame`)
+//! bye                          either way: leaving
+//! ```
+//!
+//! Latency: the host applies player two's inputs on the first frame after
+//! they arrive, so the client sees the game one trip late.  To keep its own
+//! ship responsive the client draws it with the moves the host has not
+//! acknowledged yet applied on top (`Client::view`); the next acknowledged
+//! frame replaces the guess with the real position.
+//!
+//! Disconnects: both sides send something every frame, so a peer silent for
+//! `TIMEOUT` is taken to be gone.  The host's link also sends `idle` on its
+//! own once a `HEARTBEAT` passes without a message, which covers the screens
+//! where its game loop waits for a key.  The host carries on alone with
+//! player two out (`PlayerAction::P2Leave`, so recordings stay exact); the
+//! client stops where it is.
+//!
+//! Broadcasts use the same messages one way: a `Broadcast` sends `hello`, a
+//! `snapshot` of the current game and then every frame to each `Viewer`,
+//! which re-runs them just as the client does.  Only the latest state is
+//! kept for late joiners, however long the game has been going.
+
+use std::collections::VecDeque;
+use std::fmt;
+use std::io::{self, Read, Write};
+use std::net::{
+    IpAddr, Ipv4Addr, Ipv6Addr, Shutdown, SocketAddr, TcpListener, TcpStream, ToSocketAddrs,
+};
+use std::sync::mpsc::{self, RecvTimeoutError, TryRecvError};
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::thread;
+use std::time::Duration;
+
+use crate::compute::apply_action;
+use crate::entities::{EntireGameStateInfo, GameRng, PlayerAction, MIN_HEIGHT, MIN_WIDTH};
+use crate::replay::{self, action_token, parse_action, state_hash, Drift, Replay, ReplayFrame};
+use crate::savegame::SaveGame;
+
+/// Port used when `--host` or `--join` does not name one.
+pub const DEFAULT_PORT: u16 = 7878;
+
+/// Port used when `--broadcast` or `--watch` does not name one.
+pub const DEFAULT_BROADCAST_PORT: u16 = 7879;
+
+/// Sent in `hello`; bump on any change to the messages.
+pub const PROTOCOL_VERSION: u32 = 2;
+
+/// How long either side waits for word from the other before giving up.
+pub const TIMEOUT: Duration = Duration::from_secs(5);
+
+/// A viewer, or a co-op client, with nothing else to receive gets an `idle`
+/// this often.
+const HEARTBEAT: Duration = Duration::from_secs(1);
+
+/// Longest message accepted, far above anything the game sends.
+const MAX_MESSAGE: usize = 1 << 20;
+
+/// Longest move the host accepts from the client in one input: a warp.
+const MAX_REMOTE_MOVE: i32 = 10;
+
+// ── Messages ──────────────────────────────────────────────────────────────────
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Message {
+    /// Client → host, first thing on a new connection.
+    Hello {
+        version: u32,
+    },
+    /// Host → client, instead of `Start`, when the client cannot join.
+    Refuse(String),
+    /// Host → client: a new game begins.  Only the replay header is sent.
+    Start(Replay),
+    /// Client → host: one frame of player two's inputs, numbered from 1.
+    Input {
+        seq: u64,
+        actions: Vec<PlayerAction>,
+    },
+    /// Host → client: one ticked frame.  `ack` is the last input applied.
+    Frame {
+        ack: u64,
+        frame: ReplayFrame,
+    },
+    /// Host → client: nothing was ticked this frame.
+    Idle,
+    /// Broadcast → viewer: the game in progress, `frames` frames after its
+    /// start, to follow live from there.
+    Snapshot {
+        frames: usize,
+        game: Box<SaveGame>,
+    },
+    Bye,
+}
+
+impl Message {
+    pub fn to_text(&self) -> String {
+        match self {
+            Message::Hello { version } => format!("hello {}", version),
+            Message::Refuse(reason) => format!("refuse {}", reason),
+            Message::Start(game) => {
+                let header = Replay {
+                    frames: Vec::new(),
+                    ..game.clone()
+                };
+                format!("start\n{}", header.to_text())
+            }
+            Message::Input { seq, actions } => with_tokens(format!("input {}", seq), actions),
+            Message::Frame { ack, frame } => {
+                with_tokens(format!("frame {} {:016x}", ack, frame.hash), &frame.actions)
+            }
+            Message::Idle => "idle".to_string(),
+            Message::Snapshot { frames, game } => {
+                format!("snapshot {}\n{}", frames, game.to_json())
+            }
+            Message::Bye => "bye".to_string(),
+        }
+    }
+
+    pub fn parse(text: &str) -> Result<Message, String> {
+        if let Some(header) = text.strip_prefix("start\n") {
+            return Replay::from_text(header).map(Message::Start);
+        }
+        if let Some(rest) = text.strip_prefix("snapshot ") {
+            let bad = || format!("bad snapshot {:?}", rest.lines().next().unwrap_or_default());
+            let (frames, json) = rest.split_once('\n').ok_or_else(bad)?;
+            let frames = frames.trim().parse().map_err(|_| bad())?;
+            let game = SaveGame::from_json(json)?;
+            let (width, height) = (game.state.width, game.state.height);
+            if width < MIN_WIDTH || height < MIN_HEIGHT {
+                return Err(format!(
+                    "snapshot size {}x{} is below the minimum {}x{}",
+                    width, height, MIN_WIDTH, MIN_HEIGHT
+                ));
+            }
+            return Ok(Message::Snapshot {
+                frames,
+                game: Box::new(game),
+            });
+        }
+        let mut words = text.split_whitespace();
+        let bad = || format!("bad message {:?}", text);
+        let msg = match words.next() {
+            Some("hello") => Message::Hello {
+                version: words.next().and_then(|w| w.parse().ok()).ok_or_else(bad)?,
+            },
+            Some("refuse") => Message::Refuse(text["refuse".len()..].trim().to_string()),
+            Some("input") => {
+                let seq = words.next().and_then(|w| w.parse().ok()).ok_or_else(bad)?;
+                Message::Input {
+                    seq,
+                    actions: parse_tokens(words, text)?,
+                }
+            }
+            Some("frame") => {
+                let ack = words.next().and_then(|w| w.parse().ok()).ok_or_else(bad)?;
+                let hash = words
+                    .next()
+                    .and_then(|h| u64::from_str_radix(h, 16).ok())
+                    .ok_or_else(bad)?;
+                Message::Frame {
+                    ack,
+                    frame: ReplayFrame {
+                        actions: parse_tokens(words, text)?,
+                        hash,
+                    },
+                }
+            }
+            Some("idle") => Message::Idle,
+            Some("bye") => Message::Bye,
+            _ => return Err(bad()),
+        };
+        Ok(msg)
+    }
+}
+
+fn with_tokens(mut line: String, actions: &[PlayerAction]) -> String {
+    for action in actions {
+        line.push(' ');
+        line.push_str(&action_token(action));
+    }
+    line
+}
+
+fn parse_tokens<'a>(
+    words: impl Iterator<Item = &'a str>,
+    text: &str,
+) -> Result<Vec<PlayerAction>, String> {
+    words
+        .map(|w| parse_action(w).ok_or_else(|| format!("bad action {:?} in {:?}", w, text)))
+        .collect()
+}
+
+// ── Framing ───────────────────────────────────────────────────────────────────
+
+/// Write one length-prefixed message.
+pub fn write_message(w: &mut impl Write, msg: &Message) -> io::Result<()> {
+    let text = msg.to_text();
+    let mut bytes = (text.len() as u32).to_be_bytes().to_vec();
+    bytes.extend_from_slice(text.as_bytes());
+    w.write_all(&bytes)?;
+    w.flush()
+}
+
+/// Read one length-prefixed message, blocking until it is complete.
+pub fn read_message(r: &mut impl Read) -> io::Result<Message> {
+    let mut len = [0; 4];
+    r.read_exact(&mut len)?;
+    let len = u32::from_be_bytes(len) as usize;
+    if len > MAX_MESSAGE {
+        return Err(invalid(format!("message of {} bytes is too long", len)));
+    }
+    let mut bytes = vec![0; len];
+    r.read_exact(&mut bytes)?;
+    let text = String::from_utf8(bytes).map_err(|_| invalid("message is not UTF-8"))?;
+    Message::parse(&text).map_err(invalid)
+}
+
+fn invalid(msg: impl Into<String>) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, msg.into())
+}
+
+// ── Errors ────────────────────────────────────────────────────────────────────
+
+/// Why a session ended.
+#[derive(Clone, Debug, PartialEq)]
+pub enum NetError {
+    /// The other side said goodbye.
+    Left,
+    /// The connection broke, went quiet for `TIMEOUT`, or carried nonsense.
+    Lost(String),
+    /// The client's copy of the game no longer matches the host's.
+    Desync(Drift),
+}
+
+impl fmt::Display for NetError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            NetError::Left => write!(f, "the other player left"),
+            NetError::Lost(why) => write!(f, "connection lost: {}", why),
+            NetError::Desync(d) => write!(
+                f,
+                "out of sync with the host at frame {} (expected {:016x}, got {:016x})",
+                d.frame, d.expected, d.actual
+            ),
+        }
+    }
+}
+
+impl From<io::Error> for NetError {
+    fn from(e: io::Error) -> Self {
+        NetError::Lost(match e.kind() {
+            io::ErrorKind::WouldBlock | io::ErrorKind::TimedOut => {
+                format!("no word for {} seconds", TIMEOUT.as_secs())
+            }
+            io::ErrorKind::UnexpectedEof => "connection closed".to_string(),
+            _ => e.to_string(),
+        })
+    }
+}
+
+fn unexpected(msg: &Message) -> NetError {
+    let text = msg.to_text();
+    let first = text.split_whitespace().next().unwrap_or_default();
+    NetError::Lost(format!("unexpected {:?} message", first))
+}
+
+// ── Connection ────────────────────────────────────────────────────────────────
+
+/// A TCP stream with a reader and a writer thread, so the game loop never
+/// blocks on the network: it queues what it sends and collects whatever has
+/// arrived.  With a `heartbeat`, the writer sends it whenever nothing else
+/// went out for `HEARTBEAT`, so the peer still hears from us while the game
+/// loop is held up in a screen of its own.
+struct Link {
+    stream: TcpStream,
+    incoming: mpsc::Receiver<io::Result<Message>>,
+    /// `None` once dropped, which tells the writer to finish.
+    outgoing: Option<mpsc::Sender<Message>>,
+    writer: Option<thread::JoinHandle<()>>,
+}
+
+impl Link {
+    fn new(stream: TcpStream, heartbeat: Option<Message>) -> io::Result<Link> {
+        stream.set_nonblocking(false)?;
+        stream.set_nodelay(true)?;
+        stream.set_read_timeout(Some(TIMEOUT))?;
+        stream.set_write_timeout(Some(TIMEOUT))?;
+        let mut reader = stream.try_clone()?;
+        let (tx, incoming) = mpsc::channel();
+        thread::spawn(move || loop {
+            let msg = read_message(&mut reader);
+            let failed = msg.is_err();
+            if tx.send(msg).is_err() || failed {
+                break;
+            }
+        });
+        let mut writer_stream = stream.try_clone()?;
+        let (outgoing, rx) = mpsc::channel::<Message>();
+        let writer = thread::spawn(move || loop {
+            let msg = match rx.recv_timeout(HEARTBEAT) {
+                Ok(msg) => msg,
+                Err(RecvTimeoutError::Timeout) => match &heartbeat {
+                    Some(msg) => msg.clone(),
+                    None => continue,
+                },
+                Err(RecvTimeoutError::Disconnected) => break,
+            };
+            if write_message(&mut writer_stream, &msg).is_err() {
+                break;
+            }
+        });
+        Ok(Link {
+            stream,
+            incoming,
+            outgoing: Some(outgoing),
+            writer: Some(writer),
+        })
+    }
+
+    /// Queue `msg`.  Fails once the writer has stopped on a broken
+    /// connection.
+    fn send(&mut self, msg: &Message) -> Result<(), NetError> {
+        let sent = self.outgoing.as_ref().map(|tx| tx.send(msg.clone()));
+        match sent {
+            Some(Ok(())) => Ok(()),
+            _ => Err(NetError::Lost("connection closed".to_string())),
+        }
+    }
+
+    /// The next message already received, if any.  `bye` and a broken
+    /// connection come back as errors, after everything that came before.
+    fn next(&self) -> Result<Option<Message>, NetError> {
+        match self.incoming.try_recv() {
+            Ok(Ok(Message::Bye)) => Err(NetError::Left),
+            Ok(Ok(msg)) => Ok(Some(msg)),
+            Ok(Err(e)) => Err(e.into()),
+            Err(TryRecvError::Empty) => Ok(None),
+            Err(TryRecvError::Disconnected) => Err(NetError::Lost("connection closed".to_string())),
+        }
+    }
+}
+
+impl Drop for Link {
+    fn drop(&mut self) {
+        // Let the writer get out whatever is queued (a `bye`, say), then
+        // wake the reader thread so it can finish.
+        self.outgoing = None;
+        if let Some(writer) = self.writer.take() {
+            let _ = writer.join();
+        }
+        let _ = self.stream.shutdown(Shutdown::Both);
+    }
+}
+
+// ── Host ──────────────────────────────────────────────────────────────────────
+
+/// The host's end: receives player two's inputs and streams ticked frames.
+pub struct Host {
+    link: Link,
+    /// The last input received from the client.
+    ack: u64,
+}
+
+impl Host {
+    /// Shake hands with a client that just connected.  Follow with `start`.
+    pub fn accept(mut stream: TcpStream) -> io::Result<Host> {
+        stream.set_nonblocking(false)?;
+        stream.set_read_timeout(Some(TIMEOUT))?;
+        match read_message(&mut stream)? {
+            Message::Hello {
+                version: PROTOCOL_VERSION,
+            } => Ok(Host {
+                // Idle heartbeats keep the client with us while the game
+                // loop is held up in the pause menu's screens.
+                link: Link::new(stream, Some(Message::Idle))?,
+                ack: 0,
+            }),
+            Message::Hello { version } => {
+                let why = format!(
+                    "host speaks protocol {}, client {}",
+                    PROTOCOL_VERSION, version
+                );
+                let _ = write_message(&mut stream, &Message::Refuse(why.clone()));
+                Err(invalid(why))
+            }
+            other => Err(invalid(format!(
+                "expected hello, got {:?}",
+                other.to_text()
+            ))),
+        }
+    }
+
+    /// Start `game` on the client.  `game` must be a co-op game.
+    pub fn start(&mut self, game: &Replay) -> Result<(), NetError> {
+        self.link.send(&Message::Start(game.clone()))
+    }
+
+    /// Player two's inputs received since the last call, oldest first.  Only
+    /// player-two actions are let through, and a move is at most a warp.
+    pub fn receive(&mut self) -> Result<Vec<PlayerAction>, NetError> {
+        let mut actions = Vec::new();
+        while let Some(msg) = self.link.next()? {
+            match msg {
+                Message::Input {
+                    seq,
+                    actions: input,
+                } => {
+                    self.ack = seq;
+                    actions.extend(input.iter().filter_map(remote_action));
+                }
+                other => return Err(unexpected(&other)),
+            }
+        }
+        Ok(actions)
+    }
+
+    /// Send a ticked frame; everything `receive` returned so far is in it
+    /// or an earlier one.
+    pub fn send_frame(&mut self, frame: &ReplayFrame) -> Result<(), NetError> {
+        self.link.send(&Message::Frame {
+            ack: self.ack,
+            frame: frame.clone(),
+        })
+    }
+
+    /// Tell the client nothing ticked this frame, so it knows we are alive.
+    pub fn send_idle(&mut self) -> Result<(), NetError> {
+        self.link.send(&Message::Idle)
+    }
+
+    /// Say goodbye and close the connection.
+    pub fn leave(mut self) {
+        let _ = self.link.send(&Message::Bye);
+    }
+}
+
+/// `action` as the host accepts it from the network, or `None` if the client
+/// has no business sending it.
+fn remote_action(action: &PlayerAction) -> Option<PlayerAction> {
+    match *action {
+        PlayerAction::P2MoveLeft(n) => Some(PlayerAction::P2MoveLeft(n.clamp(1, MAX_REMOTE_MOVE))),
+        PlayerAction::P2MoveRight(n) => {
+            Some(PlayerAction::P2MoveRight(n.clamp(1, MAX_REMOTE_MOVE)))
+        }
+        PlayerAction::P2Shoot => Some(PlayerAction::P2Shoot),
+        _ => None,
+    }
+}
+
+/// A local input as player two's: the client steers its ship with the
+/// ordinary keys.  Debug toggles and resizes stay local.
+pub fn as_player_two(action: &PlayerAction) -> Option<PlayerAction> {
+    match *action {
+        PlayerAction::MoveLeft(n) | PlayerAction::P2MoveLeft(n) => {
+            Some(PlayerAction::P2MoveLeft(n))
+        }
+        PlayerAction::MoveRight(n) | PlayerAction::P2MoveRight(n) => {
+            Some(PlayerAction::P2MoveRight(n))
+        }
+        PlayerAction::Shoot | PlayerAction::P2Shoot => Some(PlayerAction::P2Shoot),
+        _ => None,
+    }
+}
+
+// ── Mirrored game ─────────────────────────────────────────────────────────────
+
+/// A copy of a remote game, kept in step by re-running the frames it is sent.
+struct Mirror {
+    state: EntireGameStateInfo,
+    rng: GameRng,
+    /// Frames run since the game started.
+    frames: usize,
+    /// No frame has come since the last `idle`.
+    idle: bool,
+}
+
+impl Mirror {
+    fn new(game: &Replay) -> Mirror {
+        let (state, rng) = game.start();
+        Mirror {
+            state,
+            rng,
+            frames: 0,
+            idle: false,
+        }
+    }
+
+    /// Pick up a game `frames` frames in, from a snapshot.
+    fn resume(frames: usize, game: SaveGame) -> Mirror {
+        Mirror {
+            state: game.state,
+            rng: game.rng,
+            frames,
+            idle: false,
+        }
+    }
+
+    /// Run one frame and check we still agree with the sender.
+    fn step(&mut self, frame: &ReplayFrame) -> Result<(), NetError> {
+        self.state = replay::step(&self.state, frame, &mut self.rng);
+        let actual = state_hash(&self.state);
+        if actual != frame.hash {
+            return Err(NetError::Desync(Drift {
+                frame: self.frames,
+                expected: frame.hash,
+                actual,
+            }));
+        }
+        self.frames += 1;
+        self.idle = false;
+        Ok(())
+    }
+}
+
+/// Connect to the first address `addr` resolves to that answers.
+fn connect(addr: impl ToSocketAddrs) -> io::Result<TcpStream> {
+    let mut last_err = invalid("address resolves to nothing");
+    for addr in addr.to_socket_addrs()? {
+        match TcpStream::connect_timeout(&addr, TIMEOUT) {
+            Ok(stream) => return Ok(stream),
+            Err(e) => last_err = e,
+        }
+    }
+    Err(last_err)
+}
+
+// ── Client ────────────────────────────────────────────────────────────────────
+
+/// The client's end: a copy of the game kept in step with the host's frames.
+pub struct Client {
+    link: Link,
+    game: Mirror,
+    next_seq: u64,
+    /// Inputs sent but not yet applied by the host, oldest first.
+    pending: VecDeque<(u64, Vec<PlayerAction>)>,
+}
+
+impl Client {
+    /// Connect to a host and wait, as long as it takes, for its game to
+    /// start.
+    pub fn join(addr: impl ToSocketAddrs) -> io::Result<Client> {
+        let mut stream = connect(addr)?;
+        // No timeout yet: the host may still be choosing a level.
+        stream.set_read_timeout(None)?;
+        let hello = Message::Hello {
+            version: PROTOCOL_VERSION,
+        };
+        write_message(&mut stream, &hello)?;
+        let game = match read_message(&mut stream)? {
+            Message::Start(game) => game,
+            Message::Refuse(why) => {
+                return Err(io::Error::new(io::ErrorKind::ConnectionRefused, why))
+            }
+            other => {
+                return Err(invalid(format!(
+                    "expected start, got {:?}",
+                    other.to_text()
+                )))
+            }
+        };
+        Ok(Client {
+            link: Link::new(stream, None)?,
+            game: Mirror::new(&game),
+            next_seq: 1,
+            pending: VecDeque::new(),
+        })
+    }
+
+    /// Send this frame's local inputs as player two's.  Call every frame,
+    /// even with nothing to send: it is also how the host knows we are here.
+    pub fn send(&mut self, actions: &[PlayerAction]) -> Result<(), NetError> {
+        let actions: Vec<PlayerAction> = actions.iter().filter_map(as_player_two).collect();
+        let seq = self.next_seq;
+        self.next_seq += 1;
+        self.link.send(&Message::Input {
+            seq,
+            actions: actions.clone(),
+        })?;
+        if !actions.is_empty() {
+            self.pending.push_back((seq, actions));
+        }
+        Ok(())
+    }
+
+    /// Run every frame the host has sent since the last call.
+    pub fn sync(&mut self) -> Result<(), NetError> {
+        while let Some(msg) = self.link.next()? {
+            match msg {
+                Message::Frame { ack, frame } => {
+                    self.game.step(&frame)?;
+                    self.pending.retain(|(seq, _)| *seq > ack);
+                }
+                Message::Idle => self.game.idle = true,
+                // The host restarted: same connection, new game.
+                Message::Start(game) => {
+                    self.game = Mirror::new(&game);
+                    self.pending.clear();
+                }
+                other => return Err(unexpected(&other)),
+            }
+        }
+        Ok(())
+    }
+
+    /// The game as of the host's latest frame.
+    pub fn state(&self) -> &EntireGameStateInfo {
+        &self.game.state
+    }
+
+    /// What to draw: the latest frame with player two's unacknowledged moves
+    /// applied on top.  Shots are left to the host; a predicted bullet could
+    /// vanish again.
+    pub fn view(&self) -> EntireGameStateInfo {
+        self.pending
+            .iter()
+            .flat_map(|(_, actions)| actions)
+            .filter(|a| {
+                matches!(
+                    a,
+                    PlayerAction::P2MoveLeft(_) | PlayerAction::P2MoveRight(_)
+                )
+            })
+            .fold(self.game.state.clone(), |s, a| apply_action(&s, a))
+    }
+
+    /// The host sent no frame since its last `idle` — it is paused or on
+    /// the game-over screen.
+    pub fn host_idle(&self) -> bool {
+        self.game.idle
+    }
+
+    /// Say goodbye and close the connection.
+    pub fn leave(mut self) {
+        let _ = self.link.send(&Message::Bye);
+    }
+}
+
+// ── Broadcast ─────────────────────────────────────────────────────────────────
+
+/// Games shown to any number of viewers (`--broadcast`).  Viewers may connect
+/// at any time, on the menu too: a listener thread sends each `hello`, then
+/// a snapshot of the current game, then every frame live.
+pub struct Broadcast {
+    local_addr: SocketAddr,
+    shared: Arc<Mutex<Audience>>,
+}
+
+/// What the listener thread and the game loop share.
+struct Audience {
+    /// One writer thread per viewer; a closed channel means they are gone.
+    viewers: Vec<mpsc::Sender<Message>>,
+    /// The game in progress as of its latest frame, for late joiners.
+    game: Option<Mirror>,
+    /// The broadcast was dropped; the listener thread is to stop.
+    closed: bool,
+}
+
+impl Broadcast {
+    pub fn bind(addr: impl ToSocketAddrs) -> io::Result<Broadcast> {
+        let listener = TcpListener::bind(addr)?;
+        let local_addr = listener.local_addr()?;
+        let shared = Arc::new(Mutex::new(Audience {
+            viewers: Vec::new(),
+            game: None,
+            closed: false,
+        }));
+        let audience = Arc::clone(&shared);
+        thread::spawn(move || {
+            for stream in listener.incoming() {
+                let mut audience = audience.lock().unwrap_or_else(|e| e.into_inner());
+                if audience.closed {
+                    return;
+                }
+                if let Ok(stream) = stream {
+                    audience.admit(stream);
+                }
+            }
+        });
+        Ok(Broadcast { local_addr, shared })
+    }
+
+    pub fn local_addr(&self) -> io::Result<SocketAddr> {
+        Ok(self.local_addr)
+    }
+
+    /// Viewers connected; those who left are noticed on the next message sent.
+    pub fn viewers(&self) -> usize {
+        self.audience().viewers.len()
+    }
+
+    /// A new game begins.
+    pub fn start(&mut self, game: &Replay) {
+        let header = Replay {
+            frames: Vec::new(),
+            ..game.clone()
+        };
+        let mut audience = self.audience();
+        audience.game = Some(Mirror::new(&header));
+        audience.send(Message::Start(header));
+    }
+
+    /// The game ended; viewers keep the last frame until the next `start`.
+    pub fn stop(&mut self) {
+        self.audience().game = None;
+    }
+
+    /// Send a ticked frame.  `state` and `rng` are the game just after it,
+    /// kept to catch up viewers who join later.
+    pub fn send_frame(&mut self, frame: &ReplayFrame, state: &EntireGameStateInfo, rng: &GameRng) {
+        let mut audience = self.audience();
+        if let Some(game) = &mut audience.game {
+            game.state = state.clone();
+            game.rng = rng.clone();
+            game.frames += 1;
+        }
+        audience.send(Message::Frame {
+            ack: 0,
+            frame: frame.clone(),
+        });
+    }
+
+    pub fn send_idle(&mut self) {
+        self.audience().send(Message::Idle);
+    }
+
+    fn audience(&self) -> MutexGuard<'_, Audience> {
+        self.shared.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+impl Drop for Broadcast {
+    fn drop(&mut self) {
+        // Closing the channels sends every viewer `bye`; a connection of our
+        // own wakes the listener thread so it sees we are gone.
+        {
+            let mut audience = self.audience();
+            audience.viewers.clear();
+            audience.closed = true;
+        }
+        let mut wake = self.local_addr;
+        match wake.ip() {
+            IpAddr::V4(ip) if ip.is_unspecified() => wake.set_ip(Ipv4Addr::LOCALHOST.into()),
+            IpAddr::V6(ip) if ip.is_unspecified() => wake.set_ip(Ipv6Addr::LOCALHOST.into()),
+            _ => {}
+        }
+        let _ = TcpStream::connect_timeout(&wake, TIMEOUT);
+    }
+}
+
+impl Audience {
+    /// Let in a viewer: queue `hello` and a snapshot of the game, ahead of
+    /// anything sent after.
+    fn admit(&mut self, stream: TcpStream) {
+        let Ok(viewer) = spawn_viewer(stream) else {
+            return;
+        };
+        let mut catch_up = vec![Message::Hello {
+            version: PROTOCOL_VERSION,
+        }];
+        if let Some(game) = &self.game {
+            catch_up.push(Message::Snapshot {
+                frames: game.frames,
+                game: Box::new(SaveGame::new(&game.state, &game.rng)),
+            });
+        }
+        if catch_up.into_iter().all(|msg| viewer.send(msg).is_ok()) {
+            self.viewers.push(viewer);
+        }
+    }
+
+    /// Queue `msg` for every viewer, dropping those who have gone.
+    fn send(&mut self, msg: Message) {
+        self.viewers
+            .retain(|viewer| viewer.send(msg.clone()).is_ok());
+    }
+}
+
+/// Start a writer thread for one viewer.  It sends whatever comes down the
+/// channel, with an `idle` whenever the game has been quiet for `HEARTBEAT`
+/// (e.g. on the menu), and `bye` when the channel closes.  A slow viewer
+/// only holds up their own thread.
+fn spawn_viewer(stream: TcpStream) -> io::Result<mpsc::Sender<Message>> {
+    stream.set_nonblocking(false)?;
+    stream.set_nodelay(true)?;
+    stream.set_write_timeout(Some(TIMEOUT))?;
+    let (tx, rx) = mpsc::channel();
+    let mut stream = stream;
+    thread::spawn(move || {
+        let mut send = |msg: &Message| write_message(&mut stream, msg).is_ok();
+        loop {
+            let sent = match rx.recv_timeout(HEARTBEAT) {
+                Ok(msg) => send(&msg),
+                Err(RecvTimeoutError::Timeout) => send(&Message::Idle),
+                Err(RecvTimeoutError::Disconnected) => {
+                    send(&Message::Bye);
+                    return;
+                }
+            };
+            if !sent {
+                return;
+            }
+        }
+    });
+    Ok(tx)
+}
+
+// ── Viewer ────────────────────────────────────────────────────────────────────
+
+/// A spectator's end (`--watch`): a copy of the broadcast game, if one is on.
+pub struct Viewer {
+    link: Link,
+    game: Option<Mirror>,
+}
+
+impl Viewer {
+    pub fn watch(addr: impl ToSocketAddrs) -> io::Result<Viewer> {
+        let mut stream = connect(addr)?;
+        stream.set_read_timeout(Some(TIMEOUT))?;
+        match read_message(&mut stream)? {
+            Message::Hello {
+                version: PROTOCOL_VERSION,
+            } => {}
+            Message::Hello { version } => {
+                return Err(invalid(format!(
+                    "broadcast speaks protocol {}, we speak {}",
+                    version, PROTOCOL_VERSION
+                )))
+            }
+            other => {
+                return Err(invalid(format!(
+                    "expected hello, got {:?}",
+                    other.to_text()
+                )))
+            }
+        }
+        Ok(Viewer {
+            link: Link::new(stream, None)?,
+            game: None,
+        })
+    }
+
+    /// Run everything received since the last call.  On joining that starts
+    /// with a snapshot, so the first picture is already complete.
+    pub fn sync(&mut self) -> Result<(), NetError> {
+        while let Some(msg) = self.link.next()? {
+            match (msg, &mut self.game) {
+                (Message::Start(game), _) => self.game = Some(Mirror::new(&game)),
+                (Message::Snapshot { frames, game }, _) => {
+                    self.game = Some(Mirror::resume(frames, *game));
+                }
+                (Message::Frame { frame, .. }, Some(game)) => game.step(&frame)?,
+                (Message::Idle, Some(game)) => game.idle = true,
+                (Message::Idle, None) => {}
+                (other, _) => return Err(unexpected(&other)),
+            }
+        }
+        Ok(())
+    }
+
+    /// The game being watched, or `None` between games.
+    pub fn state(&self) -> Option<&EntireGameStateInfo> {
+        self.game.as_ref().map(|g| &g.state)
+    }
+
+    /// No frame came since the last `idle`: paused, game over or on the menu.
+    pub fn idle(&self) -> bool {
+        self.game.as_ref().is_some_and(|g| g.idle)
+    }
+}
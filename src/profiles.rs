@@ -0,0 +1,175 @@
+//! Custom difficulty profiles loaded from TOML.
+//!
+//! Each `[[profile]]` table starts from a built-in preset (`base`, default
+//! `medium`) and overrides any subset of its parameters:
+//!
+//! ```toml
+//! [[profile]]
+//! name = "Bullet Hell"
+//! base = "hard"
+//! enemy_fire_chance = 60
+//! spacecraft_chance = 0.3
+//! clear_shots_on_hit = false
+//! power_up_loss = "lose"
+//! ```
+//!
+//! Scores set on a custom profile are stored under `score_key`, which embeds
+//! the profile name and a hash of its parameters, so they never mix with the
+//! official per-level leaderboard — or with an edited version of the profile.
+
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use serde::Deserialize;
+
+use crate::entities::{Difficulty, Level, PowerUpLoss};
+use crate::replay::Fnv;
+
+/// Default profile file, looked up in the working directory.
+pub const DEFAULT_PROFILES_FILE: &str = "profiles.toml";
+
+/// A selectable custom profile: the level it is based on plus its parameters.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Profile {
+    pub level: Level,
+    pub difficulty: Difficulty,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ProfileFile {
+    #[serde(default)]
+    profile: Vec<ProfileSpec>,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ProfileSpec {
+    name: String,
+    base: Option<String>,
+    enemy_move_interval: Option<u64>,
+    enemy_spawn_rate: Option<u64>,
+    enemy_fire_chance: Option<u32>,
+    spacecraft_chance: Option<f64>,
+    bonus_spawn_interval: Option<u64>,
+    clear_shots_on_hit: Option<bool>,
+    power_up_loss: Option<String>,
+}
+
+/// Load profiles from `path`.  A missing file is not an error — it simply
+/// means there are no custom profiles.
+pub fn load_profiles(path: &Path) -> Result<Vec<Profile>, String> {
+    match fs::read_to_string(path) {
+        Ok(text) => parse_profiles(&text).map_err(|e| format!("{}: {}", path.display(), e)),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(Vec::new()),
+        Err(e) => Err(format!("{}: {}", path.display(), e)),
+    }
+}
+
+pub fn parse_profiles(text: &str) -> Result<Vec<Profile>, String> {
+    let file: ProfileFile = toml::from_str(text).map_err(|e| e.to_string())?;
+    file.profile.into_iter().map(build_profile).collect()
+}
+
+fn build_profile(spec: ProfileSpec) -> Result<Profile, String> {
+    let name = spec.name.trim().to_string();
+    if name.is_empty() {
+        return Err("profile name must not be empty".to_string());
+    }
+    let err = |what: &str| format!("profile {:?}: {}", name, what);
+
+    let level = match &spec.base {
+        Some(base) => Level::parse(base).ok_or_else(|| err(&format!("unknown base {:?}", base)))?,
+        None => Level::Medium,
+    };
+    let preset = Difficulty::preset(&level);
+    let difficulty = Difficulty {
+        name: name.clone(),
+        builtin: false,
+        enemy_move_interval: spec
+            .enemy_move_interval
+            .unwrap_or(preset.enemy_move_interval),
+        enemy_spawn_rate: spec.enemy_spawn_rate.unwrap_or(preset.enemy_spawn_rate),
+        enemy_fire_chance: spec.enemy_fire_chance.unwrap_or(preset.enemy_fire_chance),
+        spacecraft_chance: spec.spacecraft_chance.unwrap_or(preset.spacecraft_chance),
+        bonus_spawn_interval: spec
+            .bonus_spawn_interval
+            .unwrap_or(preset.bonus_spawn_interval),
+        clear_shots_on_hit: spec.clear_shots_on_hit.unwrap_or(preset.clear_shots_on_hit),
+        power_up_loss: match &spec.power_up_loss {
+            Some(s) => PowerUpLoss::parse(s).ok_or_else(|| {
+                err(&format!(
+                    "power_up_loss must be \"keep\" or \"lose\", not {:?}",
+                    s
+                ))
+            })?,
+            None => preset.power_up_loss,
+        },
+    };
+
+    check_difficulty(&difficulty).map_err(|e| err(&e))?;
+
+    Ok(Profile { level, difficulty })
+}
+
+/// Longest interval a profile may ask for, in frames (over nine hours).
+pub const MAX_INTERVAL: u64 = 1_000_000;
+
+/// Reject parameters that would panic in `tick`, which uses them as modulo
+/// divisors and RNG ratios.  Shared with the replay header parser.
+pub fn check_difficulty(d: &Difficulty) -> Result<(), String> {
+    if d.enemy_move_interval == 0 || d.enemy_spawn_rate == 0 || d.bonus_spawn_interval == 0 {
+        return Err("intervals and rates must be at least 1".to_string());
+    }
+    for (field, value) in [
+        ("enemy_move_interval", d.enemy_move_interval),
+        ("enemy_spawn_rate", d.enemy_spawn_rate),
+        ("bonus_spawn_interval", d.bonus_spawn_interval),
+    ] {
+        if value > MAX_INTERVAL {
+            return Err(format!("{} must be at most {}", field, MAX_INTERVAL));
+        }
+    }
+    if d.enemy_fire_chance == 0 {
+        return Err("enemy_fire_chance must be at least 1".to_string());
+    }
+    if !(0.0..=1.0).contains(&d.spacecraft_chance) {
+        return Err("spacecraft_chance must be between 0 and 1".to_string());
+    }
+    Ok(())
+}
+
+/// Hash of the gameplay parameters (not the name), so editing a profile's
+/// numbers starts a fresh leaderboard for it.  The death rules only count
+/// when they differ from the presets', so profiles written before they
+/// existed keep their leaderboards.
+pub fn profile_hash(d: &Difficulty) -> u64 {
+    let mut h = Fnv::new();
+    h.u64(d.enemy_move_interval);
+    h.u64(d.enemy_spawn_rate);
+    h.u32(d.enemy_fire_chance);
+    h.u64(d.spacecraft_chance.to_bits());
+    h.u64(d.bonus_spawn_interval);
+    if d.clear_shots_on_hit || d.power_up_loss != PowerUpLoss::Keep {
+        h.u8(d.clear_shots_on_hit as u8);
+        h.u8(d.power_up_loss.clone() as u8);
+    }
+    h.0
+}
+
+/// The `difficulty` value scores are stored under: the plain level name for
+/// built-in presets, `custom:<name>:<hash>` for custom profiles.
+pub fn score_key(level: &Level, d: &Difficulty) -> String {
+    if d.builtin {
+        level.as_str().to_string()
+    } else {
+        format!("custom:{}:{:016x}", d.name, profile_hash(d))
+    }
+}
+
+/// The key of a two-player co-op run: `coop:` and the solo key, so team
+/// scores get a leaderboard of their own.
+pub fn coop_score_key(level: &Level, d: &Difficulty) -> String {
+    format!("coop:{}", score_key(level, d))
+}
@@ -0,0 +1,158 @@
+//! Backends that show a finished frame.
+//!
+//! `display` draws a frame into a [`Buffer`] and hands it to a [`Renderer`].
+//! [`TerminalRenderer`] puts it on a crossterm terminal, writing only the
+//! cells that changed since the previous frame; [`TextRenderer`] writes it
+//! as plain text, for logs and snapshot tests.
+
+use std::io::Write;
+
+use crossterm::{
+    cursor,
+    style::{self, Color, Print},
+    terminal, QueueableCommand,
+};
+
+use crate::buffer::{Buffer, Cell};
+
+/// Somewhere a finished frame can be shown.
+pub trait Renderer {
+    /// Show `frame`.  With `full_redraw` nothing is assumed about what is
+    /// already on display, e.g. after another screen drew over it.
+    fn present(&mut self, frame: Buffer, full_redraw: bool) -> std::io::Result<()>;
+}
+
+// ── Terminal backend ──────────────────────────────────────────────────────────
+
+/// Shows frames on a terminal through crossterm.  It keeps the frame on
+/// screen and emits only the cells that differ from it — one cursor move per
+/// run of changed cells and one colour change per change of colour — so a
+/// frame in which little moves costs a few hundred bytes instead of a repaint
+/// of the whole play area.
+pub struct TerminalRenderer<W: Write> {
+    out: W,
+    /// What the terminal shows now; `None` when unknown.
+    shown: Option<Buffer>,
+}
+
+impl<W: Write> TerminalRenderer<W> {
+    pub fn new(out: W) -> Self {
+        TerminalRenderer { out, shown: None }
+    }
+
+    /// The underlying writer, for screens drawn without the renderer.  The
+    /// next frame clears and repaints everything.
+    pub fn writer(&mut self) -> &mut W {
+        self.shown = None;
+        &mut self.out
+    }
+
+    pub fn into_inner(self) -> W {
+        self.out
+    }
+}
+
+impl<W: Write> Renderer for TerminalRenderer<W> {
+    /// The first frame, a full redraw and any frame of a different size
+    /// clear the terminal and paint every non-blank cell.
+    fn present(&mut self, frame: Buffer, full_redraw: bool) -> std::io::Result<()> {
+        let out = &mut self.out;
+        let blank;
+        let prev = match &self.shown {
+            Some(shown)
+                if !full_redraw
+                    && (shown.width(), shown.height()) == (frame.width(), frame.height()) =>
+            {
+                shown
+            }
+            _ => {
+                out.queue(style::ResetColor)?;
+                out.queue(terminal::Clear(terminal::ClearType::All))?;
+                blank = Buffer::new(frame.width(), frame.height());
+                &blank
+            }
+        };
+        write_diff(out, prev, &frame)?;
+
+        // Park cursor in a harmless spot and flush
+        out.queue(style::ResetColor)?;
+        out.queue(cursor::MoveTo(0, frame.height().saturating_sub(1)))?;
+        out.flush()?;
+        self.shown = Some(frame);
+        Ok(())
+    }
+}
+
+/// Queue the commands that turn a terminal showing `prev` into one showing
+/// `next`.  Both buffers must be the same size.
+pub fn write_diff<W: Write>(out: &mut W, prev: &Buffer, next: &Buffer) -> std::io::Result<()> {
+    // Where the terminal cursor is and which colours are set, once known.
+    let mut at: Option<(u16, u16)> = None;
+    let mut fg: Option<Color> = None;
+    let mut bg: Option<Color> = None;
+    // Consecutive changed cells in one colour are printed together.
+    let mut run = String::new();
+
+    for y in 0..next.height() {
+        for x in 0..next.width() {
+            let cell = next.cell(x, y).copied().unwrap_or(Cell::BLANK);
+            if prev.cell(x, y) == Some(&cell) {
+                continue;
+            }
+            if at != Some((x, y)) {
+                print_run(out, &mut run)?;
+                out.queue(cursor::MoveTo(x, y))?;
+            }
+            if fg != Some(cell.fg) {
+                print_run(out, &mut run)?;
+                out.queue(style::SetForegroundColor(cell.fg))?;
+                fg = Some(cell.fg);
+            }
+            if bg != Some(cell.bg) {
+                print_run(out, &mut run)?;
+                out.queue(style::SetBackgroundColor(cell.bg))?;
+                bg = Some(cell.bg);
+            }
+            run.push(cell.ch);
+            at = Some((x + 1, y));
+        }
+    }
+    print_run(out, &mut run)
+}
+
+fn print_run<W: Write>(out: &mut W, run: &mut String) -> std::io::Result<()> {
+    if !run.is_empty() {
+        out.queue(Print(run.as_str()))?;
+        run.clear();
+    }
+    Ok(())
+}
+
+// ── Plain-text backend ────────────────────────────────────────────────────────
+
+/// Writes each frame as plain text (`Buffer::to_text`) followed by a blank
+/// line.  Colours are dropped.
+pub struct TextRenderer<W: Write> {
+    out: W,
+}
+
+impl<W: Write> TextRenderer<W> {
+    pub fn new(out: W) -> Self {
+        TextRenderer { out }
+    }
+
+    /// The underlying writer, e.g. to label the next frame.
+    pub fn writer(&mut self) -> &mut W {
+        &mut self.out
+    }
+
+    pub fn into_inner(self) -> W {
+        self.out
+    }
+}
+
+impl<W: Write> Renderer for TextRenderer<W> {
+    fn present(&mut self, frame: Buffer, _full_redraw: bool) -> std::io::Result<()> {
+        writeln!(self.out, "{}", frame.to_text())
+    }
+}
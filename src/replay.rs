@@ -0,0 +1,629 @@
+//! Deterministic replay recording and playback.
+//!
+//! A run is fully determined by the RNG seed, the level, the terminal size
+//! and the ordered list of `PlayerAction`s applied before each tick.  A
+//! `Replay` stores exactly that, plus a hash of the state after every tick so
+//! playback can detect (and report) the first frame where it drifts.
+//!
+//! The on-disk format is line-oriented text so replays attached to bug
+//! reports can be read and diffed by hand:
+//!
+//! ```text
+//! shooting_game replay 2
+//! seed 12345
+//! level hard
+//! size 80 24
+//! difficulty 8 55 60 0.3 150 Bullet Hell
+//! on_hit 0 lose
+//! players 2
+//! f 9c1d2e3f4a5b6c7d L1 S 2R1
+//! f 0a1b2c3d4e5f6789
+//! ```
+
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use rand::{Rng, SeedableRng};
+
+use crate::compute::{apply_action, init_state_with, tick, with_player_two};
+use crate::entities::{
+    BonusKind, BossPattern, BulletOwner, Difficulty, EnemyBehavior, EnemyKind, EntireGameStateInfo,
+    GameRng, GameStatus, Level, PlayerAction, PlayerId, PowerUpLoss, MIN_HEIGHT, MIN_WIDTH,
+};
+use crate::profiles::check_difficulty;
+
+/// First line of every replay file; bump the number on format changes.
+/// Format 2 added the co-op and resize action tokens and hashes HP, bosses,
+/// waves and player two.
+const HEADER: &str = "shooting_game replay 2";
+const HEADER_PREFIX: &str = "shooting_game replay ";
+
+/// Inputs applied during one ticked frame and the state hash after the tick.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ReplayFrame {
+    pub actions: Vec<PlayerAction>,
+    pub hash: u64,
+}
+
+/// Everything needed to re-run a game frame for frame.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Replay {
+    pub seed: u64,
+    pub level: Level,
+    pub difficulty: Difficulty,
+    pub width: u16,
+    pub height: u16,
+    /// A two-player co-op game.
+    pub coop: bool,
+    pub frames: Vec<ReplayFrame>,
+}
+
+impl Replay {
+    pub fn new(seed: u64, level: Level, difficulty: Difficulty, width: u16, height: u16) -> Self {
+        Replay {
+            seed,
+            level,
+            difficulty,
+            width,
+            height,
+            coop: false,
+            frames: Vec::new(),
+        }
+    }
+
+    /// The initial state and RNG this replay starts from.
+    pub fn start(&self) -> (EntireGameStateInfo, GameRng) {
+        let state = init_state_with(
+            self.level.clone(),
+            self.difficulty.clone(),
+            self.width,
+            self.height,
+            0,
+        );
+        let state = if self.coop {
+            with_player_two(&state)
+        } else {
+            state
+        };
+        (state, GameRng::seed_from_u64(self.seed))
+    }
+
+    /// Append one ticked frame.
+    pub fn record(&mut self, actions: Vec<PlayerAction>, state: &EntireGameStateInfo) {
+        self.frames.push(ReplayFrame {
+            actions,
+            hash: state_hash(state),
+        });
+    }
+
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        fs::write(path, self.to_text())
+    }
+
+    pub fn load(path: &Path) -> io::Result<Replay> {
+        let text = fs::read_to_string(path)?;
+        Replay::from_text(&text).map_err(|msg| io::Error::new(io::ErrorKind::InvalidData, msg))
+    }
+
+    pub fn to_text(&self) -> String {
+        let mut out = format!(
+            "{}\nseed {}\nlevel {}\nsize {} {}\n",
+            HEADER,
+            self.seed,
+            self.level.as_str(),
+            self.width,
+            self.height
+        );
+        // Built-in presets are implied by `level`; only custom profiles are spelled out.
+        if !self.difficulty.builtin {
+            let d = &self.difficulty;
+            out.push_str(&format!(
+                "difficulty {} {} {} {} {} {}\n",
+                d.enemy_move_interval,
+                d.enemy_spawn_rate,
+                d.enemy_fire_chance,
+                d.spacecraft_chance,
+                d.bonus_spawn_interval,
+                d.name
+            ));
+            out.push_str(&format!(
+                "on_hit {} {}\n",
+                d.clear_shots_on_hit as u8,
+                d.power_up_loss.as_str()
+            ));
+        }
+        if self.coop {
+            out.push_str("players 2\n");
+        }
+        for frame in &self.frames {
+            out.push_str(&format!("f {:016x}", frame.hash));
+            for action in &frame.actions {
+                out.push(' ');
+                out.push_str(&action_token(action));
+            }
+            out.push('\n');
+        }
+        out
+    }
+
+    /// Parse the text format.  Errors name the offending line.
+    pub fn from_text(text: &str) -> Result<Replay, String> {
+        let mut lines = text.lines().enumerate();
+        match lines.next() {
+            Some((_, HEADER)) => {}
+            Some((_, other)) if other.starts_with(HEADER_PREFIX) => {
+                return Err(format!(
+                    "replay format {} is not supported (this version reads format {})",
+                    &other[HEADER_PREFIX.len()..],
+                    &HEADER[HEADER_PREFIX.len()..]
+                ))
+            }
+            Some((_, other)) => return Err(format!("not a replay file (header {:?})", other)),
+            None => return Err("empty replay file".to_string()),
+        }
+
+        let mut seed = None;
+        let mut level = None;
+        let mut size = None;
+        let mut difficulty = None;
+        let mut on_hit = None;
+        let mut coop = false;
+        let mut frames = Vec::new();
+
+        for (i, line) in lines {
+            let err = |what: &str| format!("line {}: {}", i + 1, what);
+            let mut words = line.split_whitespace();
+            match words.next() {
+                None => {}
+                Some("seed") => {
+                    let v = words.next().and_then(|w| w.parse().ok());
+                    seed = Some(v.ok_or_else(|| err("bad seed"))?);
+                }
+                Some("level") => {
+                    let v = words.next().and_then(Level::parse);
+                    level = Some(v.ok_or_else(|| err("bad level"))?);
+                }
+                Some("size") => {
+                    let w = words.next().and_then(|w| w.parse().ok());
+                    let h = words.next().and_then(|h| h.parse().ok());
+                    match (w, h) {
+                        (Some(w), Some(h)) if w >= MIN_WIDTH && h >= MIN_HEIGHT => {
+                            size = Some((w, h))
+                        }
+                        (Some(w), Some(h)) => {
+                            return Err(err(&format!(
+                                "size {}x{} is below the minimum {}x{}",
+                                w, h, MIN_WIDTH, MIN_HEIGHT
+                            )))
+                        }
+                        _ => return Err(err("bad size")),
+                    }
+                }
+                Some("difficulty") => {
+                    let bad = |field: &str| err(&format!("bad difficulty {}", field));
+                    let mut num = |field: &str| words.next().ok_or_else(|| bad(field));
+                    let enemy_move_interval = num("enemy_move_interval")?
+                        .parse()
+                        .map_err(|_| bad("enemy_move_interval"))?;
+                    let enemy_spawn_rate = num("enemy_spawn_rate")?
+                        .parse()
+                        .map_err(|_| bad("enemy_spawn_rate"))?;
+                    let enemy_fire_chance = num("enemy_fire_chance")?
+                        .parse()
+                        .map_err(|_| bad("enemy_fire_chance"))?;
+                    let spacecraft_chance = num("spacecraft_chance")?
+                        .parse()
+                        .map_err(|_| bad("spacecraft_chance"))?;
+                    let bonus_spawn_interval = num("bonus_spawn_interval")?
+                        .parse()
+                        .map_err(|_| bad("bonus_spawn_interval"))?;
+                    let d = Difficulty {
+                        name: words.collect::<Vec<_>>().join(" "),
+                        builtin: false,
+                        enemy_move_interval,
+                        enemy_spawn_rate,
+                        enemy_fire_chance,
+                        spacecraft_chance,
+                        bonus_spawn_interval,
+                        // Filled in from `on_hit` below.
+                        clear_shots_on_hit: false,
+                        power_up_loss: PowerUpLoss::Keep,
+                    };
+                    check_difficulty(&d).map_err(|e| err(&format!("bad difficulty: {}", e)))?;
+                    difficulty = Some(d);
+                }
+                Some("on_hit") => {
+                    let clear = match words.next() {
+                        Some("0") => false,
+                        Some("1") => true,
+                        _ => return Err(err("bad on_hit")),
+                    };
+                    let loss = words.next().and_then(PowerUpLoss::parse);
+                    on_hit = Some((clear, loss.ok_or_else(|| err("bad on_hit"))?));
+                }
+                Some("players") => match words.next() {
+                    Some("1") => coop = false,
+                    Some("2") => coop = true,
+                    _ => return Err(err("bad players")),
+                },
+                Some("f") => {
+                    let hash = words
+                        .next()
+                        .and_then(|h| u64::from_str_radix(h, 16).ok())
+                        .ok_or_else(|| err("bad frame hash"))?;
+                    let actions = words
+                        .map(|w| parse_action(w).ok_or_else(|| err(&format!("bad action {:?}", w))))
+                        .collect::<Result<Vec<_>, _>>()?;
+                    frames.push(ReplayFrame { actions, hash });
+                }
+                Some(other) => return Err(err(&format!("unknown record {:?}", other))),
+            }
+        }
+
+        let (width, height) = size.ok_or("missing size")?;
+        let level: Level = level.ok_or("missing level")?;
+        // A custom profile spells out its death rules too.
+        let difficulty = match difficulty {
+            Some(d) => {
+                let (clear_shots_on_hit, power_up_loss) = on_hit.ok_or("missing on_hit")?;
+                Difficulty {
+                    clear_shots_on_hit,
+                    power_up_loss,
+                    ..d
+                }
+            }
+            None => Difficulty::preset(&level),
+        };
+        Ok(Replay {
+            seed: seed.ok_or("missing seed")?,
+            difficulty,
+            level,
+            width,
+            height,
+            coop,
+            frames,
+        })
+    }
+}
+
+// ── Playback ──────────────────────────────────────────────────────────────────
+
+/// Apply one frame's recorded inputs, then tick if the game is still running.
+pub fn step(
+    state: &EntireGameStateInfo,
+    frame: &ReplayFrame,
+    rng: &mut impl Rng,
+) -> EntireGameStateInfo {
+    let state = frame
+        .actions
+        .iter()
+        .fold(state.clone(), |s, action| apply_action(&s, action));
+    if state.status == GameStatus::Playing {
+        tick(&state, rng)
+    } else {
+        state
+    }
+}
+
+/// The first frame whose state hash did not match the recording.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Drift {
+    /// Zero-based index into `Replay::frames`.
+    pub frame: usize,
+    pub expected: u64,
+    pub actual: u64,
+}
+
+/// Running result of comparing playback against the recorded hashes.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ReplayCheck {
+    pub frames_checked: usize,
+    pub mismatches: usize,
+    pub first_drift: Option<Drift>,
+}
+
+impl ReplayCheck {
+    pub fn check(&mut self, index: usize, frame: &ReplayFrame, state: &EntireGameStateInfo) {
+        self.frames_checked += 1;
+        let actual = state_hash(state);
+        if actual != frame.hash {
+            self.mismatches += 1;
+            self.first_drift.get_or_insert(Drift {
+                frame: index,
+                expected: frame.hash,
+                actual,
+            });
+        }
+    }
+
+    pub fn is_clean(&self) -> bool {
+        self.first_drift.is_none()
+    }
+}
+
+impl fmt::Display for ReplayCheck {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.first_drift {
+            None => write!(f, "replay OK: {} frames verified", self.frames_checked),
+            Some(d) => write!(
+                f,
+                "replay DRIFTED: {} of {} frames mismatched, first at frame {} \
+                 (expected {:016x}, got {:016x})",
+                self.mismatches, self.frames_checked, d.frame, d.expected, d.actual
+            ),
+        }
+    }
+}
+
+/// Re-run a replay without a terminal and compare every frame's hash.
+pub fn verify(replay: &Replay) -> ReplayCheck {
+    let (mut state, mut rng) = replay.start();
+    let mut check = ReplayCheck::default();
+    for (i, frame) in replay.frames.iter().enumerate() {
+        if state.status != GameStatus::Playing {
+            break;
+        }
+        state = step(&state, frame, &mut rng);
+        check.check(i, frame, &state);
+    }
+    check
+}
+
+// ── State hash ────────────────────────────────────────────────────────────────
+
+/// FNV-1a over every field that influences gameplay.
+///
+/// Hand-rolled rather than `std::hash` so the value is stable across Rust
+/// versions and platforms.  `high_score` (loaded from the local database),
+/// `debug_mode` and `slow_mo` are display-only and deliberately excluded,
+/// as are the run `stats`, which only tally what the hashed fields did.
+pub fn state_hash(state: &EntireGameStateInfo) -> u64 {
+    let mut h = Fnv::new();
+
+    h.i32(state.player.x);
+    h.i32(state.player.y);
+    h.u32(state.player.lives);
+    h.u32(state.player.invulnerable);
+    h.u32(state.wave.number);
+    h.u32(state.wave.remaining);
+    h.u32(state.wave.breather);
+    h.u8(state.wave.boss_due as u8);
+
+    h.u32(state.enemies.len() as u32);
+    for e in &state.enemies {
+        h.i32(e.x);
+        h.i32(e.y);
+        h.u8(match e.kind {
+            EnemyKind::Spacecraft => 0,
+            EnemyKind::Octopus => 1,
+            EnemyKind::Armored => 2,
+        });
+        h.u32(e.hp);
+        match e.behavior {
+            EnemyBehavior::Straight => h.u8(0),
+            EnemyBehavior::Zigzag { dir, steps } => {
+                h.u8(1);
+                h.i32(dir);
+                h.u32(steps);
+            }
+            EnemyBehavior::Diver { dive } => {
+                h.u8(2);
+                h.u32(dive);
+            }
+            EnemyBehavior::Formation => h.u8(3),
+        }
+    }
+    h.u32(state.bullets.len() as u32);
+    for b in &state.bullets {
+        h.i32(b.x);
+        h.i32(b.y);
+        h.u8(match b.owner {
+            BulletOwner::Player(PlayerId::One) => 0,
+            BulletOwner::Enemy => 1,
+            BulletOwner::Player(PlayerId::Two) => 2,
+        });
+    }
+    h.u32(state.enemy_shots.len() as u32);
+    for shot in &state.enemy_shots {
+        h.u32(shot.x.to_bits());
+        h.u32(shot.y.to_bits());
+        h.u32(shot.vx.to_bits());
+        h.u32(shot.vy.to_bits());
+    }
+    match &state.boss {
+        Some(b) => {
+            h.u8(1);
+            h.i32(b.x);
+            h.i32(b.y);
+            h.u32(b.hp);
+            h.u32(b.max_hp);
+            h.i32(b.dir);
+            h.u8(match b.pattern {
+                BossPattern::Aimed => 0,
+                BossPattern::Fan => 1,
+                BossPattern::Sweep => 2,
+            });
+            h.u32(b.pattern_frame);
+        }
+        None => h.u8(0),
+    }
+    h.u32(state.flame_bullets.len() as u32);
+    for fb in &state.flame_bullets {
+        h.u32(fb.x.to_bits());
+        h.u32(fb.y.to_bits());
+        h.u32(fb.vx.to_bits());
+        hash_owner(&mut h, fb.owner);
+    }
+    h.u32(state.firebombs.len() as u32);
+    for bomb in &state.firebombs {
+        h.i32(bomb.x);
+        h.i32(bomb.y);
+        h.u32(bomb.fuse);
+        hash_owner(&mut h, bomb.owner);
+    }
+    h.u32(state.explosions.len() as u32);
+    for exp in &state.explosions {
+        h.i32(exp.x);
+        h.i32(exp.y);
+        h.u32(exp.frames);
+    }
+    h.u32(state.bonus_items.len() as u32);
+    for b in &state.bonus_items {
+        h.i32(b.x);
+        h.i32(b.y);
+        h.u8(bonus_tag(&b.kind));
+    }
+    hash_power_up(&mut h, &state.active_power_up);
+    match &state.player2 {
+        Some(p2) => {
+            h.u8(1);
+            h.i32(p2.ship.x);
+            h.i32(p2.ship.y);
+            h.u32(p2.ship.lives);
+            h.u32(p2.ship.invulnerable);
+            hash_power_up(&mut h, &p2.active_power_up);
+            h.u32(p2.score);
+        }
+        None => h.u8(0),
+    }
+
+    h.u32(state.score);
+    h.u8(match state.level {
+        Level::Easy => 0,
+        Level::Medium => 1,
+        Level::Hard => 2,
+        Level::Extreme => 3,
+    });
+    h.u8(match state.status {
+        GameStatus::Playing => 0,
+        GameStatus::GameOver => 1,
+        GameStatus::Paused(_) => 2,
+    });
+    h.u64(state.frame);
+    h.u32(state.width as u32);
+    h.u32(state.height as u32);
+    h.u8(state.god_mode as u8);
+    h.u32(state.muzzle_flash);
+    match &state.cheer_msg {
+        Some((msg, frames)) => {
+            h.bytes(msg.as_bytes());
+            h.u32(*frames);
+        }
+        None => h.u8(0xff),
+    }
+
+    h.0
+}
+
+/// 64-bit FNV-1a, shared with `profiles` for profile hashes.
+pub(crate) struct Fnv(pub(crate) u64);
+
+impl Fnv {
+    pub(crate) fn new() -> Self {
+        Fnv(0xcbf2_9ce4_8422_2325)
+    }
+
+    pub(crate) fn bytes(&mut self, bytes: &[u8]) {
+        for &b in bytes {
+            self.0 ^= b as u64;
+            self.0 = self.0.wrapping_mul(0x0000_0100_0000_01b3);
+        }
+    }
+
+    pub(crate) fn u8(&mut self, v: u8) {
+        self.bytes(&[v]);
+    }
+
+    pub(crate) fn u32(&mut self, v: u32) {
+        self.bytes(&v.to_le_bytes());
+    }
+
+    pub(crate) fn i32(&mut self, v: i32) {
+        self.bytes(&v.to_le_bytes());
+    }
+
+    pub(crate) fn u64(&mut self, v: u64) {
+        self.bytes(&v.to_le_bytes());
+    }
+}
+
+fn hash_power_up(h: &mut Fnv, power_up: &Option<(BonusKind, u32)>) {
+    match power_up {
+        Some((kind, frames)) => {
+            h.u8(bonus_tag(kind));
+            h.u32(*frames);
+        }
+        None => h.u8(0xff),
+    }
+}
+
+/// Player two's flames and bombs are tagged; player one's add nothing.
+fn hash_owner(h: &mut Fnv, owner: PlayerId) {
+    if owner == PlayerId::Two {
+        h.u8(2);
+    }
+}
+
+fn bonus_tag(kind: &BonusKind) -> u8 {
+    match kind {
+        BonusKind::SpreadShot => 0,
+        BonusKind::ExtraLife => 1,
+        BonusKind::RapidFire => 2,
+        BonusKind::FlameBurst => 3,
+        BonusKind::Firebomb => 4,
+    }
+}
+
+// ── Text encoding helpers ─────────────────────────────────────────────────────
+
+/// `L<n>` / `R<n>` move, `S` shoot, `dbg` / `god` / `slow` debug toggles,
+/// `size<w>x<h>` terminal resize.  Player two's inputs carry a `2` prefix:
+/// `2L<n>`, `2R<n>`, `2S`, and `2X` when player two leaves.
+pub(crate) fn action_token(action: &PlayerAction) -> String {
+    match action {
+        PlayerAction::MoveLeft(n) => format!("L{}", n),
+        PlayerAction::MoveRight(n) => format!("R{}", n),
+        PlayerAction::Shoot => "S".to_string(),
+        PlayerAction::ToggleDebug => "dbg".to_string(),
+        PlayerAction::ToggleGodMode => "god".to_string(),
+        PlayerAction::ToggleSlowMo => "slow".to_string(),
+        PlayerAction::Resize(w, h) => format!("size{}x{}", w, h),
+        PlayerAction::P2MoveLeft(n) => format!("2L{}", n),
+        PlayerAction::P2MoveRight(n) => format!("2R{}", n),
+        PlayerAction::P2Shoot => "2S".to_string(),
+        PlayerAction::P2Leave => "2X".to_string(),
+    }
+}
+
+pub(crate) fn parse_action(token: &str) -> Option<PlayerAction> {
+    match token {
+        "S" => Some(PlayerAction::Shoot),
+        "2S" => Some(PlayerAction::P2Shoot),
+        "2X" => Some(PlayerAction::P2Leave),
+        "dbg" => Some(PlayerAction::ToggleDebug),
+        "god" => Some(PlayerAction::ToggleGodMode),
+        "slow" => Some(PlayerAction::ToggleSlowMo),
+        _ if token.starts_with("size") => {
+            let (w, h) = token["size".len()..].split_once('x')?;
+            Some(PlayerAction::Resize(w.parse().ok()?, h.parse().ok()?))
+        }
+        _ if token.starts_with('2') => {
+            let n = token.get(2..)?.parse().ok()?;
+            match token.as_bytes().get(1)? {
+                b'L' => Some(PlayerAction::P2MoveLeft(n)),
+                b'R' => Some(PlayerAction::P2MoveRight(n)),
+                _ => None,
+            }
+        }
+        _ => {
+            let n = token.get(1..)?.parse().ok()?;
+            match token.as_bytes()[0] {
+                b'L' => Some(PlayerAction::MoveLeft(n)),
+                b'R' => Some(PlayerAction::MoveRight(n)),
+                _ => None,
+            }
+        }
+    }
+}
@@ -0,0 +1,116 @@
+//! Saved games: a run put aside mid-game and continued later from the menu.
+//!
+//! The file is JSON holding the whole `EntireGameStateInfo` and the RNG
+//! state, so a continued run plays out exactly as it would have without the
+//! break:
+//!
+//! ```json
+//! { "version": 2, "state": { "player": { "x": 40, ... }, ... }, "rng": { ... } }
+//! ```
+//!
+//! `version` is checked before anything else is read; a save written by
+//! another version of the game is turned down with a message instead of
+//! being half-loaded.
+
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use crate::entities::{EntireGameStateInfo, GameRng, GameStatus};
+
+/// Default save file, kept in the working directory.
+pub const DEFAULT_SAVE_FILE: &str = "savegame.json";
+
+/// Bump whenever a change to the entities would break older saves.
+pub const SAVE_VERSION: u32 = 2;
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct SaveGame {
+    pub version: u32,
+    pub state: EntireGameStateInfo,
+    pub rng: GameRng,
+}
+
+/// The state has no `PartialEq` of its own: two saves are equal when they
+/// would write the same file.
+impl PartialEq for SaveGame {
+    fn eq(&self, other: &Self) -> bool {
+        self.to_json() == other.to_json()
+    }
+}
+
+/// Just the version, read first so an old save fails on that alone.
+#[derive(Deserialize)]
+struct Version {
+    version: u32,
+}
+
+impl SaveGame {
+    pub fn new(state: &EntireGameStateInfo, rng: &GameRng) -> Self {
+        SaveGame {
+            version: SAVE_VERSION,
+            state: state.clone(),
+            rng: rng.clone(),
+        }
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).expect("game state always serializes")
+    }
+
+    pub fn from_json(text: &str) -> Result<SaveGame, String> {
+        let Version { version } =
+            serde_json::from_str(text).map_err(|_| "not a saved game".to_string())?;
+        if version != SAVE_VERSION {
+            let age = if version < SAVE_VERSION {
+                "an older"
+            } else {
+                "a newer"
+            };
+            return Err(format!(
+                "saved by {} version of the game (format {}, this one reads {})",
+                age, version, SAVE_VERSION
+            ));
+        }
+        serde_json::from_str(text).map_err(|e| format!("damaged saved game: {}", e))
+    }
+
+    /// One line for the menu, e.g. `Hard · wave 3 · 1200 pts`.
+    pub fn summary(&self) -> String {
+        let s = &self.state;
+        let players = if s.player2.is_some() { " · co-op" } else { "" };
+        format!(
+            "{} · wave {} · {} pts{}",
+            s.difficulty.name, s.wave.number, s.score, players
+        )
+    }
+}
+
+/// Whether a game in `state` is worth saving: it is under way and not over.
+pub fn can_save(state: &EntireGameStateInfo) -> bool {
+    state.status != GameStatus::GameOver
+}
+
+pub fn save(path: &Path, game: &SaveGame) -> io::Result<()> {
+    fs::write(path, game.to_json())
+}
+
+/// Load the save at `path`.  No file is `Ok(None)`; a file that cannot be
+/// continued is an error saying why.
+pub fn load(path: &Path) -> Result<Option<SaveGame>, String> {
+    match fs::read_to_string(path) {
+        Ok(text) => SaveGame::from_json(&text).map(Some),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(None),
+        Err(e) => Err(e.to_string()),
+    }
+}
+
+/// Delete the save, e.g. once it has been continued.  A missing file is fine.
+pub fn remove(path: &Path) -> io::Result<()> {
+    match fs::remove_file(path) {
+        Err(e) if e.kind() != io::ErrorKind::NotFound => Err(e),
+        _ => Ok(()),
+    }
+}
@@ -0,0 +1,55 @@
+//! Player preferences, kept in a small TOML file next to the profiles.
+//!
+//! ```toml
+//! pause_on_focus_loss = true
+//! mouse_control = false
+//! ```
+//!
+//! Every key is optional; missing keys take their default, so files written
+//! by older versions keep loading as new settings are added.
+
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+/// Default settings file, looked up in the working directory.
+pub const DEFAULT_SETTINGS_FILE: &str = "settings.toml";
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Settings {
+    /// Pause the game when the terminal window loses focus.
+    pub pause_on_focus_loss: bool,
+    /// Steer the ship with the mouse and fire with the left button.
+    pub mouse_control: bool,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Settings {
+            pause_on_focus_loss: true,
+            mouse_control: false,
+        }
+    }
+}
+
+/// Load settings from `path`.  A missing file is not an error — it simply
+/// means everything is at its default.
+pub fn load_settings(path: &Path) -> Result<Settings, String> {
+    match fs::read_to_string(path) {
+        Ok(text) => parse_settings(&text).map_err(|e| format!("{}: {}", path.display(), e)),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(Settings::default()),
+        Err(e) => Err(format!("{}: {}", path.display(), e)),
+    }
+}
+
+pub fn parse_settings(text: &str) -> Result<Settings, String> {
+    toml::from_str(text).map_err(|e| e.to_string())
+}
+
+pub fn save_settings(path: &Path, settings: &Settings) -> io::Result<()> {
+    let text = toml::to_string(settings).map_err(io::Error::other)?;
+    fs::write(path, text)
+}
@@ -0,0 +1,229 @@
+//! Headless simulation for balance testing.
+//!
+//! Runs `init_state` + the demo bot + `tick` with no terminal, one seeded
+//! game at a time, and aggregates what happened from each tick's
+//! `GameEvent`s.  Used by the `simulate` binary.
+
+use std::collections::BTreeMap;
+
+use rand::SeedableRng;
+use serde::Serialize;
+
+use crate::compute::{init_state_with, tick};
+use crate::demo::update_demo;
+use crate::entities::{
+    BonusKind, Difficulty, EnemyKind, EntireGameStateInfo, GameEvent, GameRng, GameStatus,
+    HitCause, Level, RunStats,
+};
+
+/// Outcome of one simulated game.
+#[derive(Clone, Debug, PartialEq)]
+pub struct GameSummary {
+    /// `Difficulty::name` the game was played on.
+    pub profile: String,
+    pub seed: u64,
+    /// Frames survived (equals the frame cap when the bot never died).
+    pub frames: u64,
+    pub score: u32,
+    /// Wave the game ended on.
+    pub wave: u32,
+    /// Kills keyed by `enemy_label`, plus `"boss"`.
+    pub kills: BTreeMap<&'static str, u32>,
+    /// Power-ups caught keyed by `bonus_label`.
+    pub power_ups: BTreeMap<&'static str, u32>,
+    /// Lives lost keyed by `cause_label`.
+    pub hits: BTreeMap<&'static str, u32>,
+    /// Cause of the life loss that ended the game; `None` if it hit the cap.
+    pub death: Option<HitCause>,
+    /// The game's own tally, as saved to the scores database.
+    pub stats: RunStats,
+}
+
+/// Play one game with the demo bot until game over or `max_frames`.
+pub fn simulate_game(
+    level: Level,
+    difficulty: &Difficulty,
+    seed: u64,
+    width: u16,
+    height: u16,
+    max_frames: u64,
+) -> GameSummary {
+    simulate_game_observed(
+        level,
+        difficulty,
+        seed,
+        width,
+        height,
+        max_frames,
+        &mut |_| {},
+    )
+}
+
+/// `simulate_game`, calling `observe` with the state after every tick —
+/// e.g. to render frames to a log.
+pub fn simulate_game_observed(
+    level: Level,
+    difficulty: &Difficulty,
+    seed: u64,
+    width: u16,
+    height: u16,
+    max_frames: u64,
+    observe: &mut dyn FnMut(&EntireGameStateInfo),
+) -> GameSummary {
+    // The game's own RNG, so a simulated seed replays the same in a real game.
+    let mut rng = GameRng::seed_from_u64(seed);
+    let mut state = init_state_with(level, difficulty.clone(), width, height, 0);
+    let mut summary = GameSummary {
+        profile: difficulty.name.clone(),
+        seed,
+        frames: 0,
+        score: 0,
+        wave: 1,
+        kills: BTreeMap::new(),
+        power_ups: BTreeMap::new(),
+        hits: BTreeMap::new(),
+        death: None,
+        stats: RunStats::default(),
+    };
+
+    // Same order as the game loop: bot input first, then the tick.
+    while state.status == GameStatus::Playing && state.frame < max_frames {
+        state = update_demo(&state);
+        state = tick(&state, &mut rng);
+        observe(&state);
+        for event in &state.events {
+            match event {
+                GameEvent::EnemyKilled(kind) => {
+                    *summary.kills.entry(enemy_label(kind)).or_default() += 1;
+                }
+                GameEvent::PowerUpCaught(kind) => {
+                    *summary.power_ups.entry(bonus_label(kind)).or_default() += 1;
+                }
+                GameEvent::PlayerHit(cause) => {
+                    *summary.hits.entry(cause_label(cause)).or_default() += 1;
+                    summary.death = Some(cause.clone());
+                }
+                GameEvent::BossDefeated => {
+                    *summary.kills.entry("boss").or_default() += 1;
+                }
+                GameEvent::WaveCleared(_) => {}
+            }
+        }
+    }
+
+    summary.frames = state.frame;
+    summary.score = state.score;
+    summary.wave = state.wave.number;
+    summary.stats = state.stats;
+    if state.status == GameStatus::Playing {
+        summary.death = None;
+    }
+    summary
+}
+
+/// Aggregate statistics over many games on one difficulty profile.
+/// Serializes with the field names of `simulate --format json`.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct ProfileReport {
+    pub profile: String,
+    pub games: usize,
+    #[serde(rename = "survival_frames")]
+    pub frames: Distribution,
+    #[serde(rename = "score")]
+    pub scores: Distribution,
+    #[serde(rename = "wave_reached")]
+    pub waves: Distribution,
+    pub kills: BTreeMap<&'static str, u32>,
+    pub power_ups: BTreeMap<&'static str, u32>,
+    /// Game-ending causes, plus `"survived"` for games that hit the frame cap.
+    #[serde(rename = "game_ended_by")]
+    pub deaths: BTreeMap<&'static str, u32>,
+    /// Every life lost, keyed by `cause_label`.
+    #[serde(rename = "lives_lost_to")]
+    pub hits: BTreeMap<&'static str, u32>,
+}
+
+impl ProfileReport {
+    pub fn from_games(profile: &str, games: &[GameSummary]) -> ProfileReport {
+        let mut report = ProfileReport {
+            profile: profile.to_string(),
+            games: games.len(),
+            frames: Distribution::of(games.iter().map(|g| g.frames).collect()),
+            scores: Distribution::of(games.iter().map(|g| g.score as u64).collect()),
+            waves: Distribution::of(games.iter().map(|g| g.wave as u64).collect()),
+            kills: BTreeMap::new(),
+            power_ups: BTreeMap::new(),
+            deaths: BTreeMap::new(),
+            hits: BTreeMap::new(),
+        };
+        for g in games {
+            for (k, n) in &g.kills {
+                *report.kills.entry(k).or_default() += n;
+            }
+            for (k, n) in &g.power_ups {
+                *report.power_ups.entry(k).or_default() += n;
+            }
+            for (k, n) in &g.hits {
+                *report.hits.entry(k).or_default() += n;
+            }
+            let death = g.death.as_ref().map_or("survived", cause_label);
+            *report.deaths.entry(death).or_default() += 1;
+        }
+        report
+    }
+}
+
+/// Summary of a set of samples.  All zero for an empty set.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize)]
+pub struct Distribution {
+    pub min: u64,
+    pub p25: u64,
+    pub median: u64,
+    pub p75: u64,
+    pub max: u64,
+    pub mean: f64,
+}
+
+impl Distribution {
+    pub fn of(mut samples: Vec<u64>) -> Distribution {
+        if samples.is_empty() {
+            return Distribution::default();
+        }
+        samples.sort_unstable();
+        // Nearest-rank percentile.
+        let pct = |p: usize| samples[(samples.len() - 1) * p / 100];
+        Distribution {
+            min: samples[0],
+            p25: pct(25),
+            median: pct(50),
+            p75: pct(75),
+            max: samples[samples.len() - 1],
+            mean: samples.iter().sum::<u64>() as f64 / samples.len() as f64,
+        }
+    }
+}
+
+pub fn enemy_label(kind: &EnemyKind) -> &'static str {
+    match kind {
+        EnemyKind::Spacecraft => "spacecraft",
+        EnemyKind::Octopus => "octopus",
+        EnemyKind::Armored => "armored",
+    }
+}
+
+pub fn bonus_label(kind: &BonusKind) -> &'static str {
+    match kind {
+        BonusKind::SpreadShot => "spread_shot",
+        BonusKind::ExtraLife => "extra_life",
+        BonusKind::RapidFire => "rapid_fire",
+        BonusKind::FlameBurst => "flame_burst",
+        BonusKind::Firebomb => "firebomb",
+    }
+}
+
+pub fn cause_label(cause: &HitCause) -> &'static str {
+    match cause {
+        HitCause::EnemyBullet => "enemy_bullet",
+        HitCause::EnemyContact => "enemy_contact",
+    }
+}
@@ -0,0 +1,33 @@
+-- shooting_game.db as the first release left it: the original tables, no
+-- wave or co-op columns, and PRAGMA user_version still 0.
+CREATE TABLE top_scores (
+    id          INTEGER PRIMARY KEY AUTOINCREMENT,
+    username    TEXT    NOT NULL,
+    difficulty  TEXT    NOT NULL,
+    points      INTEGER NOT NULL DEFAULT 0,
+    created_at  TEXT    NOT NULL DEFAULT (datetime('now')),
+    updated_at  TEXT    NOT NULL DEFAULT (datetime('now')),
+    deleted_at  TEXT,
+    UNIQUE(username, difficulty)
+);
+CREATE TABLE scores (
+    id          INTEGER PRIMARY KEY AUTOINCREMENT,
+    username    TEXT    NOT NULL,
+    difficulty  TEXT    NOT NULL,
+    points      INTEGER NOT NULL DEFAULT 0,
+    created_at  TEXT    NOT NULL DEFAULT (datetime('now')),
+    deleted_at  TEXT
+);
+
+INSERT INTO top_scores (username, difficulty, points, created_at, updated_at, deleted_at) VALUES
+    ('alice', 'hard',   4200, '2024-03-01 20:00:00', '2024-03-02 21:15:00', NULL),
+    ('bob',   'hard',   3100, '2024-03-01 20:30:00', '2024-03-01 20:30:00', NULL),
+    ('alice', 'easy',    900, '2024-03-03 19:00:00', '2024-03-03 19:00:00', NULL),
+    ('carol', 'hard',   9900, '2024-03-04 18:00:00', '2024-03-04 18:00:00', '2024-04-01 09:00:00');
+
+INSERT INTO scores (username, difficulty, points, created_at, deleted_at) VALUES
+    ('alice', 'hard', 1500, '2024-03-01 20:00:00', NULL),
+    ('bob',   'hard', 3100, '2024-03-01 20:30:00', NULL),
+    ('alice', 'hard', 4200, '2024-03-02 21:15:00', NULL),
+    ('alice', 'easy',  900, '2024-03-03 19:00:00', NULL),
+    ('carol', 'hard', 9900, '2024-03-04 18:00:00', '2024-04-01 09:00:00');
@@ -0,0 +1,128 @@
+use shooting_game::bindings::*;
+
+use std::path::PathBuf;
+
+use crossterm::event::KeyCode;
+
+fn temp_path(name: &str) -> PathBuf {
+    std::env::temp_dir().join(format!("shooting_game_{}_{}", std::process::id(), name))
+}
+
+// ── Defaults ──────────────────────────────────────────────────────────────────
+
+#[test]
+fn defaults_match_the_classic_keys() {
+    let b = Bindings::default();
+    assert_eq!(b.actions_for(KeyCode::Left), vec![Action::MoveLeft]);
+    assert_eq!(b.actions_for(KeyCode::Char('a')), vec![Action::MoveLeft]);
+    assert_eq!(b.actions_for(KeyCode::Char('D')), vec![Action::MoveRight]);
+    assert_eq!(b.actions_for(KeyCode::Char('F')), vec![Action::Fast]);
+    assert_eq!(b.actions_for(KeyCode::Char('w')), vec![Action::Warp]);
+    assert_eq!(b.actions_for(KeyCode::Char(' ')), vec![Action::Shoot]);
+    assert_eq!(b.actions_for(KeyCode::Char('`')), vec![Action::Debug]);
+    assert_eq!(b.actions_for(KeyCode::Char('Q')), vec![Action::Quit]);
+    assert_eq!(b.actions_for(KeyCode::Char('j')), vec![Action::P2MoveLeft]);
+    assert_eq!(b.actions_for(KeyCode::Char('l')), vec![Action::P2MoveRight]);
+    assert_eq!(b.actions_for(KeyCode::Char('i')), vec![Action::P2Shoot]);
+}
+
+#[test]
+fn esc_both_pauses_and_quits() {
+    let b = Bindings::default();
+    assert_eq!(
+        b.actions_for(KeyCode::Esc),
+        vec![Action::Pause, Action::Quit]
+    );
+}
+
+#[test]
+fn unbound_key_triggers_nothing() {
+    assert!(Bindings::default()
+        .actions_for(KeyCode::Char('z'))
+        .is_empty());
+}
+
+// ── Parsing ───────────────────────────────────────────────────────────────────
+
+#[test]
+fn empty_file_uses_defaults() {
+    assert_eq!(parse_bindings("").unwrap(), Bindings::default());
+}
+
+#[test]
+fn missing_file_uses_defaults() {
+    let b = load_bindings(&temp_path("no_keys.toml")).unwrap();
+    assert_eq!(b, Bindings::default());
+}
+
+#[test]
+fn entry_replaces_only_its_action() {
+    let b = parse_bindings(r#"shoot = ["k", "Enter"]"#).unwrap();
+    assert_eq!(b.keys(Action::Shoot), &[KeyCode::Char('k'), KeyCode::Enter]);
+    assert!(!b.triggers(Action::Shoot, KeyCode::Char(' ')));
+    assert!(b.triggers(Action::MoveLeft, KeyCode::Char('a')));
+}
+
+#[test]
+fn key_names_are_case_insensitive() {
+    assert_eq!(parse_key("space"), Some(KeyCode::Char(' ')));
+    assert_eq!(parse_key("ESC"), Some(KeyCode::Esc));
+    assert_eq!(parse_key("J"), Some(KeyCode::Char('j')));
+    assert_eq!(parse_key("f5"), Some(KeyCode::F(5)));
+    assert_eq!(parse_key("F13"), None);
+    assert_eq!(parse_key("Hyper"), None);
+}
+
+#[test]
+fn unknown_action_is_rejected() {
+    let err = parse_bindings(r#"jump = ["j"]"#).unwrap_err();
+    assert!(err.contains("jump"), "{err}");
+}
+
+#[test]
+fn unknown_key_is_rejected() {
+    let err = parse_bindings(r#"shoot = ["Spcae"]"#).unwrap_err();
+    assert!(err.contains("Spcae"), "{err}");
+}
+
+#[test]
+fn bindings_round_trip() {
+    let path = temp_path("keys_round_trip.toml");
+    let mut b = Bindings::default();
+    b.rebind(Action::Shoot, KeyCode::Char('"'));
+    b.rebind(Action::Warp, KeyCode::F(2));
+    save_bindings(&path, &b).unwrap();
+    let loaded = load_bindings(&path);
+    let _ = std::fs::remove_file(&path);
+    assert_eq!(loaded.unwrap(), b);
+}
+
+// ── Rebinding ─────────────────────────────────────────────────────────────────
+
+#[test]
+fn rebind_takes_the_key_from_other_actions() {
+    let mut b = Bindings::default();
+    b.rebind(Action::Shoot, KeyCode::Char('A'));
+    assert_eq!(b.keys(Action::Shoot), &[KeyCode::Char('a')]);
+    assert_eq!(b.keys(Action::MoveLeft), &[KeyCode::Left]);
+    assert_eq!(b.actions_for(KeyCode::Char('a')), vec![Action::Shoot]);
+}
+
+#[test]
+fn reset_restores_the_default_keys() {
+    let mut b = Bindings::default();
+    b.rebind(Action::Shoot, KeyCode::Char('a'));
+    b.reset(Action::MoveLeft);
+    assert_eq!(b.keys(Action::MoveLeft), Action::MoveLeft.default_keys());
+}
+
+#[test]
+fn labels_for_hints() {
+    let mut b = Bindings::default();
+    assert_eq!(b.label(Action::MoveLeft), "← / A");
+    assert_eq!(b.label(Action::Shoot), "SPACE");
+    b.rebind(Action::Shoot, KeyCode::Left);
+    assert_eq!(b.label(Action::MoveLeft), "A");
+    b.rebind(Action::Shoot, KeyCode::Char('a'));
+    assert_eq!(b.label(Action::MoveLeft), "(unbound)");
+}
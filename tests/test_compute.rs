@@ -10,9 +10,13 @@ fn make_state() -> EntireGameStateInfo {
             x: 20,
             y: 16,
             lives: 3,
+            invulnerable: 0,
         },
+        player2: None,
         enemies: Vec::new(),
         bullets: Vec::new(),
+        enemy_shots: Vec::new(),
+        boss: None,
         flame_bullets: Vec::new(),
         firebombs: Vec::new(),
         explosions: Vec::new(),
@@ -21,6 +25,13 @@ fn make_state() -> EntireGameStateInfo {
         score: 0,
         high_score: 0,
         level: Level::Easy,
+        difficulty: Difficulty::preset(&Level::Easy),
+        wave: WaveState {
+            number: 1,
+            remaining: 6,
+            breather: 0,
+            boss_due: false,
+        },
         status: GameStatus::Playing,
         frame: 0,
         width: 40,
@@ -30,6 +41,8 @@ fn make_state() -> EntireGameStateInfo {
         slow_mo: false,
         muzzle_flash: 0,
         cheer_msg: None,
+        events: Vec::new(),
+        stats: RunStats::default(),
     }
 }
 
@@ -150,7 +163,7 @@ fn shoot_adds_bullet_at_player_position() {
     let b = &s2.bullets[0];
     assert_eq!(b.x, s.player.x);
     assert_eq!(b.y, s.player.y - 1);
-    assert_eq!(b.owner, BulletOwner::Player);
+    assert_eq!(b.owner, BulletOwner::Player(PlayerId::One));
 }
 
 #[test]
@@ -160,7 +173,7 @@ fn shoot_cap_at_three_player_bullets() {
         s.bullets.push(Bullet {
             x: 5,
             y: 5,
-            owner: BulletOwner::Player,
+            owner: BulletOwner::Player(PlayerId::One),
         });
     }
     let s2 = player_shoot(&s);
@@ -175,7 +188,7 @@ fn shoot_respects_cap_with_mixed_bullets() {
         s.bullets.push(Bullet {
             x: 5,
             y: 5,
-            owner: BulletOwner::Player,
+            owner: BulletOwner::Player(PlayerId::One),
         });
     }
     for _ in 0..5 {
@@ -190,7 +203,7 @@ fn shoot_respects_cap_with_mixed_bullets() {
     let player_count = s2
         .bullets
         .iter()
-        .filter(|b| b.owner == BulletOwner::Player)
+        .filter(|b| b.owner == BulletOwner::Player(PlayerId::One))
         .count();
     assert_eq!(player_count, 3);
 }
@@ -202,14 +215,14 @@ fn shoot_allows_third_bullet() {
         s.bullets.push(Bullet {
             x: 5,
             y: 5,
-            owner: BulletOwner::Player,
+            owner: BulletOwner::Player(PlayerId::One),
         });
     }
     let s2 = player_shoot(&s);
     let player_count = s2
         .bullets
         .iter()
-        .filter(|b| b.owner == BulletOwner::Player)
+        .filter(|b| b.owner == BulletOwner::Player(PlayerId::One))
         .count();
     assert_eq!(player_count, 3);
 }
@@ -238,7 +251,7 @@ fn shoot_no_muzzle_flash_when_capped() {
         s.bullets.push(Bullet {
             x: 5,
             y: 5,
-            owner: BulletOwner::Player,
+            owner: BulletOwner::Player(PlayerId::One),
         });
     }
     let s2 = player_shoot(&s);
@@ -258,7 +271,7 @@ fn spreadshot_fires_three_bullets() {
     let pb: Vec<_> = s2
         .bullets
         .iter()
-        .filter(|b| b.owner == BulletOwner::Player)
+        .filter(|b| b.owner == BulletOwner::Player(PlayerId::One))
         .collect();
     assert_eq!(pb.len(), 3);
 }
@@ -271,7 +284,7 @@ fn spreadshot_bullet_columns_spread() {
     let mut xs: Vec<i32> = s2
         .bullets
         .iter()
-        .filter(|b| b.owner == BulletOwner::Player)
+        .filter(|b| b.owner == BulletOwner::Player(PlayerId::One))
         .map(|b| b.x)
         .collect();
     xs.sort();
@@ -284,7 +297,11 @@ fn spreadshot_all_bullets_one_row_above_player() {
     let mut s = make_state();
     s.active_power_up = Some((BonusKind::SpreadShot, 300));
     let s2 = player_shoot(&s);
-    for b in s2.bullets.iter().filter(|b| b.owner == BulletOwner::Player) {
+    for b in s2
+        .bullets
+        .iter()
+        .filter(|b| b.owner == BulletOwner::Player(PlayerId::One))
+    {
         assert_eq!(b.y, s.player.y - 1);
     }
 }
@@ -297,13 +314,13 @@ fn spreadshot_blocked_while_any_player_bullet_on_screen() {
     s.bullets.push(Bullet {
         x: 5,
         y: 5,
-        owner: BulletOwner::Player,
+        owner: BulletOwner::Player(PlayerId::One),
     });
     let s2 = player_shoot(&s);
     let pb_count = s2
         .bullets
         .iter()
-        .filter(|b| b.owner == BulletOwner::Player)
+        .filter(|b| b.owner == BulletOwner::Player(PlayerId::One))
         .count();
     assert_eq!(
         pb_count, 1,
@@ -322,14 +339,14 @@ fn rapidfire_cap_is_six() {
         s.bullets.push(Bullet {
             x: 20,
             y: 5 + i,
-            owner: BulletOwner::Player,
+            owner: BulletOwner::Player(PlayerId::One),
         });
     }
     let s2 = player_shoot(&s);
     let pb_count = s2
         .bullets
         .iter()
-        .filter(|b| b.owner == BulletOwner::Player)
+        .filter(|b| b.owner == BulletOwner::Player(PlayerId::One))
         .count();
     assert_eq!(pb_count, 6);
 }
@@ -342,14 +359,14 @@ fn rapidfire_blocked_at_six() {
         s.bullets.push(Bullet {
             x: 20,
             y: 3 + i,
-            owner: BulletOwner::Player,
+            owner: BulletOwner::Player(PlayerId::One),
         });
     }
     let s2 = player_shoot(&s);
     let pb_count = s2
         .bullets
         .iter()
-        .filter(|b| b.owner == BulletOwner::Player)
+        .filter(|b| b.owner == BulletOwner::Player(PlayerId::One))
         .count();
     assert_eq!(pb_count, 6, "7th shot must be blocked under RapidFire");
 }
@@ -462,11 +479,13 @@ fn firebomb_cap_at_two() {
         x: 20,
         y: 10,
         fuse: 80,
+        owner: PlayerId::One,
     });
     s.firebombs.push(FirebombProj {
         x: 22,
         y: 8,
         fuse: 70,
+        owner: PlayerId::One,
     });
     let s2 = player_shoot(&s);
     assert_eq!(s2.firebombs.len(), 2, "firebomb cap must be 2");
@@ -480,11 +499,13 @@ fn firebomb_no_muzzle_flash_when_capped() {
         x: 20,
         y: 10,
         fuse: 80,
+        owner: PlayerId::One,
     });
     s.firebombs.push(FirebombProj {
         x: 22,
         y: 8,
         fuse: 70,
+        owner: PlayerId::One,
     });
     let s2 = player_shoot(&s);
     assert_eq!(s2.muzzle_flash, 0, "no flash when firebomb shot is blocked");
@@ -499,6 +520,7 @@ fn tick_flame_bullet_moves_up_and_diagonally() {
         x: 20.0,
         y: 10.0,
         vx: 0.5,
+        owner: PlayerId::One,
     });
     let s2 = tick(&s, &mut seeded_rng());
     let fb = s2.flame_bullets.iter().find(|fb| (fb.y - 9.0).abs() < 0.01);
@@ -518,6 +540,7 @@ fn tick_flame_bullet_discarded_at_top_boundary() {
         x: 20.0,
         y: 3.0,
         vx: 0.0,
+        owner: PlayerId::One,
     });
     let s2 = tick(&s, &mut seeded_rng());
     assert_eq!(s2.flame_bullets.len(), 1);
@@ -537,6 +560,7 @@ fn tick_flame_bullet_discarded_out_of_bounds_left() {
         x: 1.5,
         y: 10.0,
         vx: -2.0,
+        owner: PlayerId::One,
     });
     let s2 = tick(&s, &mut seeded_rng());
     assert!(
@@ -554,12 +578,15 @@ fn tick_flame_bullet_kills_enemy_on_hit() {
         x: 20,
         y: 8,
         kind: EnemyKind::Spacecraft,
+        hp: 1,
+        behavior: EnemyBehavior::Straight,
     });
     // Place bullet so that after moving up (y 9→8) it lands on the enemy.
     s.flame_bullets.push(FlameBullet {
         x: 20.0,
         y: 9.0,
         vx: 0.0,
+        owner: PlayerId::One,
     });
     let s2 = tick(&s, &mut seeded_rng());
     assert!(
@@ -579,11 +606,14 @@ fn tick_flame_bullet_scores_on_kill() {
         x: 20,
         y: 8,
         kind: EnemyKind::Spacecraft,
+        hp: 1,
+        behavior: EnemyBehavior::Straight,
     });
     s.flame_bullets.push(FlameBullet {
         x: 20.0,
         y: 9.0,
         vx: 0.0,
+        owner: PlayerId::One,
     });
     let s2 = tick(&s, &mut seeded_rng());
     assert_eq!(
@@ -601,6 +631,7 @@ fn tick_firebomb_fuse_decrements_every_frame() {
         x: 20,
         y: 10,
         fuse: 90,
+        owner: PlayerId::One,
     });
     let s2 = tick(&s, &mut seeded_rng());
     assert_eq!(s2.firebombs[0].fuse, 89);
@@ -615,6 +646,7 @@ fn tick_firebomb_moves_up_on_interval() {
         x: 20,
         y: 10,
         fuse: 90,
+        owner: PlayerId::One,
     });
     let s2 = tick(&s, &mut seeded_rng());
     assert_eq!(
@@ -631,6 +663,7 @@ fn tick_firebomb_does_not_move_off_interval() {
         x: 20,
         y: 10,
         fuse: 90,
+        owner: PlayerId::One,
     });
     let s2 = tick(&s, &mut seeded_rng());
     assert_eq!(
@@ -648,6 +681,7 @@ fn tick_firebomb_detonates_on_fuse_expiry() {
         x: 20,
         y: 10,
         fuse: 1,
+        owner: PlayerId::One,
     });
     let s2 = tick(&s, &mut seeded_rng());
     // fuse ticks to 0 → bomb must be removed (detonated)
@@ -664,6 +698,7 @@ fn tick_firebomb_detonation_spawns_explosion() {
         x: 20,
         y: 10,
         fuse: 1,
+        owner: PlayerId::One,
     });
     let s2 = tick(&s, &mut seeded_rng());
     assert!(
@@ -680,11 +715,14 @@ fn tick_firebomb_kills_enemy_in_blast_radius() {
         x: 23,
         y: 10,
         kind: EnemyKind::Spacecraft,
+        hp: 1,
+        behavior: EnemyBehavior::Straight,
     });
     s.firebombs.push(FirebombProj {
         x: 20,
         y: 10,
         fuse: 1,
+        owner: PlayerId::One,
     });
     let s2 = tick(&s, &mut seeded_rng());
     assert!(
@@ -701,11 +739,14 @@ fn tick_firebomb_does_not_kill_enemy_outside_blast_radius() {
         x: 25,
         y: 10,
         kind: EnemyKind::Spacecraft,
+        hp: 1,
+        behavior: EnemyBehavior::Straight,
     });
     s.firebombs.push(FirebombProj {
         x: 20,
         y: 10,
         fuse: 1,
+        owner: PlayerId::One,
     });
     let s2 = tick(&s, &mut seeded_rng());
     assert_eq!(
@@ -722,11 +763,14 @@ fn tick_firebomb_scores_for_blast_kill() {
         x: 23,
         y: 10,
         kind: EnemyKind::Spacecraft,
+        hp: 1,
+        behavior: EnemyBehavior::Straight,
     });
     s.firebombs.push(FirebombProj {
         x: 20,
         y: 10,
         fuse: 1,
+        owner: PlayerId::One,
     });
     let s2 = tick(&s, &mut seeded_rng());
     assert_eq!(s2.score, 100, "blast kill must award points");
@@ -740,11 +784,14 @@ fn tick_firebomb_proximity_detonation() {
         x: 21,
         y: 10,
         kind: EnemyKind::Octopus,
+        hp: 1,
+        behavior: EnemyBehavior::Straight,
     }); // dx=1, dy=0 → dist²=1 ≤ 4
     s.firebombs.push(FirebombProj {
         x: 20,
         y: 10,
         fuse: 90,
+        owner: PlayerId::One,
     });
     let s2 = tick(&s, &mut seeded_rng());
     assert!(
@@ -798,14 +845,14 @@ fn tick_player_bullet_moves_up() {
     s.bullets.push(Bullet {
         x: 20,
         y: 10,
-        owner: BulletOwner::Player,
+        owner: BulletOwner::Player(PlayerId::One),
     });
     let s2 = tick(&s, &mut seeded_rng());
     // bullet at y=10 → y=9 (and not discarded since 9 >= 2)
     let b: Vec<_> = s2
         .bullets
         .iter()
-        .filter(|b| b.owner == BulletOwner::Player)
+        .filter(|b| b.owner == BulletOwner::Player(PlayerId::One))
         .collect();
     assert_eq!(b.len(), 1);
     assert_eq!(b[0].y, 9);
@@ -836,18 +883,18 @@ fn tick_bullet_discarded_at_top_boundary() {
     s.bullets.push(Bullet {
         x: 20,
         y: 3,
-        owner: BulletOwner::Player,
+        owner: BulletOwner::Player(PlayerId::One),
     });
     s.bullets.push(Bullet {
         x: 15,
         y: 2,
-        owner: BulletOwner::Player,
+        owner: BulletOwner::Player(PlayerId::One),
     });
     let s2 = tick(&s, &mut seeded_rng());
     let kept: Vec<_> = s2
         .bullets
         .iter()
-        .filter(|b| b.owner == BulletOwner::Player)
+        .filter(|b| b.owner == BulletOwner::Player(PlayerId::One))
         .collect();
     assert_eq!(kept.len(), 1);
     assert_eq!(kept[0].y, 2);
@@ -878,316 +925,1194 @@ fn tick_bullet_discarded_at_bottom_boundary() {
     assert_eq!(kept[0].y, 17);
 }
 
-// ── tick — enemy movement ─────────────────────────────────────────────────────
+// ── Waves ─────────────────────────────────────────────────────────────────────
 
 #[test]
-fn tick_enemies_move_on_interval_easy() {
-    // Easy interval = 22; we want frame N such that N+1 ≡ 0 (mod 22) → N=21
+fn init_state_starts_wave_one_with_banner() {
+    let s = init_state(Level::Easy, 40, 20, 0);
+    assert_eq!(s.wave.number, 1);
+    assert!(s.wave.breather > 0);
+    assert_eq!(
+        s.wave.remaining,
+        wave_spec(&s.level, &s.difficulty, 1).budget
+    );
+    assert_eq!(
+        s.cheer_msg.as_ref().map(|(m, _)| m.as_str()),
+        Some("WAVE 1")
+    );
+}
+
+#[test]
+fn wave_one_matches_difficulty() {
+    let d = Difficulty::preset(&Level::Medium);
+    let spec = wave_spec(&Level::Medium, &d, 1);
+    assert_eq!(spec.enemy_move_interval, d.enemy_move_interval);
+    assert_eq!(spec.enemy_spawn_rate, d.enemy_spawn_rate);
+    assert_eq!(spec.enemy_fire_chance, d.enemy_fire_chance);
+    assert_eq!(spec.spacecraft_chance, d.spacecraft_chance);
+}
+
+#[test]
+fn later_waves_are_larger_and_faster() {
+    let d = Difficulty::preset(&Level::Medium);
+    let (w1, w5) = (
+        wave_spec(&Level::Medium, &d, 1),
+        wave_spec(&Level::Medium, &d, 5),
+    );
+    assert!(w5.budget > w1.budget);
+    assert!(w5.enemy_move_interval < w1.enemy_move_interval);
+    assert!(w5.enemy_spawn_rate < w1.enemy_spawn_rate);
+    assert!(w5.enemy_fire_chance < w1.enemy_fire_chance);
+    assert!(w5.spacecraft_chance < w1.spacecraft_chance);
+}
+
+#[test]
+fn wave_ramp_is_capped() {
+    let d = Difficulty::preset(&Level::Extreme);
+    let (w11, w50) = (
+        wave_spec(&Level::Extreme, &d, 11),
+        wave_spec(&Level::Extreme, &d, 50),
+    );
+    assert_eq!(w11.enemy_move_interval, w50.enemy_move_interval);
+    assert_eq!(w11.enemy_spawn_rate, w50.enemy_spawn_rate);
+    assert_eq!(w50.enemy_move_interval, d.enemy_move_interval / 2);
+    assert!(w50.budget > w11.budget);
+}
+
+#[test]
+fn wave_ramp_does_not_overflow_huge_intervals() {
+    let d = Difficulty {
+        enemy_move_interval: u64::MAX,
+        ..Difficulty::preset(&Level::Easy)
+    };
+    assert!(wave_spec(&Level::Easy, &d, 5).enemy_move_interval > 1);
+}
+
+#[test]
+fn tick_no_spawn_during_breather() {
     let mut s = make_state();
-    s.frame = 21;
-    s.enemies.push(Enemy {
-        x: 10,
-        y: 5,
-        kind: EnemyKind::Spacecraft,
-    });
+    s.wave.breather = 10;
+    s.frame = 129; // next frame = 130, Easy spawn rate
     let s2 = tick(&s, &mut seeded_rng());
-    assert_eq!(s2.enemies[0].y, 6); // moved on frame 22
+    assert!(s2.enemies.is_empty());
+    assert_eq!(s2.wave.breather, 9);
+    assert_eq!(s2.wave.remaining, s.wave.remaining);
 }
 
 #[test]
-fn tick_enemies_do_not_move_off_interval() {
+fn tick_spawn_spends_wave_budget() {
     let mut s = make_state();
-    s.frame = 1; // next frame = 2, not divisible by 14
-    s.enemies.push(Enemy {
-        x: 10,
-        y: 5,
-        kind: EnemyKind::Spacecraft,
-    });
+    s.frame = 129;
     let s2 = tick(&s, &mut seeded_rng());
-    assert_eq!(s2.enemies[0].y, 5);
+    assert_eq!(s2.enemies.len(), 1);
+    assert_eq!(s2.wave.remaining, s.wave.remaining - 1);
 }
 
 #[test]
-fn tick_enemy_purged_past_bottom() {
-    // height=20, purge when e.y >= height-2 = 18
-    // Enemy at y=17 moves to 18 on frame 22 → purged
+fn tick_no_spawn_once_budget_spent() {
     let mut s = make_state();
-    s.frame = 21;
+    s.wave.remaining = 0;
     s.enemies.push(Enemy {
         x: 10,
-        y: 17,
+        y: 5,
         kind: EnemyKind::Spacecraft,
+        hp: 1,
+        behavior: EnemyBehavior::Straight,
     });
+    s.frame = 129;
     let s2 = tick(&s, &mut seeded_rng());
-    assert!(s2.enemies.is_empty());
+    assert_eq!(s2.enemies.len(), 1);
+    assert_eq!(s2.wave.number, 1);
 }
 
-// ── tick — collision: player bullet ↔ enemy ──────────────────────────────────
+#[test]
+fn tick_wave_advances_when_budget_spent_and_field_clear() {
+    let mut s = make_state();
+    s.wave.remaining = 0;
+    let s2 = tick(&s, &mut seeded_rng());
+    assert_eq!(s2.wave.number, 2);
+    assert_eq!(
+        s2.wave.remaining,
+        wave_spec(&s.level, &s.difficulty, 2).budget
+    );
+    assert!(s2.wave.breather > 0);
+    assert!(s2.events.contains(&GameEvent::WaveCleared(1)));
+    assert_eq!(
+        s2.cheer_msg.as_ref().map(|(m, _)| m.as_str()),
+        Some("WAVE 2")
+    );
+}
 
 #[test]
-fn tick_player_bullet_hits_enemy_direct() {
-    // tick() moves bullets BEFORE collision detection.
-    // Player bullet moves UP (y-1), so place it one row below the enemy.
+fn tick_wave_advances_on_last_kill() {
     let mut s = make_state();
-    s.frame = 1; // frame 2, no movement
+    s.wave.remaining = 0;
     s.enemies.push(Enemy {
         x: 10,
         y: 5,
         kind: EnemyKind::Spacecraft,
+        hp: 1,
+        behavior: EnemyBehavior::Straight,
     });
     s.bullets.push(Bullet {
         x: 10,
-        y: 6,
-        owner: BulletOwner::Player,
-    }); // moves to y=5
+        y: 7,
+        owner: BulletOwner::Player(PlayerId::One),
+    });
     let s2 = tick(&s, &mut seeded_rng());
     assert!(s2.enemies.is_empty());
+    assert_eq!(s2.wave.number, 2);
     assert_eq!(s2.score, 100);
 }
 
+// ── Bosses ────────────────────────────────────────────────────────────────────
+
+/// A boss at the top centre of the 40×20 test field (cols 17–23, rows 2–4)
+/// that will not move or fire on the next tick from frame 0.
+fn make_boss(hp: u32) -> Boss {
+    Boss {
+        x: 20,
+        y: 2,
+        hp,
+        max_hp: 40,
+        dir: 1,
+        pattern: BossPattern::Aimed,
+        pattern_frame: 0,
+    }
+}
+
 #[test]
-fn tick_player_bullet_hits_enemy_wide_box() {
-    // Bounding box is 3-wide: x±1 also hits
+fn advancing_to_every_third_wave_schedules_a_boss() {
     let mut s = make_state();
-    s.frame = 1;
-    s.enemies.push(Enemy {
-        x: 10,
-        y: 5,
-        kind: EnemyKind::Spacecraft,
-    });
-    s.bullets.push(Bullet {
-        x: 11,
-        y: 6,
-        owner: BulletOwner::Player,
-    }); // x+1, moves to y=5
+    s.wave.number = 2;
+    s.wave.remaining = 0;
     let s2 = tick(&s, &mut seeded_rng());
-    assert!(s2.enemies.is_empty());
+    assert_eq!(s2.wave.number, 3);
+    assert!(s2.wave.boss_due);
 }
 
 #[test]
-fn tick_player_bullet_misses_enemy_outside_box() {
+fn boss_arrives_when_boss_wave_is_cleared() {
     let mut s = make_state();
-    s.frame = 1;
-    s.enemies.push(Enemy {
-        x: 10,
-        y: 5,
-        kind: EnemyKind::Spacecraft,
-    });
-    s.bullets.push(Bullet {
-        x: 12,
-        y: 5,
-        owner: BulletOwner::Player,
-    }); // x+2, outside
+    s.wave.number = 3;
+    s.wave.remaining = 0;
+    s.wave.boss_due = true;
     let s2 = tick(&s, &mut seeded_rng());
-    assert_eq!(s2.enemies.len(), 1);
+    let boss = s2.boss.as_ref().expect("boss spawned");
+    assert_eq!(boss.hp, boss.max_hp);
+    assert_eq!(s2.wave.number, 3, "wave must not end before the boss");
+    assert!(!s2.wave.boss_due);
+    assert!(s2.cheer_msg.unwrap().0.contains("BOSS"));
 }
 
 #[test]
-fn tick_player_bullet_hits_enemy_second_row() {
-    // Bounding box is 2-tall: enemy.y+1 also hits
+fn wave_does_not_end_while_boss_alive() {
     let mut s = make_state();
-    s.frame = 1;
-    s.enemies.push(Enemy {
-        x: 10,
-        y: 5,
-        kind: EnemyKind::Spacecraft,
-    });
-    s.bullets.push(Bullet {
-        x: 10,
-        y: 6,
-        owner: BulletOwner::Player,
-    }); // y+1
+    s.wave.remaining = 0;
+    s.boss = Some(make_boss(40));
     let s2 = tick(&s, &mut seeded_rng());
-    assert!(s2.enemies.is_empty());
+    assert_eq!(s2.wave.number, 1);
+    assert!(s2.boss.is_some());
 }
 
 #[test]
-fn tick_octopus_scores_150() {
+fn crossing_score_threshold_summons_boss() {
     let mut s = make_state();
-    s.frame = 1;
+    s.score = 4950;
     s.enemies.push(Enemy {
         x: 10,
         y: 5,
-        kind: EnemyKind::Octopus,
+        kind: EnemyKind::Spacecraft,
+        hp: 1,
+        behavior: EnemyBehavior::Straight,
     });
     s.bullets.push(Bullet {
         x: 10,
-        y: 6,
-        owner: BulletOwner::Player,
-    }); // moves to y=5
+        y: 7,
+        owner: BulletOwner::Player(PlayerId::One),
+    });
     let s2 = tick(&s, &mut seeded_rng());
-    assert_eq!(s2.score, 150);
+    assert_eq!(s2.score, 5050);
+    assert!(s2.boss.is_some());
 }
 
-// ── tick — collision: enemy bullet ↔ player ──────────────────────────────────
-
 #[test]
-fn tick_enemy_bullet_hits_player() {
-    // Enemy bullet moves DOWN (y+1) before collision detection.
-    // Collision checks against state.player.y (old position = 16).
-    // Place bullet at player.y-1 so it moves into player.y.
-    let mut s = make_state(); // player at (20, 16)
-    s.frame = 1;
+fn player_bullet_damages_boss_by_one() {
+    let mut s = make_state();
+    s.boss = Some(make_boss(40));
     s.bullets.push(Bullet {
-        x: 20,
-        y: 15,
-        owner: BulletOwner::Enemy,
-    }); // moves to y=16
+        x: 22,
+        y: 5,
+        owner: BulletOwner::Player(PlayerId::One),
+    }); // moves to y=4, the boss's bottom row
     let s2 = tick(&s, &mut seeded_rng());
-    assert_eq!(s2.player.lives, 2);
+    assert_eq!(s2.boss.unwrap().hp, 39);
+    assert!(s2.bullets.is_empty(), "bullet must be consumed");
+    assert_eq!(s2.score, 0, "no score before the killing blow");
 }
 
 #[test]
-fn tick_player_loses_life_on_enemy_contact() {
-    let mut s = make_state(); // player.y = 16
-    s.frame = 1;
-    s.enemies.push(Enemy {
-        x: 5,
-        y: 16,
-        kind: EnemyKind::Spacecraft,
+fn flame_bullet_damages_boss() {
+    let mut s = make_state();
+    s.boss = Some(make_boss(40));
+    s.flame_bullets.push(FlameBullet {
+        x: 20.0,
+        y: 5.0,
+        vx: 0.0,
+        owner: PlayerId::One,
     });
     let s2 = tick(&s, &mut seeded_rng());
-    assert_eq!(s2.player.lives, 2);
+    assert_eq!(s2.boss.unwrap().hp, 39);
+    assert!(s2.flame_bullets.is_empty());
 }
 
 #[test]
-fn tick_game_over_when_lives_reach_zero() {
+fn firebomb_detonates_near_boss_and_deals_blast_damage() {
     let mut s = make_state();
-    s.player.lives = 1;
-    s.frame = 1;
-    s.bullets.push(Bullet {
+    s.boss = Some(make_boss(40));
+    s.firebombs.push(FirebombProj {
         x: 20,
-        y: 15,
-        owner: BulletOwner::Enemy,
-    }); // moves to y=16
+        y: 6,
+        fuse: 90,
+        owner: PlayerId::One,
+    }); // two rows below the hull → dist² = 4
     let s2 = tick(&s, &mut seeded_rng());
-    assert_eq!(s2.player.lives, 0);
-    assert_eq!(s2.status, GameStatus::GameOver);
+    assert!(s2.firebombs.is_empty());
+    assert_eq!(s2.boss.unwrap().hp, 35);
 }
 
 #[test]
-fn tick_no_game_over_when_lives_above_zero() {
+fn killing_blow_scores_and_removes_boss() {
     let mut s = make_state();
-    s.player.lives = 2;
-    s.frame = 1;
+    s.boss = Some(make_boss(1));
     s.bullets.push(Bullet {
         x: 20,
-        y: 15,
-        owner: BulletOwner::Enemy,
-    }); // moves to y=16
+        y: 5,
+        owner: BulletOwner::Player(PlayerId::One),
+    });
     let s2 = tick(&s, &mut seeded_rng());
-    assert_eq!(s2.player.lives, 1);
-    assert_eq!(s2.status, GameStatus::Playing);
+    assert!(s2.boss.is_none());
+    assert_eq!(s2.score, 40 * 50);
+    assert!(s2.events.contains(&GameEvent::BossDefeated));
+    assert!(!s2.explosions.is_empty());
 }
 
 #[test]
-fn tick_lives_saturate_at_zero() {
+fn defeating_wave_boss_ends_the_wave() {
     let mut s = make_state();
-    s.player.lives = 0;
-    s.frame = 1;
+    s.wave.number = 3;
+    s.wave.remaining = 0;
+    s.boss = Some(make_boss(1));
     s.bullets.push(Bullet {
         x: 20,
-        y: 16,
-        owner: BulletOwner::Enemy,
+        y: 5,
+        owner: BulletOwner::Player(PlayerId::One),
     });
     let s2 = tick(&s, &mut seeded_rng());
-    assert_eq!(s2.player.lives, 0); // saturating_sub, no underflow
+    assert!(s2.events.contains(&GameEvent::WaveCleared(3)));
+    assert_eq!(s2.wave.number, 4);
 }
 
 #[test]
-fn tick_score_saturates_at_u32_max() {
+fn boss_aimed_shot_heads_for_player() {
     let mut s = make_state();
-    s.score = u32::MAX;
-    s.frame = 1;
-    s.enemies.push(Enemy {
-        x: 10,
-        y: 5,
-        kind: EnemyKind::Spacecraft,
+    s.player.x = 30;
+    s.boss = Some(Boss {
+        pattern_frame: 23,
+        ..make_boss(40)
     });
-    s.bullets.push(Bullet {
-        x: 10,
-        y: 6,
-        owner: BulletOwner::Player,
-    }); // moves to y=5 -> kill worth +100
     let s2 = tick(&s, &mut seeded_rng());
-    assert_eq!(s2.score, u32::MAX, "score must saturate at u32::MAX");
+    assert_eq!(s2.enemy_shots.len(), 1);
+    let shot = &s2.enemy_shots[0];
+    assert!(shot.vx > 0.0 && shot.vy > 0.0);
 }
 
-// ── tick — enemy bullet hitbox: 3-wide × 2-tall ──────────────────────────────
-//
-// Player sprite:   ▲        ← row player.y     (tip, 1 col wide)
-//                 /█\       ← row player.y+1   (fuselage+wings, 3 cols wide)
-//
-// Hitbox covers both rows at x ± 1.
+#[test]
+fn boss_fan_fires_five_shots() {
+    let mut s = make_state();
+    s.boss = Some(Boss {
+        pattern: BossPattern::Fan,
+        pattern_frame: 44,
+        ..make_boss(40)
+    });
+    let s2 = tick(&s, &mut seeded_rng());
+    assert_eq!(s2.enemy_shots.len(), 5);
+}
 
 #[test]
-fn tick_enemy_bullet_hits_player_fuselage_center() {
-    // Bullet at (player.x, player.y) → moves to (player.x, player.y+1) = fuselage center
-    let mut s = make_state(); // player at (20, 16)
-    s.frame = 1;
-    s.bullets.push(Bullet {
-        x: 20,
-        y: 16,
-        owner: BulletOwner::Enemy,
+fn boss_patterns_rotate() {
+    let mut s = make_state();
+    s.boss = Some(Boss {
+        pattern_frame: 119,
+        ..make_boss(40)
     });
     let s2 = tick(&s, &mut seeded_rng());
-    assert_eq!(s2.player.lives, 2);
+    let boss = s2.boss.unwrap();
+    assert_eq!(boss.pattern, BossPattern::Fan);
+    assert_eq!(boss.pattern_frame, 0);
 }
 
 #[test]
-fn tick_enemy_bullet_hits_player_left_wing() {
-    // Bullet at (player.x-1, player.y) → moves to (player.x-1, player.y+1) = left wing
+fn boss_bounces_off_wall() {
     let mut s = make_state();
-    s.frame = 1;
-    s.bullets.push(Bullet {
-        x: 19,
-        y: 16,
-        owner: BulletOwner::Enemy,
+    s.frame = 2; // frame 3 is a boss move frame
+    s.boss = Some(Boss {
+        x: 35, // rightmost position: 35 + 3 = 37 < width - 2
+        ..make_boss(40)
     });
     let s2 = tick(&s, &mut seeded_rng());
-    assert_eq!(s2.player.lives, 2);
+    let boss = s2.boss.unwrap();
+    assert_eq!(boss.dir, -1);
+    assert_eq!(boss.x, 34);
 }
 
 #[test]
-fn tick_enemy_bullet_hits_player_right_wing() {
-    // Bullet at (player.x+1, player.y) → moves to (player.x+1, player.y+1) = right wing
+fn enemy_shot_moves_by_velocity() {
     let mut s = make_state();
-    s.frame = 1;
-    s.bullets.push(Bullet {
-        x: 21,
-        y: 16,
-        owner: BulletOwner::Enemy,
+    s.enemy_shots.push(EnemyShot {
+        x: 10.0,
+        y: 5.0,
+        vx: 0.5,
+        vy: 0.25,
     });
     let s2 = tick(&s, &mut seeded_rng());
-    assert_eq!(s2.player.lives, 2);
+    assert_eq!(s2.enemy_shots[0].x, 10.5);
+    assert_eq!(s2.enemy_shots[0].y, 5.25);
 }
 
 #[test]
-fn tick_enemy_bullet_hits_player_tip_off_center() {
-    // Bullet at (player.x-1, player.y-1) → moves to (player.x-1, player.y) = tip row, x-1
+fn enemy_shot_hits_player() {
     let mut s = make_state();
-    s.frame = 1;
-    s.bullets.push(Bullet {
-        x: 19,
-        y: 15,
-        owner: BulletOwner::Enemy,
+    s.enemy_shots.push(EnemyShot {
+        x: 21.0,
+        y: 15.6,
+        vx: 0.0,
+        vy: 0.5,
     });
     let s2 = tick(&s, &mut seeded_rng());
     assert_eq!(s2.player.lives, 2);
+    assert!(s2.enemy_shots.is_empty());
+    assert!(s2
+        .events
+        .contains(&GameEvent::PlayerHit(HitCause::EnemyBullet)));
 }
 
+// ── tick — enemy movement ─────────────────────────────────────────────────────
+
 #[test]
-fn tick_enemy_bullet_misses_player_too_far() {
-    // Bullet at (player.x+2, player.y-1) → moves to (player.x+2, player.y): x=22, outside ±1
+fn tick_enemies_move_on_interval_easy() {
+    // Easy interval = 22; we want frame N such that N+1 ≡ 0 (mod 22) → N=21
     let mut s = make_state();
-    s.frame = 1;
-    s.bullets.push(Bullet {
-        x: 22,
-        y: 15,
-        owner: BulletOwner::Enemy,
+    s.frame = 21;
+    s.enemies.push(Enemy {
+        x: 10,
+        y: 5,
+        kind: EnemyKind::Spacecraft,
+        hp: 1,
+        behavior: EnemyBehavior::Straight,
     });
     let s2 = tick(&s, &mut seeded_rng());
-    assert_eq!(s2.player.lives, 3);
+    assert_eq!(s2.enemies[0].y, 6); // moved on frame 22
+}
+
+#[test]
+fn tick_enemies_do_not_move_off_interval() {
+    let mut s = make_state();
+    s.frame = 1; // next frame = 2, not divisible by 14
+    s.enemies.push(Enemy {
+        x: 10,
+        y: 5,
+        kind: EnemyKind::Spacecraft,
+        hp: 1,
+        behavior: EnemyBehavior::Straight,
+    });
+    let s2 = tick(&s, &mut seeded_rng());
+    assert_eq!(s2.enemies[0].y, 5);
+}
+
+#[test]
+fn tick_enemy_move_interval_comes_from_difficulty() {
+    let mut s = make_state();
+    s.difficulty.enemy_move_interval = 3;
+    s.frame = 2; // next frame = 3
+    s.enemies.push(Enemy {
+        x: 10,
+        y: 5,
+        kind: EnemyKind::Spacecraft,
+        hp: 1,
+        behavior: EnemyBehavior::Straight,
+    });
+    let s2 = tick(&s, &mut seeded_rng());
+    assert_eq!(s2.enemies[0].y, 6);
+}
+
+#[test]
+fn tick_spawn_rate_comes_from_difficulty() {
+    let mut s = make_state();
+    s.difficulty.enemy_spawn_rate = 5;
+    s.difficulty.spacecraft_chance = 1.0;
+    s.frame = 4; // next frame = 5
+    let s2 = tick(&s, &mut seeded_rng());
+    assert_eq!(s2.enemies.len(), 1);
+    assert_eq!(s2.enemies[0].kind, EnemyKind::Spacecraft);
+}
+
+#[test]
+fn tick_enemy_purged_past_bottom() {
+    // height=20, purge when e.y >= height-2 = 18
+    // Enemy at y=17 moves to 18 on frame 22 → purged
+    let mut s = make_state();
+    s.frame = 21;
+    s.enemies.push(Enemy {
+        x: 10,
+        y: 17,
+        kind: EnemyKind::Spacecraft,
+        hp: 1,
+        behavior: EnemyBehavior::Straight,
+    });
+    let s2 = tick(&s, &mut seeded_rng());
+    assert!(s2.enemies.is_empty());
+}
+
+// ── tick — enemy behaviours ───────────────────────────────────────────────────
+
+fn enemy_with(x: i32, y: i32, behavior: EnemyBehavior) -> Enemy {
+    Enemy {
+        x,
+        y,
+        kind: EnemyKind::Spacecraft,
+        hp: 1,
+        behavior,
+    }
+}
+
+#[test]
+fn zigzag_steps_sideways_and_turns_after_a_leg() {
+    let mut s = make_state();
+    s.frame = 21; // Easy move interval 22
+    s.enemies.push(enemy_with(
+        10,
+        5,
+        EnemyBehavior::Zigzag { dir: 1, steps: 3 },
+    ));
+    let s2 = tick(&s, &mut seeded_rng());
+    assert_eq!((s2.enemies[0].x, s2.enemies[0].y), (11, 6));
+    assert_eq!(
+        s2.enemies[0].behavior,
+        EnemyBehavior::Zigzag { dir: -1, steps: 0 }
+    );
+}
+
+#[test]
+fn zigzag_bounces_off_the_wall() {
+    let mut s = make_state();
+    s.frame = 21;
+    s.enemies.push(enemy_with(
+        2,
+        5,
+        EnemyBehavior::Zigzag { dir: -1, steps: 0 },
+    ));
+    let s2 = tick(&s, &mut seeded_rng());
+    assert_eq!(s2.enemies[0].x, 3);
+    assert_eq!(
+        s2.enemies[0].behavior,
+        EnemyBehavior::Zigzag { dir: 1, steps: 1 }
+    );
+}
+
+#[test]
+fn diver_starts_diving_after_a_few_rows() {
+    let mut s = make_state();
+    s.frame = 21;
+    s.enemies
+        .push(enemy_with(10, 4, EnemyBehavior::Diver { dive: 0 }));
+    let s2 = tick(&s, &mut seeded_rng());
+    assert_eq!(s2.enemies[0].y, 5);
+    assert_eq!(s2.enemies[0].behavior, EnemyBehavior::Diver { dive: 1 });
+}
+
+#[test]
+fn diving_enemy_ignores_move_interval_and_homes_in() {
+    let mut s = make_state(); // player at x = 20
+    s.frame = 1; // not a move frame
+    s.enemies
+        .push(enemy_with(10, 5, EnemyBehavior::Diver { dive: 3 }));
+    let s2 = tick(&s, &mut seeded_rng());
+    assert_eq!((s2.enemies[0].x, s2.enemies[0].y), (11, 6));
+}
+
+#[test]
+fn diving_enemy_accelerates() {
+    let mut s = make_state();
+    s.frame = 1;
+    s.enemies
+        .push(enemy_with(20, 5, EnemyBehavior::Diver { dive: 1 }));
+    let slow = (0..4).fold(s.clone(), |st, _| tick(&st, &mut seeded_rng()));
+    assert_eq!(slow.enemies[0].y, 6, "one row per 4 frames at first");
+
+    s.enemies[0].behavior = EnemyBehavior::Diver { dive: 60 };
+    let fast = (0..4).fold(s, |st, _| tick(&st, &mut seeded_rng()));
+    assert_eq!(fast.enemies[0].y, 9, "one row per frame at full speed");
+}
+
+#[test]
+fn formation_moves_in_lockstep() {
+    let mut s = make_state();
+    s.frame = 21;
+    s.enemies.push(enemy_with(10, 5, EnemyBehavior::Formation));
+    s.enemies.push(enemy_with(14, 5, EnemyBehavior::Formation));
+    let s2 = tick(&s, &mut seeded_rng());
+    assert_eq!(s2.enemies[0].y, 6);
+    assert_eq!(s2.enemies[1].y, 6);
+    assert_eq!(s2.enemies[1].x - s2.enemies[0].x, 4);
+    assert_ne!(s2.enemies[0].x, 10, "formation sways sideways");
+}
+
+#[test]
+fn formation_members_peel_off_into_dives() {
+    let mut s = make_state();
+    s.difficulty.enemy_move_interval = 1;
+    s.difficulty.enemy_fire_chance = u32::MAX;
+    s.wave.remaining = 0;
+    s.enemies = (0..5)
+        .map(|i| enemy_with(8 + 4 * i, 2, EnemyBehavior::Formation))
+        .collect();
+    let mut rng = seeded_rng();
+    let peeled = (0..10).any(|_| {
+        s = tick(&s, &mut rng);
+        s.enemies
+            .iter()
+            .any(|e| matches!(e.behavior, EnemyBehavior::Diver { .. }))
+    });
+    assert!(peeled);
+}
+
+#[test]
+fn early_waves_spawn_straight_enemies_only() {
+    let d = Difficulty::preset(&Level::Easy);
+    let spec = wave_spec(&Level::Easy, &d, 1);
+    assert_eq!(spec.zigzag_chance, 0.0);
+    assert_eq!(spec.diver_chance, 0.0);
+    assert_eq!(spec.formation_chance, 0.0);
+}
+
+#[test]
+fn behaviours_unlock_earlier_on_harder_levels() {
+    let d = Difficulty::preset(&Level::Medium);
+    assert_eq!(wave_spec(&Level::Medium, &d, 3).formation_chance, 0.0);
+    assert!(wave_spec(&Level::Hard, &d, 3).formation_chance > 0.0);
+    assert!(wave_spec(&Level::Medium, &d, 4).formation_chance > 0.0);
+}
+
+#[test]
+fn formation_spawn_spends_one_budget_per_member() {
+    // Wave 10 spawns formations; find a seed whose spawn rolls one.
+    let mut s = make_state();
+    s.wave.number = 10;
+    s.wave.remaining = 3;
+    s.difficulty.enemy_spawn_rate = 1;
+    let s2 = (0..500)
+        .map(|seed| tick(&s, &mut StdRng::seed_from_u64(seed)))
+        .find(|st| st.enemies.len() > 1)
+        .expect("some seed spawns a formation");
+    assert_eq!(s2.enemies.len(), 3, "capped by the remaining budget");
+    assert_eq!(s2.wave.remaining, 0);
+    assert!(s2
+        .enemies
+        .iter()
+        .all(|e| e.behavior == EnemyBehavior::Formation && e.y == 2));
+    assert_eq!(s2.enemies[1].x - s2.enemies[0].x, 4);
+}
+
+// ── tick — enemy fire patterns ────────────────────────────────────────────────
+
+/// First tick (over seeds 0..) in which the lone enemy of `s` fires.
+fn first_volley(s: &EntireGameStateInfo) -> EntireGameStateInfo {
+    (0..200)
+        .map(|seed| tick(s, &mut StdRng::seed_from_u64(seed)))
+        .find(|st| {
+            !st.enemy_shots.is_empty() || st.bullets.iter().any(|b| b.owner == BulletOwner::Enemy)
+        })
+        .expect("the enemy fires for some seed")
+}
+
+fn firing_state(kind: EnemyKind, x: i32) -> EntireGameStateInfo {
+    let mut s = make_state();
+    s.frame = 1;
+    s.difficulty.enemy_fire_chance = 2;
+    s.enemies.push(Enemy {
+        x,
+        y: 5,
+        hp: kind.max_hp(),
+        kind,
+        behavior: EnemyBehavior::Straight,
+    });
+    s
+}
+
+#[test]
+fn spacecraft_fires_straight_bullets() {
+    let s2 = first_volley(&firing_state(EnemyKind::Spacecraft, 10));
+    assert!(s2.enemy_shots.is_empty());
+    assert_eq!(s2.bullets.len(), 1);
+    assert_eq!(s2.bullets[0].x, 10);
+}
+
+#[test]
+fn octopus_fires_three_way_spread() {
+    let s2 = first_volley(&firing_state(EnemyKind::Octopus, 10));
+    assert!(s2.bullets.is_empty());
+    assert_eq!(s2.enemy_shots.len(), 3);
+    let vxs: Vec<f32> = s2.enemy_shots.iter().map(|s| s.vx).collect();
+    assert!(vxs[0] < 0.0 && vxs[1] == 0.0 && vxs[2] > 0.0);
+    assert!(s2.enemy_shots.iter().all(|s| s.vy > 0.0));
+}
+
+#[test]
+fn armored_fires_aimed_shots() {
+    // Player at x = 20: a shot from the left drifts right, and vice versa.
+    let from_left = first_volley(&firing_state(EnemyKind::Armored, 10));
+    assert_eq!(from_left.enemy_shots.len(), 1);
+    assert!(from_left.enemy_shots[0].vx > 0.0);
+    let from_right = first_volley(&firing_state(EnemyKind::Armored, 30));
+    assert!(from_right.enemy_shots[0].vx < 0.0);
+}
+
+#[test]
+fn aimed_shot_reaches_a_stationary_player() {
+    let mut s = first_volley(&firing_state(EnemyKind::Armored, 12));
+    s.enemies.clear();
+    s.difficulty.enemy_spawn_rate = 10_000;
+    let lives = s.player.lives;
+    let mut rng = seeded_rng();
+    for _ in 0..40 {
+        s = tick(&s, &mut rng);
+    }
+    assert_eq!(s.player.lives, lives - 1);
+}
+
+// ── tick — collision: player bullet ↔ enemy ──────────────────────────────────
+
+#[test]
+fn tick_player_bullet_hits_enemy_direct() {
+    // tick() moves bullets BEFORE collision detection.
+    // Player bullet moves UP (y-1), so place it one row below the enemy.
+    let mut s = make_state();
+    s.frame = 1; // frame 2, no movement
+    s.enemies.push(Enemy {
+        x: 10,
+        y: 5,
+        kind: EnemyKind::Spacecraft,
+        hp: 1,
+        behavior: EnemyBehavior::Straight,
+    });
+    s.bullets.push(Bullet {
+        x: 10,
+        y: 6,
+        owner: BulletOwner::Player(PlayerId::One),
+    }); // moves to y=5
+    let s2 = tick(&s, &mut seeded_rng());
+    assert!(s2.enemies.is_empty());
+    assert_eq!(s2.score, 100);
+}
+
+#[test]
+fn tick_player_bullet_hits_enemy_wide_box() {
+    // Bounding box is 3-wide: x±1 also hits
+    let mut s = make_state();
+    s.frame = 1;
+    s.enemies.push(Enemy {
+        x: 10,
+        y: 5,
+        kind: EnemyKind::Spacecraft,
+        hp: 1,
+        behavior: EnemyBehavior::Straight,
+    });
+    s.bullets.push(Bullet {
+        x: 11,
+        y: 6,
+        owner: BulletOwner::Player(PlayerId::One),
+    }); // x+1, moves to y=5
+    let s2 = tick(&s, &mut seeded_rng());
+    assert!(s2.enemies.is_empty());
+}
+
+#[test]
+fn tick_player_bullet_misses_enemy_outside_box() {
+    let mut s = make_state();
+    s.frame = 1;
+    s.enemies.push(Enemy {
+        x: 10,
+        y: 5,
+        kind: EnemyKind::Spacecraft,
+        hp: 1,
+        behavior: EnemyBehavior::Straight,
+    });
+    s.bullets.push(Bullet {
+        x: 12,
+        y: 5,
+        owner: BulletOwner::Player(PlayerId::One),
+    }); // x+2, outside
+    let s2 = tick(&s, &mut seeded_rng());
+    assert_eq!(s2.enemies.len(), 1);
+}
+
+#[test]
+fn tick_player_bullet_hits_enemy_second_row() {
+    // Bounding box is 2-tall: enemy.y+1 also hits
+    let mut s = make_state();
+    s.frame = 1;
+    s.enemies.push(Enemy {
+        x: 10,
+        y: 5,
+        kind: EnemyKind::Spacecraft,
+        hp: 1,
+        behavior: EnemyBehavior::Straight,
+    });
+    s.bullets.push(Bullet {
+        x: 10,
+        y: 6,
+        owner: BulletOwner::Player(PlayerId::One),
+    }); // y+1
+    let s2 = tick(&s, &mut seeded_rng());
+    assert!(s2.enemies.is_empty());
+}
+
+#[test]
+fn tick_octopus_scores_150() {
+    let mut s = make_state();
+    s.frame = 1;
+    s.enemies.push(Enemy {
+        x: 10,
+        y: 5,
+        kind: EnemyKind::Octopus,
+        hp: 1,
+        behavior: EnemyBehavior::Straight,
+    });
+    s.bullets.push(Bullet {
+        x: 10,
+        y: 6,
+        owner: BulletOwner::Player(PlayerId::One),
+    }); // moves to y=5
+    let s2 = tick(&s, &mut seeded_rng());
+    assert_eq!(s2.score, 150);
+}
+
+// ── tick — enemy hit points ───────────────────────────────────────────────────
+
+fn armored_at(x: i32, y: i32, hp: u32) -> Enemy {
+    Enemy {
+        x,
+        y,
+        kind: EnemyKind::Armored,
+        hp,
+        behavior: EnemyBehavior::Straight,
+    }
+}
+
+fn player_bullet(x: i32, y: i32) -> Bullet {
+    Bullet {
+        x,
+        y,
+        owner: BulletOwner::Player(PlayerId::One),
+    }
+}
+
+#[test]
+fn tick_bullet_damages_armored_without_killing() {
+    let mut s = make_state();
+    s.enemies.push(armored_at(10, 5, 3));
+    s.bullets.push(player_bullet(10, 7));
+    let s2 = tick(&s, &mut seeded_rng());
+    assert_eq!(s2.enemies.len(), 1);
+    assert_eq!(s2.enemies[0].hp, 2);
+    assert!(s2.bullets.is_empty(), "bullet must be consumed");
+    assert_eq!(s2.score, 0, "no score before the killing blow");
+    assert!(s2.events.is_empty());
+}
+
+#[test]
+fn tick_armored_dies_on_third_hit_and_scores_once() {
+    let mut s = make_state();
+    s.enemies.push(armored_at(10, 5, 3));
+    let mut total_kills = 0;
+    for _ in 0..3 {
+        s.bullets.push(player_bullet(10, 7));
+        s = tick(&s, &mut seeded_rng());
+        total_kills += s
+            .events
+            .iter()
+            .filter(|e| matches!(e, GameEvent::EnemyKilled(EnemyKind::Armored)))
+            .count();
+    }
+    assert!(s.enemies.is_empty());
+    assert_eq!(s.score, 250);
+    assert_eq!(total_kills, 1);
+}
+
+#[test]
+fn tick_two_bullets_in_one_frame_both_count() {
+    let mut s = make_state();
+    s.enemies.push(armored_at(10, 5, 2));
+    s.bullets.push(player_bullet(10, 7));
+    s.bullets.push(player_bullet(11, 7));
+    let s2 = tick(&s, &mut seeded_rng());
+    assert!(s2.enemies.is_empty());
+    assert_eq!(s2.score, 250);
+    assert!(s2.bullets.is_empty());
+}
+
+#[test]
+fn tick_surplus_bullet_passes_dead_enemy() {
+    // One HP left: the second bullet must not be spent on a corpse.
+    let mut s = make_state();
+    s.enemies.push(armored_at(10, 5, 1));
+    s.bullets.push(player_bullet(10, 7));
+    s.bullets.push(player_bullet(11, 7));
+    let s2 = tick(&s, &mut seeded_rng());
+    assert!(s2.enemies.is_empty());
+    assert_eq!(s2.bullets.len(), 1);
+}
+
+#[test]
+fn tick_flame_damages_armored() {
+    let mut s = make_state();
+    s.enemies.push(armored_at(10, 5, 3));
+    s.flame_bullets.push(FlameBullet {
+        x: 10.0,
+        y: 7.0,
+        vx: 0.0,
+        owner: PlayerId::One,
+    });
+    let s2 = tick(&s, &mut seeded_rng());
+    assert_eq!(s2.enemies[0].hp, 2);
+    assert!(s2.flame_bullets.is_empty());
+}
+
+#[test]
+fn tick_explosion_deals_two_damage() {
+    let mut s = make_state();
+    s.enemies.push(armored_at(23, 10, 3));
+    s.enemies.push(armored_at(17, 10, 2));
+    s.firebombs.push(FirebombProj {
+        x: 20,
+        y: 10,
+        fuse: 1,
+        owner: PlayerId::One,
+    });
+    let s2 = tick(&s, &mut seeded_rng());
+    assert_eq!(s2.enemies.len(), 1, "the 2-HP enemy dies");
+    assert_eq!(s2.enemies[0].hp, 1);
+    assert_eq!(s2.score, 250);
+}
+
+#[test]
+fn wave_one_spawns_no_armored() {
+    let d = Difficulty::preset(&Level::Hard);
+    assert_eq!(wave_spec(&Level::Hard, &d, 1).armored_chance, 0.0);
+    assert!(wave_spec(&Level::Hard, &d, 4).armored_chance > 0.0);
+}
+
+#[test]
+fn tick_spawned_enemy_has_full_hp() {
+    let mut s = make_state();
+    s.wave.number = 11;
+    s.frame = 64; // wave 11 halves Easy's spawn rate of 130 → 65
+    let s2 = tick(&s, &mut seeded_rng());
+    let e = &s2.enemies[0];
+    assert_eq!(e.hp, e.kind.max_hp());
+}
+
+// ── tick — collision: enemy bullet ↔ player ──────────────────────────────────
+
+#[test]
+fn tick_enemy_bullet_hits_player() {
+    // Enemy bullet moves DOWN (y+1) before collision detection.
+    // Collision checks against state.player.y (old position = 16).
+    // Place bullet at player.y-1 so it moves into player.y.
+    let mut s = make_state(); // player at (20, 16)
+    s.frame = 1;
+    s.bullets.push(Bullet {
+        x: 20,
+        y: 15,
+        owner: BulletOwner::Enemy,
+    }); // moves to y=16
+    let s2 = tick(&s, &mut seeded_rng());
+    assert_eq!(s2.player.lives, 2);
+}
+
+#[test]
+fn tick_player_loses_life_on_enemy_contact() {
+    let mut s = make_state(); // player.y = 16
+    s.frame = 1;
+    s.enemies.push(Enemy {
+        x: 5,
+        y: 16,
+        kind: EnemyKind::Spacecraft,
+        hp: 1,
+        behavior: EnemyBehavior::Straight,
+    });
+    let s2 = tick(&s, &mut seeded_rng());
+    assert_eq!(s2.player.lives, 2);
+}
+
+#[test]
+fn tick_game_over_when_lives_reach_zero() {
+    let mut s = make_state();
+    s.player.lives = 1;
+    s.frame = 1;
+    s.bullets.push(Bullet {
+        x: 20,
+        y: 15,
+        owner: BulletOwner::Enemy,
+    }); // moves to y=16
+    let s2 = tick(&s, &mut seeded_rng());
+    assert_eq!(s2.player.lives, 0);
+    assert_eq!(s2.status, GameStatus::GameOver);
+}
+
+#[test]
+fn tick_no_game_over_when_lives_above_zero() {
+    let mut s = make_state();
+    s.player.lives = 2;
+    s.frame = 1;
+    s.bullets.push(Bullet {
+        x: 20,
+        y: 15,
+        owner: BulletOwner::Enemy,
+    }); // moves to y=16
+    let s2 = tick(&s, &mut seeded_rng());
+    assert_eq!(s2.player.lives, 1);
+    assert_eq!(s2.status, GameStatus::Playing);
+}
+
+#[test]
+fn tick_lives_saturate_at_zero() {
+    let mut s = make_state();
+    s.player.lives = 0;
+    s.frame = 1;
+    s.bullets.push(Bullet {
+        x: 20,
+        y: 16,
+        owner: BulletOwner::Enemy,
+    });
+    let s2 = tick(&s, &mut seeded_rng());
+    assert_eq!(s2.player.lives, 0); // saturating_sub, no underflow
+}
+
+#[test]
+fn tick_score_saturates_at_u32_max() {
+    let mut s = make_state();
+    s.score = u32::MAX;
+    s.frame = 1;
+    s.enemies.push(Enemy {
+        x: 10,
+        y: 5,
+        kind: EnemyKind::Spacecraft,
+        hp: 1,
+        behavior: EnemyBehavior::Straight,
+    });
+    s.bullets.push(Bullet {
+        x: 10,
+        y: 6,
+        owner: BulletOwner::Player(PlayerId::One),
+    }); // moves to y=5 -> kill worth +100
+    let s2 = tick(&s, &mut seeded_rng());
+    assert_eq!(s2.score, u32::MAX, "score must saturate at u32::MAX");
+}
+
+// ── tick — invulnerability after a hit ───────────────────────────────────────
+
+/// `make_state` with an enemy bullet about to hit the player.
+fn about_to_be_hit() -> EntireGameStateInfo {
+    let mut s = make_state();
+    s.frame = 1;
+    s.bullets.push(Bullet {
+        x: 20,
+        y: 15,
+        owner: BulletOwner::Enemy,
+    });
+    s
+}
+
+#[test]
+fn losing_a_life_starts_invulnerability() {
+    let s2 = tick(&about_to_be_hit(), &mut seeded_rng());
+    assert_eq!(s2.player.lives, 2);
+    assert!(s2.player.invulnerable > 0);
+}
+
+#[test]
+fn invulnerable_player_is_not_hit() {
+    let mut s = about_to_be_hit();
+    s.player.invulnerable = 10;
+    s.enemy_shots.push(EnemyShot {
+        x: 20.0,
+        y: 16.0,
+        vx: 0.0,
+        vy: 0.0,
+    });
+    s.enemies.push(Enemy {
+        x: 5,
+        y: 16,
+        kind: EnemyKind::Spacecraft,
+        hp: 1,
+        behavior: EnemyBehavior::Straight,
+    });
+    let s2 = tick(&s, &mut seeded_rng());
+    assert_eq!(s2.player.lives, 3);
+    assert!(s2.events.is_empty());
+    assert!(s2.enemies.is_empty(), "contact enemies still crash");
+    assert_eq!(s2.player.invulnerable, 9);
+}
+
+#[test]
+fn invulnerability_wears_off() {
+    let mut s = about_to_be_hit();
+    s.player.invulnerable = 1;
+    s.bullets[0].y = 14; // lands on the player next frame
+    let s2 = tick(&s, &mut seeded_rng());
+    assert_eq!(s2.player.invulnerable, 0);
+    let s3 = tick(&s2, &mut seeded_rng());
+    assert_eq!(s3.player.lives, 2);
+}
+
+#[test]
+fn god_mode_does_not_start_invulnerability() {
+    let mut s = about_to_be_hit();
+    s.god_mode = true;
+    let s2 = tick(&s, &mut seeded_rng());
+    assert_eq!(s2.player.invulnerable, 0);
+}
+
+#[test]
+fn hit_clears_enemy_fire_when_configured() {
+    let mut s = about_to_be_hit();
+    s.difficulty.clear_shots_on_hit = true;
+    s.bullets.push(Bullet {
+        x: 5,
+        y: 5,
+        owner: BulletOwner::Enemy,
+    });
+    s.bullets.push(Bullet {
+        x: 8,
+        y: 10,
+        owner: BulletOwner::Player(PlayerId::One),
+    });
+    s.enemy_shots.push(EnemyShot {
+        x: 30.0,
+        y: 5.0,
+        vx: 0.0,
+        vy: 0.5,
+    });
+    let s2 = tick(&s, &mut seeded_rng());
+    assert!(s2.enemy_shots.is_empty());
+    assert_eq!(s2.bullets.len(), 1);
+    assert_eq!(s2.bullets[0].owner, BulletOwner::Player(PlayerId::One));
+
+    s.difficulty.clear_shots_on_hit = false;
+    let kept = tick(&s, &mut seeded_rng());
+    assert_eq!(kept.enemy_shots.len(), 1);
+    assert_eq!(kept.bullets.len(), 2);
+}
+
+#[test]
+fn power_up_loss_on_hit_is_configurable() {
+    let mut s = about_to_be_hit();
+    s.active_power_up = Some((BonusKind::RapidFire, 100));
+    s.difficulty.power_up_loss = PowerUpLoss::Keep;
+    let kept = tick(&s, &mut seeded_rng());
+    assert_eq!(kept.active_power_up, Some((BonusKind::RapidFire, 99)));
+
+    s.difficulty.power_up_loss = PowerUpLoss::Lose;
+    let lost = tick(&s, &mut seeded_rng());
+    assert_eq!(lost.active_power_up, None);
+}
+
+#[test]
+fn presets_keep_the_original_death_rules() {
+    for level in Level::ALL {
+        let d = Difficulty::preset(&level);
+        assert!(!d.clear_shots_on_hit, "{:?}", level);
+        assert_eq!(d.power_up_loss, PowerUpLoss::Keep, "{:?}", level);
+    }
+}
+
+// ── tick — enemy bullet hitbox: 3-wide × 2-tall ──────────────────────────────
+//
+// Player sprite:   ▲        ← row player.y     (tip, 1 col wide)
+//                 /█\       ← row player.y+1   (fuselage+wings, 3 cols wide)
+//
+// Hitbox covers both rows at x ± 1.
+
+#[test]
+fn tick_enemy_bullet_hits_player_fuselage_center() {
+    // Bullet at (player.x, player.y) → moves to (player.x, player.y+1) = fuselage center
+    let mut s = make_state(); // player at (20, 16)
+    s.frame = 1;
+    s.bullets.push(Bullet {
+        x: 20,
+        y: 16,
+        owner: BulletOwner::Enemy,
+    });
+    let s2 = tick(&s, &mut seeded_rng());
+    assert_eq!(s2.player.lives, 2);
+}
+
+#[test]
+fn tick_enemy_bullet_hits_player_left_wing() {
+    // Bullet at (player.x-1, player.y) → moves to (player.x-1, player.y+1) = left wing
+    let mut s = make_state();
+    s.frame = 1;
+    s.bullets.push(Bullet {
+        x: 19,
+        y: 16,
+        owner: BulletOwner::Enemy,
+    });
+    let s2 = tick(&s, &mut seeded_rng());
+    assert_eq!(s2.player.lives, 2);
+}
+
+#[test]
+fn tick_enemy_bullet_hits_player_right_wing() {
+    // Bullet at (player.x+1, player.y) → moves to (player.x+1, player.y+1) = right wing
+    let mut s = make_state();
+    s.frame = 1;
+    s.bullets.push(Bullet {
+        x: 21,
+        y: 16,
+        owner: BulletOwner::Enemy,
+    });
+    let s2 = tick(&s, &mut seeded_rng());
+    assert_eq!(s2.player.lives, 2);
+}
+
+#[test]
+fn tick_enemy_bullet_hits_player_tip_off_center() {
+    // Bullet at (player.x-1, player.y-1) → moves to (player.x-1, player.y) = tip row, x-1
+    let mut s = make_state();
+    s.frame = 1;
+    s.bullets.push(Bullet {
+        x: 19,
+        y: 15,
+        owner: BulletOwner::Enemy,
+    });
+    let s2 = tick(&s, &mut seeded_rng());
+    assert_eq!(s2.player.lives, 2);
+}
+
+#[test]
+fn tick_enemy_bullet_misses_player_too_far() {
+    // Bullet at (player.x+2, player.y-1) → moves to (player.x+2, player.y): x=22, outside ±1
+    let mut s = make_state();
+    s.frame = 1;
+    s.bullets.push(Bullet {
+        x: 22,
+        y: 15,
+        owner: BulletOwner::Enemy,
+    });
+    let s2 = tick(&s, &mut seeded_rng());
+    assert_eq!(s2.player.lives, 3);
 }
 
 #[test]
@@ -1201,69 +2126,706 @@ fn tick_enemy_bullet_consumed_on_hit() {
         owner: BulletOwner::Enemy,
     });
     let s2 = tick(&s, &mut seeded_rng());
-    assert!(s2.bullets.iter().all(|b| b.owner != BulletOwner::Enemy));
+    assert!(s2.bullets.iter().all(|b| b.owner != BulletOwner::Enemy));
+}
+
+// ── tick — enemy spawn ────────────────────────────────────────────────────────
+
+#[test]
+fn tick_enemy_spawns_on_interval() {
+    // Easy spawn_rate = 130; frame 129 → next frame 130 → spawn
+    let mut s = make_state();
+    s.frame = 129;
+    let s2 = tick(&s, &mut seeded_rng());
+    assert_eq!(s2.enemies.len(), 1);
+    assert_eq!(s2.enemies[0].y, 2);
+}
+
+#[test]
+fn tick_no_spawn_off_interval() {
+    let mut s = make_state();
+    s.frame = 1; // next frame = 2, not 90
+    let s2 = tick(&s, &mut seeded_rng());
+    assert!(s2.enemies.is_empty());
+}
+
+// ── Enemy contact: remove on reach ───────────────────────────────────────────
+
+#[test]
+fn tick_enemy_contact_costs_one_life() {
+    let mut s = make_state();
+    s.enemies.push(Enemy {
+        x: s.player.x,
+        y: s.player.y,
+        kind: EnemyKind::Spacecraft,
+        hp: 1,
+        behavior: EnemyBehavior::Straight,
+    });
+    let s2 = tick(&s, &mut seeded_rng());
+    assert_eq!(s2.player.lives, 2, "one life lost on contact");
+}
+
+#[test]
+fn tick_enemy_contact_removes_enemy() {
+    let mut s = make_state();
+    s.enemies.push(Enemy {
+        x: s.player.x,
+        y: s.player.y,
+        kind: EnemyKind::Spacecraft,
+        hp: 1,
+        behavior: EnemyBehavior::Straight,
+    });
+    let s2 = tick(&s, &mut seeded_rng());
+    assert!(
+        s2.enemies.is_empty(),
+        "enemy removed after crashing into player"
+    );
+}
+
+#[test]
+fn tick_enemy_contact_no_repeated_damage() {
+    // After the enemy is removed on contact, subsequent ticks deal no further damage.
+    let mut s = make_state();
+    s.enemies.push(Enemy {
+        x: s.player.x,
+        y: s.player.y,
+        kind: EnemyKind::Spacecraft,
+        hp: 1,
+        behavior: EnemyBehavior::Straight,
+    });
+    let s2 = tick(&s, &mut seeded_rng());
+    let s3 = tick(&s2, &mut seeded_rng());
+    assert_eq!(s2.player.lives, 2, "one life lost");
+    assert_eq!(s3.player.lives, 2, "no further damage next frame");
+}
+
+// ── apply_action ─────────────────────────────────────────────────────────────
+
+#[test]
+fn apply_action_matches_direct_calls() {
+    let s = make_state();
+    assert_eq!(
+        apply_action(&s, &PlayerAction::MoveLeft(10)).player.x,
+        move_player_left_n(&s, 10).player.x
+    );
+    assert_eq!(
+        apply_action(&s, &PlayerAction::MoveRight(2)).player.x,
+        move_player_right_n(&s, 2).player.x
+    );
+    assert_eq!(
+        apply_action(&s, &PlayerAction::Shoot).bullets.len(),
+        player_shoot(&s).bullets.len()
+    );
+}
+
+#[test]
+fn apply_action_toggles_debug_flags() {
+    let s = make_state();
+    assert!(apply_action(&s, &PlayerAction::ToggleDebug).debug_mode);
+    assert!(apply_action(&s, &PlayerAction::ToggleGodMode).god_mode);
+    assert!(apply_action(&s, &PlayerAction::ToggleSlowMo).slow_mo);
+}
+
+// ── Pause ─────────────────────────────────────────────────────────────────────
+
+#[test]
+fn pause_and_resume() {
+    let s = pause(&make_state());
+    assert_eq!(s.status, GameStatus::Paused(PauseItem::Resume));
+    assert_eq!(resume(&s).status, GameStatus::Playing);
+
+    let mut over = make_state();
+    over.status = GameStatus::GameOver;
+    assert_eq!(pause(&over).status, GameStatus::GameOver);
+}
+
+#[test]
+fn paused_tick_freezes_everything() {
+    let mut s = make_state();
+    s.frame = 7;
+    s.active_power_up = Some((BonusKind::SpreadShot, 300));
+    s.bullets.push(Bullet {
+        x: 10,
+        y: 10,
+        owner: BulletOwner::Player(PlayerId::One),
+    });
+    let paused = pause(&s);
+    let s2 = tick(&paused, &mut seeded_rng());
+    assert_eq!(s2.frame, 7);
+    assert_eq!(s2.active_power_up, Some((BonusKind::SpreadShot, 300)));
+    assert_eq!(s2.bullets[0].y, 10);
+    assert!(s2.events.is_empty());
+}
+
+#[test]
+fn paused_game_ignores_player_actions() {
+    let s = pause(&make_state());
+    assert_eq!(apply_action(&s, &PlayerAction::MoveLeft(3)).player.x, 20);
+    assert_eq!(apply_action(&s, &PlayerAction::MoveRight(3)).player.x, 20);
+    assert!(apply_action(&s, &PlayerAction::Shoot).bullets.is_empty());
+}
+
+#[test]
+fn pause_select_wraps_around() {
+    let s = pause(&make_state());
+    assert_eq!(
+        pause_select(&s, -1).status,
+        GameStatus::Paused(PauseItem::QuitToMenu)
+    );
+    let s = pause_select(&pause_select(&s, 1), 1);
+    assert_eq!(s.status, GameStatus::Paused(PauseItem::Settings));
+    assert_eq!(
+        pause_select(&pause_select(&s, 1), 1).status,
+        GameStatus::Paused(PauseItem::Resume)
+    );
+    assert_eq!(pause_select(&make_state(), 1).status, GameStatus::Playing);
+}
+
+// ── Resize ────────────────────────────────────────────────────────────────────
+
+#[test]
+fn playfield_fits_minimum() {
+    assert!(playfield_fits(MIN_WIDTH, MIN_HEIGHT));
+    assert!(!playfield_fits(MIN_WIDTH - 1, MIN_HEIGHT));
+    assert!(!playfield_fits(MIN_WIDTH, MIN_HEIGHT - 1));
+}
+
+#[test]
+fn resize_grows_playfield_and_keeps_positions() {
+    let mut s = make_state();
+    s.enemies.push(Enemy {
+        x: 10,
+        y: 5,
+        kind: EnemyKind::Spacecraft,
+        hp: 1,
+        behavior: EnemyBehavior::Straight,
+    });
+    let s2 = resize(&s, 80, 30);
+    assert_eq!((s2.width, s2.height), (80, 30));
+    assert_eq!(s2.player.x, 20);
+    assert_eq!(s2.player.y, 26, "player keeps to the row above the border");
+    assert_eq!((s2.enemies[0].x, s2.enemies[0].y), (10, 5));
+}
+
+#[test]
+fn resize_shrink_clamps_everything_inside() {
+    let mut s = make_state();
+    s.width = 80;
+    s.height = 30;
+    s.player.x = 70;
+    s.player.y = 26;
+    s.enemies.push(Enemy {
+        x: 77,
+        y: 24,
+        kind: EnemyKind::Octopus,
+        hp: 1,
+        behavior: EnemyBehavior::Straight,
+    });
+    s.bullets.push(Bullet {
+        x: 75,
+        y: 27,
+        owner: BulletOwner::Enemy,
+    });
+    s.enemy_shots.push(EnemyShot {
+        x: 76.5,
+        y: 25.0,
+        vx: 0.0,
+        vy: 0.5,
+    });
+    s.bonus_items.push(BonusItem {
+        x: 60,
+        y: 20,
+        kind: BonusKind::RapidFire,
+    });
+    s.explosions.push(Explosion {
+        x: 78,
+        y: 27,
+        frames: 3,
+    });
+
+    let s2 = resize(&s, 40, 20);
+    assert_eq!((s2.player.x, s2.player.y), (38, 16));
+    let e = &s2.enemies[0];
+    assert_eq!((e.x, e.y), (37, 15), "enemy stays above the player");
+    assert_eq!((s2.bullets[0].x, s2.bullets[0].y), (38, 17));
+    assert_eq!((s2.enemy_shots[0].x, s2.enemy_shots[0].y), (38.0, 17.0));
+    assert_eq!((s2.bonus_items[0].x, s2.bonus_items[0].y), (38, 17));
+    assert_eq!((s2.explosions[0].x, s2.explosions[0].y), (38, 17));
+    assert_eq!(s2.player.lives, 3);
+}
+
+#[test]
+fn resize_never_goes_below_minimum() {
+    let s2 = resize(&make_state(), 10, 5);
+    assert_eq!((s2.width, s2.height), (MIN_WIDTH, MIN_HEIGHT));
+}
+
+#[test]
+fn resize_applies_while_paused() {
+    let s = pause(&make_state());
+    let s2 = apply_action(&s, &PlayerAction::Resize(60, 24));
+    assert_eq!((s2.width, s2.height), (60, 24));
+    assert_eq!(s2.status, GameStatus::Paused(PauseItem::Resume));
+}
+
+// ── tick — events ─────────────────────────────────────────────────────────────
+
+#[test]
+fn tick_reports_enemy_killed_event() {
+    let mut s = make_state();
+    s.frame = 1;
+    s.enemies.push(Enemy {
+        x: 10,
+        y: 5,
+        kind: EnemyKind::Octopus,
+        hp: 1,
+        behavior: EnemyBehavior::Straight,
+    });
+    s.bullets.push(Bullet {
+        x: 10,
+        y: 6,
+        owner: BulletOwner::Player(PlayerId::One),
+    });
+    let s2 = tick(&s, &mut seeded_rng());
+    assert_eq!(s2.events, vec![GameEvent::EnemyKilled(EnemyKind::Octopus)]);
+}
+
+#[test]
+fn tick_reports_hit_cause() {
+    let mut s = make_state();
+    s.frame = 1;
+    s.bullets.push(Bullet {
+        x: 20,
+        y: 15,
+        owner: BulletOwner::Enemy,
+    });
+    let s2 = tick(&s, &mut seeded_rng());
+    assert_eq!(s2.events, vec![GameEvent::PlayerHit(HitCause::EnemyBullet)]);
+
+    let mut s = make_state();
+    s.enemies.push(Enemy {
+        x: s.player.x,
+        y: s.player.y,
+        kind: EnemyKind::Spacecraft,
+        hp: 1,
+        behavior: EnemyBehavior::Straight,
+    });
+    let s2 = tick(&s, &mut seeded_rng());
+    assert_eq!(
+        s2.events,
+        vec![GameEvent::PlayerHit(HitCause::EnemyContact)]
+    );
 }
 
-// ── tick — enemy spawn ────────────────────────────────────────────────────────
+#[test]
+fn tick_no_hit_event_in_god_mode() {
+    let mut s = make_state();
+    s.frame = 1;
+    s.god_mode = true;
+    s.bullets.push(Bullet {
+        x: 20,
+        y: 15,
+        owner: BulletOwner::Enemy,
+    });
+    let s2 = tick(&s, &mut seeded_rng());
+    assert!(s2.events.is_empty());
+}
 
 #[test]
-fn tick_enemy_spawns_on_interval() {
-    // Easy spawn_rate = 130; frame 129 → next frame 130 → spawn
+fn tick_reports_power_up_caught() {
     let mut s = make_state();
-    s.frame = 129;
+    s.frame = 1;
+    s.bonus_items.push(BonusItem {
+        x: s.player.x,
+        y: s.player.y,
+        kind: BonusKind::RapidFire,
+    });
     let s2 = tick(&s, &mut seeded_rng());
-    assert_eq!(s2.enemies.len(), 1);
-    assert_eq!(s2.enemies[0].y, 2);
+    assert_eq!(
+        s2.events,
+        vec![GameEvent::PowerUpCaught(BonusKind::RapidFire)]
+    );
 }
 
 #[test]
-fn tick_no_spawn_off_interval() {
+fn tick_clears_previous_events() {
     let mut s = make_state();
-    s.frame = 1; // next frame = 2, not 90
+    s.frame = 1;
+    s.events = vec![GameEvent::PlayerHit(HitCause::EnemyBullet)];
+    let s2 = tick(&s, &mut seeded_rng());
+    assert!(s2.events.is_empty());
+}
+
+// ── Co-op ─────────────────────────────────────────────────────────────────────
+
+/// `make_state` with player two joined: player one at x=13, player two at
+/// x=26, both on row 16.
+fn coop_state() -> EntireGameStateInfo {
+    let mut s = with_player_two(&make_state());
+    s.frame = 1;
+    s
+}
+
+fn p2(s: &EntireGameStateInfo) -> &Player2 {
+    s.player2.as_ref().expect("co-op state")
+}
+
+#[test]
+fn with_player_two_spreads_the_ships_out() {
+    let s = coop_state();
+    assert_eq!(s.player.x, 13);
+    assert_eq!(p2(&s).ship.x, 26);
+    assert_eq!(p2(&s).ship.y, s.player.y);
+    assert_eq!(p2(&s).ship.lives, 3);
+    assert_eq!(p2(&s).score, 0);
+}
+
+#[test]
+fn player_two_actions_do_nothing_solo() {
+    let s = make_state();
+    for action in [
+        PlayerAction::P2MoveLeft(1),
+        PlayerAction::P2MoveRight(1),
+        PlayerAction::P2Shoot,
+    ] {
+        let s2 = apply_action(&s, &action);
+        assert!(s2.player2.is_none());
+        assert!(s2.bullets.is_empty());
+        assert_eq!(s2.player.x, s.player.x);
+    }
+}
+
+#[test]
+fn player_two_moves_and_shoots_independently() {
+    let s = coop_state();
+    let s = apply_action(&s, &PlayerAction::P2MoveLeft(2));
+    assert_eq!(p2(&s).ship.x, 24);
+    assert_eq!(s.player.x, 13, "player one stays put");
+    let s = apply_action(&s, &PlayerAction::P2Shoot);
+    assert_eq!(s.bullets.len(), 1);
+    assert_eq!((s.bullets[0].x, s.bullets[0].y), (24, 15));
+    assert_eq!(s.bullets[0].owner, BulletOwner::Player(PlayerId::Two));
+    assert_eq!(s.muzzle_flash, 0, "the muzzle flash is player one's");
+}
+
+#[test]
+fn bullet_caps_are_per_player() {
+    let mut s = coop_state();
+    for _ in 0..3 {
+        s = player_shoot(&s);
+        s.bullets.iter_mut().for_each(|b| b.y -= 2);
+    }
+    let s = player_two_shoot(&s);
+    assert_eq!(
+        s.bullets.len(),
+        4,
+        "player one's full cap does not block player two"
+    );
+}
+
+#[test]
+fn player_two_uses_their_own_power_up() {
+    let mut s = coop_state();
+    s.player2.as_mut().unwrap().active_power_up = Some((BonusKind::FlameBurst, 100));
+    let s = player_two_shoot(&s);
+    assert_eq!(s.flame_bullets.len(), 4);
+    assert!(s.flame_bullets.iter().all(|f| f.owner == PlayerId::Two));
+    let s = player_shoot(&s);
+    assert_eq!(s.bullets.len(), 1, "player one still fires normal bullets");
+}
+
+#[test]
+fn kills_score_for_the_player_who_shot() {
+    let mut s = coop_state();
+    s.enemies.push(Enemy {
+        x: 26,
+        y: 8,
+        kind: EnemyKind::Octopus,
+        hp: 1,
+        behavior: EnemyBehavior::Straight,
+    });
+    s.bullets.push(Bullet {
+        x: 26,
+        y: 9,
+        owner: BulletOwner::Player(PlayerId::Two),
+    });
     let s2 = tick(&s, &mut seeded_rng());
     assert!(s2.enemies.is_empty());
+    assert_eq!(s2.score, 150, "the team score counts every kill");
+    assert_eq!(player_score(&s2, PlayerId::Two), 150);
+    assert_eq!(player_score(&s2, PlayerId::One), 0);
 }
 
-// ── Enemy contact: remove on reach ───────────────────────────────────────────
+#[test]
+fn enemy_fire_hits_only_the_ship_it_reaches() {
+    let mut s = coop_state();
+    s.bullets.push(Bullet {
+        x: 26,
+        y: 15,
+        owner: BulletOwner::Enemy,
+    });
+    let s2 = tick(&s, &mut seeded_rng());
+    assert_eq!(p2(&s2).ship.lives, 2);
+    assert!(p2(&s2).ship.invulnerable > 0);
+    assert_eq!(s2.player.lives, 3);
+    assert_eq!(s2.player.invulnerable, 0);
+}
 
 #[test]
-fn tick_enemy_contact_costs_one_life() {
-    let mut s = make_state();
+fn contact_enemy_crashes_into_the_nearest_ship() {
+    let mut s = coop_state();
     s.enemies.push(Enemy {
-        x: s.player.x,
-        y: s.player.y,
+        x: 24,
+        y: 16,
         kind: EnemyKind::Spacecraft,
+        hp: 1,
+        behavior: EnemyBehavior::Straight,
     });
     let s2 = tick(&s, &mut seeded_rng());
-    assert_eq!(s2.player.lives, 2, "one life lost on contact");
+    assert!(s2.enemies.is_empty());
+    assert_eq!(p2(&s2).ship.lives, 2);
+    assert_eq!(s2.player.lives, 3);
 }
 
 #[test]
-fn tick_enemy_contact_removes_enemy() {
+fn bonus_goes_to_the_ship_that_catches_it() {
+    let mut s = coop_state();
+    s.bonus_items.push(BonusItem {
+        x: 26,
+        y: 16,
+        kind: BonusKind::SpreadShot,
+    });
+    let s2 = tick(&s, &mut seeded_rng());
+    assert!(matches!(
+        p2(&s2).active_power_up,
+        Some((BonusKind::SpreadShot, _))
+    ));
+    assert!(s2.active_power_up.is_none());
+}
+
+#[test]
+fn game_goes_on_while_one_player_has_lives() {
+    let mut s = coop_state();
+    s.player.lives = 1;
+    s.bullets.push(Bullet {
+        x: 13,
+        y: 15,
+        owner: BulletOwner::Enemy,
+    });
+    let s2 = tick(&s, &mut seeded_rng());
+    assert_eq!(s2.player.lives, 0);
+    assert_eq!(s2.status, GameStatus::Playing);
+    assert_eq!(players_in_play(&s2), vec![PlayerId::Two]);
+}
+
+#[test]
+fn game_over_once_both_players_are_out() {
+    let mut s = coop_state();
+    s.player.lives = 0;
+    s.player2.as_mut().unwrap().ship.lives = 1;
+    s.bullets.push(Bullet {
+        x: 26,
+        y: 15,
+        owner: BulletOwner::Enemy,
+    });
+    let s2 = tick(&s, &mut seeded_rng());
+    assert_eq!(s2.status, GameStatus::GameOver);
+}
+
+#[test]
+fn player_who_is_out_cannot_act_or_be_hit() {
+    let mut s = coop_state();
+    s.player2.as_mut().unwrap().ship.lives = 0;
+    let s = apply_action(&s, &PlayerAction::P2Shoot);
+    let s = apply_action(&s, &PlayerAction::P2MoveLeft(3));
+    assert!(s.bullets.is_empty());
+    assert_eq!(p2(&s).ship.x, 26);
+
+    let mut s = s;
+    s.bonus_items.push(BonusItem {
+        x: 26,
+        y: 16,
+        kind: BonusKind::ExtraLife,
+    });
+    let s2 = tick(&s, &mut seeded_rng());
+    assert_eq!(p2(&s2).ship.lives, 0, "a ship that is out catches nothing");
+    assert_eq!(s2.bonus_items.len(), 1);
+}
+
+#[test]
+fn aimed_fire_targets_the_nearest_ship() {
+    // Player one at x = 13, player two at x = 26.
+    let nearer_one = first_volley(&with_player_two(&firing_state(EnemyKind::Armored, 18)));
+    assert!(nearer_one.enemy_shots[0].vx < 0.0);
+    let nearer_two = first_volley(&with_player_two(&firing_state(EnemyKind::Armored, 22)));
+    assert!(nearer_two.enemy_shots[0].vx > 0.0);
+}
+
+#[test]
+fn resize_moves_player_two_with_the_floor() {
+    let s = coop_state();
+    let s2 = resize(&s, 60, 30);
+    assert_eq!(p2(&s2).ship.y, 26);
+    assert_eq!(p2(&s2).ship.y, s2.player.y);
+    assert_eq!(p2(&s2).ship.x, 26);
+}
+
+#[test]
+fn player_two_leaving_takes_their_ship_out() {
+    let mut s = coop_state();
+    s.player2.as_mut().unwrap().score = 300;
+    s.player2.as_mut().unwrap().active_power_up = Some((BonusKind::RapidFire, 50));
+    let s2 = apply_action(&s, &PlayerAction::P2Leave);
+    assert_eq!(p2(&s2).ship.lives, 0);
+    assert_eq!(p2(&s2).active_power_up, None);
+    assert_eq!(p2(&s2).score, 300, "their points stay on the board");
+    assert_eq!(s2.cheer_msg.as_ref().unwrap().0, "PLAYER 2 LEFT");
+
+    // Player one carries on; the game is not over.
+    let s3 = tick(&s2, &mut seeded_rng());
+    assert_eq!(s3.status, GameStatus::Playing);
+    assert_eq!(players_in_play(&s3), vec![PlayerId::One]);
+}
+
+#[test]
+fn player_two_leaving_is_ignored_in_a_solo_game() {
+    let s = make_state();
+    let s2 = apply_action(&s, &PlayerAction::P2Leave);
+    assert!(s2.player2.is_none());
+    assert_eq!(s2.cheer_msg, s.cheer_msg);
+}
+
+// ── Run statistics ────────────────────────────────────────────────────────────
+
+fn enemy_at(x: i32, y: i32, kind: EnemyKind) -> Enemy {
+    Enemy {
+        x,
+        y,
+        hp: kind.max_hp(),
+        kind,
+        behavior: EnemyBehavior::Straight,
+    }
+}
+
+#[test]
+fn shots_fired_counts_every_projectile() {
+    let s = make_state();
+    assert_eq!(player_shoot(&s).stats.shots_fired, 1);
+
+    let mut spread = make_state();
+    spread.active_power_up = Some((BonusKind::SpreadShot, 100));
+    assert_eq!(player_shoot(&spread).stats.shots_fired, 3);
+
+    let mut flame = make_state();
+    flame.active_power_up = Some((BonusKind::FlameBurst, 100));
+    assert_eq!(player_shoot(&flame).stats.shots_fired, 4);
+
+    let mut bomb = make_state();
+    bomb.active_power_up = Some((BonusKind::Firebomb, 100));
+    assert_eq!(player_shoot(&bomb).stats.shots_fired, 1);
+}
+
+#[test]
+fn capped_shot_is_not_counted() {
     let mut s = make_state();
-    s.enemies.push(Enemy {
-        x: s.player.x,
-        y: s.player.y,
-        kind: EnemyKind::Spacecraft,
+    for _ in 0..4 {
+        s = player_shoot(&s);
+    }
+    assert_eq!(s.bullets.len(), 3);
+    assert_eq!(s.stats.shots_fired, 3);
+}
+
+#[test]
+fn tick_tallies_hits_and_kills_per_kind() {
+    let mut s = make_state();
+    s.enemies.push(enemy_at(10, 8, EnemyKind::Spacecraft));
+    s.enemies.push(enemy_at(30, 8, EnemyKind::Armored));
+    for x in [10, 30, 20] {
+        s.bullets.push(Bullet {
+            x,
+            y: 9,
+            owner: BulletOwner::Player(PlayerId::One),
+        });
+    }
+    let s2 = tick(&s, &mut seeded_rng());
+    assert_eq!(s2.stats.shots_hit, 2, "the stray bullet is no hit");
+    assert_eq!(s2.stats.kills, [1, 0, 0], "the armoured one survives");
+    assert_eq!(s2.stats.total_kills(), 1);
+}
+
+#[test]
+fn firebomb_blast_is_one_hit() {
+    let mut s = make_state();
+    s.enemies.push(enemy_at(20, 8, EnemyKind::Spacecraft));
+    s.enemies.push(enemy_at(21, 8, EnemyKind::Octopus));
+    s.firebombs.push(FirebombProj {
+        x: 20,
+        y: 9,
+        fuse: 30,
+        owner: PlayerId::One,
     });
     let s2 = tick(&s, &mut seeded_rng());
-    assert!(
-        s2.enemies.is_empty(),
-        "enemy removed after crashing into player"
-    );
+    assert!(s2.enemies.is_empty());
+    assert_eq!(s2.stats.shots_hit, 1);
+    assert_eq!(s2.stats.kills, [1, 1, 0]);
 }
 
 #[test]
-fn tick_enemy_contact_no_repeated_damage() {
-    // After the enemy is removed on contact, subsequent ticks deal no further damage.
+fn tick_tallies_power_ups_and_lives_lost() {
     let mut s = make_state();
-    s.enemies.push(Enemy {
+    s.frame = 1;
+    s.bonus_items.push(BonusItem {
         x: s.player.x,
         y: s.player.y,
-        kind: EnemyKind::Spacecraft,
+        kind: BonusKind::RapidFire,
+    });
+    s.bullets.push(Bullet {
+        x: 20,
+        y: 16,
+        owner: BulletOwner::Enemy,
     });
     let s2 = tick(&s, &mut seeded_rng());
-    let s3 = tick(&s2, &mut seeded_rng());
-    assert_eq!(s2.player.lives, 2, "one life lost");
-    assert_eq!(s3.player.lives, 2, "no further damage next frame");
+    assert_eq!(s2.stats.power_ups, [0, 0, 1, 0, 0]);
+    assert_eq!(s2.stats.lives_lost, 1);
+
+    s.god_mode = true;
+    assert_eq!(tick(&s, &mut seeded_rng()).stats.lives_lost, 0);
+}
+
+#[test]
+fn paused_frames_are_not_survived() {
+    let mut s = make_state();
+    s = tick(&s, &mut seeded_rng());
+    assert_eq!(s.stats.frames, 1);
+    s.status = GameStatus::Paused(PauseItem::Resume);
+    s = tick(&s, &mut seeded_rng());
+    assert_eq!(s.stats.frames, 1);
+}
+
+#[test]
+fn peak_rate_is_the_best_window() {
+    let mut s = make_state();
+    s.stats.frames = SCORE_RATE_WINDOW - 1;
+    s.stats.window_points = 500;
+    s.stats.peak_rate = 400;
+    s.enemies.push(enemy_at(20, 8, EnemyKind::Spacecraft));
+    s.bullets.push(Bullet {
+        x: 20,
+        y: 9,
+        owner: BulletOwner::Player(PlayerId::One),
+    });
+    let s2 = tick(&s, &mut seeded_rng());
+    assert_eq!(s2.stats.peak_rate, 600);
+    assert_eq!(s2.stats.window_points, 0, "a new window starts");
+
+    let mut s3 = s2.clone();
+    s3.enemies.push(enemy_at(20, 8, EnemyKind::Spacecraft));
+    s3.bullets.push(Bullet {
+        x: 20,
+        y: 9,
+        owner: BulletOwner::Player(PlayerId::One),
+    });
+    let s4 = tick(&s3, &mut seeded_rng());
+    assert_eq!(s4.stats.window_points, 100);
+    assert_eq!(s4.stats.peak_rate, 600);
 }
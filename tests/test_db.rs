@@ -0,0 +1,602 @@
+use rusqlite::{params, Connection};
+
+use shooting_game::db::*;
+use shooting_game::entities::RunStats;
+
+fn memory_db() -> Connection {
+    open(&Location::Memory).unwrap()
+}
+
+fn temp_path(name: &str) -> std::path::PathBuf {
+    std::env::temp_dir().join(format!("shooting_game_{}_{}", std::process::id(), name))
+}
+
+/// A database file at `name` built from the v1 fixture, as the first release
+/// left it.
+fn v1_database(name: &str) -> std::path::PathBuf {
+    let path = temp_path(name);
+    let _ = std::fs::remove_file(&path);
+    let conn = Connection::open(&path).unwrap();
+    conn.execute_batch(include_str!("fixtures/shooting_game_v1.sql"))
+        .unwrap();
+    path
+}
+
+fn columns(conn: &Connection, table: &str) -> Vec<String> {
+    let mut stmt = conn
+        .prepare(&format!("PRAGMA table_info({})", table))
+        .unwrap();
+    let names = stmt.query_map([], |row| row.get(1)).unwrap();
+    names.map(|n| n.unwrap()).collect()
+}
+
+fn count(conn: &Connection, table: &str) -> u32 {
+    conn.query_row(&format!("SELECT COUNT(*) FROM {}", table), [], |row| {
+        row.get(0)
+    })
+    .unwrap()
+}
+
+fn soft_delete(conn: &Connection, table: &str, username: &str) {
+    conn.execute(
+        &format!(
+            "UPDATE {} SET deleted_at = datetime('now') WHERE username = ?1",
+            table
+        ),
+        params![username],
+    )
+    .unwrap();
+}
+
+/// `count` players on `hard`, scoring 100, 200, … so player `i` ranks
+/// `count - i`.
+fn board(conn: &Connection, count: u32) {
+    for i in 1..=count {
+        upsert_top_score(conn, &format!("p{}", i), "hard", i * 100, i).unwrap();
+    }
+}
+
+// ── Top scores ────────────────────────────────────────────────────────────────
+
+#[test]
+fn top_scores_best_first() {
+    let conn = memory_db();
+    board(&conn, 3);
+    upsert_top_score(&conn, "other", "easy", 5000, 9).unwrap();
+
+    let page = top_scores_page(&conn, "hard", 0, 10).unwrap();
+    assert_eq!(page.total, 3);
+    assert_eq!(page.offset, 0);
+    let names: Vec<_> = page.rows.iter().map(|r| r.username.as_str()).collect();
+    assert_eq!(names, ["p3", "p2", "p1"]);
+    let ranks: Vec<_> = page.rows.iter().map(|r| r.rank).collect();
+    assert_eq!(ranks, [1, 2, 3]);
+    assert_eq!(page.rows[0].points, 300);
+    assert_eq!(page.rows[0].wave, 3);
+    assert_eq!(page.rows[0].date.len(), "2026-01-01".len());
+}
+
+#[test]
+fn top_scores_pages() {
+    let conn = memory_db();
+    board(&conn, 25);
+
+    let second = top_scores_page(&conn, "hard", 10, 10).unwrap();
+    assert_eq!(second.total, 25);
+    assert_eq!(second.rows.len(), 10);
+    assert_eq!(second.rows[0].rank, 11);
+    assert_eq!(second.rows[0].username, "p15");
+
+    let last = top_scores_page(&conn, "hard", 20, 10).unwrap();
+    assert_eq!(last.rows.len(), 5);
+    assert_eq!(last.rows[4].rank, 25);
+
+    assert!(top_scores_page(&conn, "hard", 30, 10)
+        .unwrap()
+        .rows
+        .is_empty());
+}
+
+#[test]
+fn equal_scores_share_a_rank() {
+    let conn = memory_db();
+    upsert_top_score(&conn, "a", "hard", 500, 1).unwrap();
+    upsert_top_score(&conn, "b", "hard", 300, 1).unwrap();
+    upsert_top_score(&conn, "c", "hard", 300, 1).unwrap();
+    upsert_top_score(&conn, "d", "hard", 100, 1).unwrap();
+
+    let page = top_scores_page(&conn, "hard", 0, 10).unwrap();
+    let ranks: Vec<_> = page.rows.iter().map(|r| r.rank).collect();
+    assert_eq!(ranks, [1, 2, 2, 4]);
+    // The earlier of two equal scores is listed first.
+    assert_eq!(page.rows[1].username, "b");
+    assert_eq!(player_rank(&conn, "hard", "c").unwrap().unwrap().rank, 2);
+}
+
+#[test]
+fn deleted_top_scores_are_left_out() {
+    let conn = memory_db();
+    board(&conn, 3);
+    soft_delete(&conn, "top_scores", "p3");
+
+    let page = top_scores_page(&conn, "hard", 0, 10).unwrap();
+    assert_eq!(page.total, 2);
+    assert_eq!(page.rows[0].username, "p2");
+    assert_eq!(page.rows[0].rank, 1);
+    assert_eq!(player_rank(&conn, "hard", "p2").unwrap().unwrap().rank, 1);
+    assert_eq!(player_rank(&conn, "hard", "p3").unwrap(), None);
+}
+
+// ── Player rank ───────────────────────────────────────────────────────────────
+
+#[test]
+fn player_rank_beyond_the_first_page() {
+    let conn = memory_db();
+    board(&conn, 30);
+    let me = player_rank(&conn, "hard", "p5").unwrap().unwrap();
+    assert_eq!(me.rank, 26);
+    assert_eq!(me.points, 500);
+    assert_eq!(me.username, "p5");
+}
+
+#[test]
+fn player_rank_is_per_difficulty() {
+    let conn = memory_db();
+    board(&conn, 3);
+    assert_eq!(player_rank(&conn, "easy", "p1").unwrap(), None);
+    assert_eq!(player_rank(&conn, "hard", "nobody").unwrap(), None);
+}
+
+// ── Recent games ──────────────────────────────────────────────────────────────
+
+#[test]
+fn recent_games_newest_first() {
+    let conn = memory_db();
+    for points in [100, 200, 300] {
+        insert_score(&conn, "me", "hard", points, 2, None).unwrap();
+    }
+    insert_score(&conn, "me", "easy", 999, 2, None).unwrap();
+    insert_score(&conn, "you", "hard", 999, 2, None).unwrap();
+
+    let page = recent_games_page(&conn, "hard", "me", 0, 10).unwrap();
+    assert_eq!(page.total, 3);
+    let points: Vec<_> = page.rows.iter().map(|g| g.points).collect();
+    assert_eq!(points, [300, 200, 100]);
+    assert_eq!(page.rows[0].p2_points, None);
+    assert_eq!(page.rows[0].date.len(), "2026-01-01 12:00".len());
+}
+
+#[test]
+fn recent_games_pages() {
+    let conn = memory_db();
+    for points in 1..=12 {
+        insert_score(&conn, "me", "hard", points, 1, None).unwrap();
+    }
+    let page = recent_games_page(&conn, "hard", "me", 5, 5).unwrap();
+    assert_eq!(page.total, 12);
+    let points: Vec<_> = page.rows.iter().map(|g| g.points).collect();
+    assert_eq!(points, [7, 6, 5, 4, 3]);
+    assert_eq!(
+        recent_games_page(&conn, "hard", "me", 10, 5)
+            .unwrap()
+            .rows
+            .len(),
+        2
+    );
+}
+
+#[test]
+fn recent_games_order_by_date() {
+    let conn = memory_db();
+    insert_score(&conn, "me", "hard", 1, 1, None).unwrap();
+    insert_score(&conn, "me", "hard", 2, 1, None).unwrap();
+    // The first game, inserted first, was played later.
+    conn.execute(
+        "UPDATE scores SET created_at = '2099-01-01 10:00:00' WHERE points = 1",
+        [],
+    )
+    .unwrap();
+    let page = recent_games_page(&conn, "hard", "me", 0, 5).unwrap();
+    assert_eq!(page.rows[0].points, 1);
+    assert_eq!(page.rows[0].date, "2099-01-01 10:00");
+}
+
+#[test]
+fn recent_coop_games_show_player_two() {
+    let conn = memory_db();
+    insert_score(&conn, "me", "coop:hard", 900, 4, Some(400)).unwrap();
+    let page = recent_games_page(&conn, "coop:hard", "me", 0, 5).unwrap();
+    assert_eq!(page.rows[0].p2_points, Some(400));
+    assert_eq!(page.rows[0].wave, 4);
+}
+
+#[test]
+fn deleted_games_are_left_out() {
+    let conn = memory_db();
+    insert_score(&conn, "me", "hard", 100, 1, None).unwrap();
+    insert_score(&conn, "me", "hard", 200, 1, None).unwrap();
+    conn.execute(
+        "UPDATE scores SET deleted_at = datetime('now') WHERE points = 200",
+        [],
+    )
+    .unwrap();
+    let page = recent_games_page(&conn, "hard", "me", 0, 5).unwrap();
+    assert_eq!(page.total, 1);
+    assert_eq!(page.rows[0].points, 100);
+
+    soft_delete(&conn, "scores", "me");
+    assert_eq!(
+        recent_games_page(&conn, "hard", "me", 0, 5).unwrap().total,
+        0
+    );
+}
+
+// ── Migrations ────────────────────────────────────────────────────────────────
+
+#[test]
+fn new_database_is_at_the_current_version() {
+    let conn = memory_db();
+    assert_eq!(schema_version(&conn).unwrap(), SCHEMA_VERSION);
+    assert!(columns(&conn, "scores").contains(&"p2_points".to_string()));
+}
+
+#[test]
+fn v1_database_is_upgraded_in_place() {
+    let path = v1_database("v1_upgrade.db");
+    {
+        let conn = Connection::open(&path).unwrap();
+        assert_eq!(schema_version(&conn).unwrap(), 0);
+        init(&conn).unwrap();
+    }
+
+    let conn = Connection::open(&path).unwrap();
+    assert_eq!(schema_version(&conn).unwrap(), SCHEMA_VERSION);
+    assert!(columns(&conn, "top_scores").contains(&"wave".to_string()));
+    for column in ["wave", "players", "p2_points"] {
+        assert!(columns(&conn, "scores").contains(&column.to_string()));
+    }
+
+    // Every row survives, soft-deleted ones included.
+    assert_eq!(count(&conn, "top_scores"), 4);
+    assert_eq!(count(&conn, "scores"), 5);
+
+    // Old rows read with the new columns' defaults.
+    let board = top_scores_page(&conn, "hard", 0, 10).unwrap();
+    let names: Vec<_> = board.rows.iter().map(|r| r.username.as_str()).collect();
+    assert_eq!(names, ["alice", "bob"]);
+    assert_eq!(board.rows[0].points, 4200);
+    assert_eq!(board.rows[0].wave, 0);
+    assert_eq!(board.rows[0].date, "2024-03-02");
+    let games = recent_games_page(&conn, "hard", "alice", 0, 10).unwrap();
+    let points: Vec<_> = games.rows.iter().map(|g| g.points).collect();
+    assert_eq!(points, [4200, 1500]);
+    assert_eq!(games.rows[0].p2_points, None);
+    assert_eq!(load_top_score(&conn, "hard").unwrap(), 4200);
+    assert_eq!(load_stats(&conn, 1).unwrap(), None);
+
+    // And new games go in next to them.
+    insert_score(&conn, "alice", "coop:hard", 700, 3, Some(300)).unwrap();
+    upsert_top_score(&conn, "bob", "hard", 5000, 6).unwrap();
+    assert_eq!(player_rank(&conn, "hard", "bob").unwrap().unwrap().rank, 1);
+    let _ = std::fs::remove_file(&path);
+}
+
+#[test]
+fn migrating_twice_changes_nothing() {
+    let path = v1_database("v1_twice.db");
+    let conn = Connection::open(&path).unwrap();
+    init(&conn).unwrap();
+    init(&conn).unwrap();
+    assert_eq!(schema_version(&conn).unwrap(), SCHEMA_VERSION);
+    assert_eq!(count(&conn, "scores"), 5);
+    let _ = std::fs::remove_file(&path);
+}
+
+#[test]
+fn unversioned_database_with_some_columns_is_upgraded() {
+    // Made between the wave columns and versioning: user_version 0, but
+    // `wave` is already there.
+    let conn = Connection::open_in_memory().unwrap();
+    conn.execute_batch(include_str!("fixtures/shooting_game_v1.sql"))
+        .unwrap();
+    conn.execute_batch(
+        "ALTER TABLE top_scores ADD COLUMN wave INTEGER NOT NULL DEFAULT 0;
+         ALTER TABLE scores ADD COLUMN wave INTEGER NOT NULL DEFAULT 0;",
+    )
+    .unwrap();
+    init(&conn).unwrap();
+    assert_eq!(schema_version(&conn).unwrap(), SCHEMA_VERSION);
+    assert!(columns(&conn, "scores").contains(&"players".to_string()));
+}
+
+#[test]
+fn migrations_start_from_the_stored_version() {
+    let conn = Connection::open_in_memory().unwrap();
+    conn.execute_batch(include_str!("fixtures/shooting_game_v1.sql"))
+        .unwrap();
+    // Marked as version 1, so only the later migrations run.
+    conn.pragma_update(None, "user_version", 1).unwrap();
+    migrate(&conn).unwrap();
+    assert_eq!(schema_version(&conn).unwrap(), SCHEMA_VERSION);
+    assert!(columns(&conn, "top_scores").contains(&"wave".to_string()));
+}
+
+#[test]
+fn newer_database_is_left_alone() {
+    let conn = memory_db();
+    conn.pragma_update(None, "user_version", SCHEMA_VERSION + 1)
+        .unwrap();
+    init(&conn).unwrap();
+    assert_eq!(schema_version(&conn).unwrap(), SCHEMA_VERSION + 1);
+    insert_score(&conn, "me", "hard", 10, 1, None).unwrap();
+}
+
+// ── Statistics ────────────────────────────────────────────────────────────────
+
+fn some_stats() -> RunStats {
+    RunStats {
+        shots_fired: 120,
+        shots_hit: 45,
+        kills: [20, 12, 3],
+        bosses: 1,
+        power_ups: [2, 1, 0, 1, 3],
+        lives_lost: 3,
+        frames: 5400,
+        peak_rate: 850,
+        window_points: 0,
+    }
+}
+
+#[test]
+fn stats_round_trip() {
+    let conn = memory_db();
+    let id = insert_score(&conn, "me", "hard", 4000, 5, None).unwrap();
+    save_stats(&conn, id, &some_stats()).unwrap();
+    assert_eq!(load_stats(&conn, id).unwrap(), Some(some_stats()));
+}
+
+#[test]
+fn game_without_stats_has_none() {
+    let conn = memory_db();
+    let id = insert_score(&conn, "me", "hard", 4000, 5, None).unwrap();
+    assert_eq!(load_stats(&conn, id).unwrap(), None);
+}
+
+#[test]
+fn stats_are_saved_with_a_finished_game() {
+    let conn = memory_db();
+    let finished = GameResult {
+        stats: some_stats(),
+        ..result(700, true)
+    };
+    save_result(&conn, &finished).unwrap();
+    let games = recent_games_page(&conn, "hard", "me", 0, 1).unwrap();
+    assert_eq!(games.total, 1);
+    let id: i64 = conn
+        .query_row("SELECT id FROM scores", [], |row| row.get(0))
+        .unwrap();
+    assert_eq!(load_stats(&conn, id).unwrap(), Some(some_stats()));
+
+    // A game left early has no `scores` row to hang them on.
+    let left = GameResult {
+        stats: some_stats(),
+        ..result(900, false)
+    };
+    save_result(&conn, &left).unwrap();
+    assert_eq!(count(&conn, "score_stats"), 1);
+}
+
+// ── Location ──────────────────────────────────────────────────────────────────
+
+#[test]
+fn memory_is_a_location() {
+    assert_eq!(Location::from_path(MEMORY.into()), Location::Memory);
+    assert_eq!(
+        Location::from_path("scores.db".into()),
+        Location::File("scores.db".into())
+    );
+}
+
+#[test]
+fn default_path_is_in_the_data_dir() {
+    assert_eq!(
+        default_path_in(Some("/home/me/.local/share".into())),
+        std::path::PathBuf::from("/home/me/.local/share/shooting_game/shooting_game.db")
+    );
+    assert_eq!(default_path_in(None), std::path::PathBuf::from(DB_FILE));
+}
+
+#[test]
+fn memory_databases_are_separate() {
+    let a = memory_db();
+    insert_score(&a, "me", "hard", 100, 1, None).unwrap();
+    let b = memory_db();
+    assert_eq!(count(&b, "scores"), 0);
+}
+
+#[test]
+fn open_creates_the_directory() {
+    let dir = temp_path("db_dir");
+    let _ = std::fs::remove_dir_all(&dir);
+    let path = dir.join("deeper").join(DB_FILE);
+    let conn = open(&Location::File(path.clone())).unwrap();
+    insert_score(&conn, "me", "hard", 100, 1, None).unwrap();
+    drop(conn);
+    assert!(path.is_file());
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn legacy_database_is_moved_once() {
+    let dir = temp_path("adopt");
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    let legacy = dir.join(DB_FILE);
+    let target = dir.join("data").join("shooting_game").join(DB_FILE);
+    {
+        let conn = open(&Location::File(legacy.clone())).unwrap();
+        insert_score(&conn, "me", "hard", 4200, 3, None).unwrap();
+    }
+
+    assert!(adopt_legacy(&legacy, &target).unwrap());
+    assert!(!legacy.exists());
+    let conn = open(&Location::File(target.clone())).unwrap();
+    assert_eq!(
+        recent_games_page(&conn, "hard", "me", 0, 5).unwrap().rows[0].points,
+        4200
+    );
+
+    // Nothing left to move.
+    assert!(!adopt_legacy(&legacy, &target).unwrap());
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn legacy_database_does_not_replace_a_newer_one() {
+    let dir = temp_path("adopt_existing");
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    let legacy = dir.join("old.db");
+    let target = dir.join("new.db");
+    std::fs::write(&legacy, "old").unwrap();
+    std::fs::write(&target, "new").unwrap();
+
+    assert!(!adopt_legacy(&legacy, &target).unwrap());
+    assert_eq!(std::fs::read_to_string(&target).unwrap(), "new");
+    assert!(legacy.exists());
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+// ── Errors ────────────────────────────────────────────────────────────────────
+
+#[test]
+fn damaged_file_is_corrupt() {
+    let path = temp_path("garbage.db");
+    std::fs::write(&path, "this is not a database, just some text ".repeat(50)).unwrap();
+    assert_eq!(
+        open(&Location::File(path.clone())).unwrap_err(),
+        DbError::Corrupt
+    );
+    let _ = std::fs::remove_file(&path);
+}
+
+#[test]
+fn locked_file_is_locked() {
+    let path = temp_path("locked.db");
+    let _ = std::fs::remove_file(&path);
+    let holder = open(&Location::File(path.clone())).unwrap();
+    holder.execute_batch("BEGIN EXCLUSIVE").unwrap();
+
+    assert_eq!(
+        open(&Location::File(path.clone())).unwrap_err(),
+        DbError::Locked
+    );
+    holder.execute_batch("COMMIT").unwrap();
+    assert!(open(&Location::File(path.clone())).is_ok());
+    let _ = std::fs::remove_file(&path);
+}
+
+#[test]
+fn unreachable_directory_cannot_be_opened() {
+    // A file where the directory should be.
+    let blocker = temp_path("blocker");
+    std::fs::write(&blocker, "").unwrap();
+    let err = open(&Location::File(blocker.join(DB_FILE))).unwrap_err();
+    assert!(matches!(err, DbError::Open(_)), "{:?}", err);
+    assert!(err.to_string().starts_with("cannot open the database"));
+    let _ = std::fs::remove_file(&blocker);
+}
+
+#[test]
+fn errors_read_plainly() {
+    assert_eq!(
+        DbError::Locked.to_string(),
+        "the database is locked by another program"
+    );
+    assert_eq!(DbError::ReadOnly.to_string(), "the database is read-only");
+    assert_eq!(DbError::Corrupt.to_string(), "the database file is damaged");
+}
+
+// ── Saving with retries ───────────────────────────────────────────────────────
+
+fn result(points: u32, finished: bool) -> GameResult {
+    GameResult {
+        username: "me".to_string(),
+        difficulty: "hard".to_string(),
+        points,
+        wave: 2,
+        p2_points: None,
+        finished,
+        stats: RunStats::default(),
+    }
+}
+
+#[test]
+fn result_of_an_unfinished_game_only_counts_as_a_best() {
+    let conn = memory_db();
+    save_result(&conn, &result(300, false)).unwrap();
+    assert_eq!(count(&conn, "scores"), 0);
+    assert_eq!(load_top_score(&conn, "hard").unwrap(), 300);
+    save_result(&conn, &result(200, true)).unwrap();
+    assert_eq!(count(&conn, "scores"), 1);
+    assert_eq!(load_top_score(&conn, "hard").unwrap(), 300);
+}
+
+#[test]
+fn store_saves_straight_away() {
+    let mut store = ScoreStore::open(Location::Memory);
+    assert!(store.error().is_none());
+    store.save(result(500, true));
+    assert!(store.pending().is_empty());
+    let best = store.read(|c| load_top_score(c, "hard"));
+    assert_eq!(best, Some(500));
+}
+
+#[test]
+fn store_opens_the_database_once_it_can() {
+    let dir = temp_path("store_later");
+    let _ = std::fs::remove_dir_all(&dir);
+    // The directory is blocked by a file until the game is over.
+    std::fs::write(&dir, "").unwrap();
+    let mut store = ScoreStore::open(Location::File(dir.join(DB_FILE)));
+    assert!(matches!(store.error(), Some(DbError::Open(_))));
+    assert!(store.conn().is_none());
+    assert_eq!(store.read(|c| load_top_score(c, "hard")), None);
+
+    store.save(result(100, true));
+    store.save(result(200, true));
+    assert_eq!(store.pending().len(), 2);
+    store.retry();
+    assert_eq!(store.pending().len(), 2);
+
+    std::fs::remove_file(&dir).unwrap();
+    store.retry();
+    assert!(store.pending().is_empty());
+    assert!(store.error().is_none());
+    let games = recent_games_page(store.conn().unwrap(), "hard", "me", 0, 5).unwrap();
+    let points: Vec<_> = games.rows.iter().map(|g| g.points).collect();
+    assert_eq!(points, [200, 100]);
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn store_queues_saves_while_locked() {
+    let path = temp_path("store_locked.db");
+    let _ = std::fs::remove_file(&path);
+    let mut store = ScoreStore::open(Location::File(path.clone()));
+    let holder = open(&Location::File(path.clone())).unwrap();
+    holder.execute_batch("BEGIN EXCLUSIVE").unwrap();
+
+    store.save(result(700, true));
+    assert_eq!(store.error(), Some(&DbError::Locked));
+    assert_eq!(store.pending(), [result(700, true)]);
+
+    holder.execute_batch("COMMIT").unwrap();
+    store.retry();
+    assert!(store.pending().is_empty());
+    assert!(store.error().is_none());
+    // Written exactly once.
+    assert_eq!(count(&holder, "scores"), 1);
+    assert_eq!(load_top_score(&holder, "hard").unwrap(), 700);
+    let _ = std::fs::remove_file(&path);
+}
@@ -1,106 +1,21 @@
-use shooting_game::display::render;
+use crossterm::event::KeyCode;
+use crossterm::style::Color;
+use std::path::Path;
+
+use shooting_game::bindings::{Action, Bindings};
+use shooting_game::buffer::Buffer;
+use shooting_game::display::{draw, draw_too_small, render};
 use shooting_game::entities::{
-    Bullet, BulletOwner, Enemy, EnemyKind, EntireGameStateInfo, GameStatus, Level, Player,
+    BonusItem, BonusKind, Boss, BossPattern, Bullet, BulletOwner, Difficulty, Enemy, EnemyBehavior,
+    EnemyKind, EntireGameStateInfo, GameStatus, Level, PauseItem, Player, Player2, PlayerId,
+    RunStats, WaveState,
 };
-
-// ── Virtual terminal emulator ─────────────────────────────────────────────────
-//
-// Parses the raw escape-sequence bytes that crossterm writes into a `Vec<u8>`
-// and maintains a 2-D character grid — just like a real terminal would.
-// Supported sequences (the only ones render() uses):
-//   ESC [ <row> ; <col> H  — MoveTo (1-indexed)
-//   ESC [ 2 J              — Clear All
-//   ESC [ … m              — color/reset (ignored; we only check content)
-
-struct VirtualScreen {
-    grid: Vec<Vec<char>>,
-    col: usize,
-    row: usize,
-    width: usize,
-    height: usize,
-}
-
-impl VirtualScreen {
-    fn new(width: usize, height: usize) -> Self {
-        Self {
-            grid: vec![vec![' '; width]; height],
-            col: 0,
-            row: 0,
-            width,
-            height,
-        }
-    }
-
-    /// Apply one frame's worth of escape-sequence output to the screen.
-    fn apply(&mut self, buf: &[u8]) {
-        let s = std::str::from_utf8(buf).expect("crossterm output must be valid UTF-8");
-        let bytes = s.as_bytes();
-        let mut i = 0;
-        while i < bytes.len() {
-            if bytes[i] == 0x1b && i + 1 < bytes.len() && bytes[i + 1] == b'[' {
-                // ESC[ — find the final byte (letter)
-                let start = i + 2;
-                let mut j = start;
-                while j < bytes.len()
-                    && (bytes[j].is_ascii_digit() || bytes[j] == b';' || bytes[j] == b'?')
-                {
-                    j += 1;
-                }
-                if j < bytes.len() {
-                    let params = &s[start..j];
-                    match bytes[j] {
-                        b'H' => {
-                            // Cursor position: ESC[row;colH (1-indexed)
-                            let mut parts =
-                                params.split(';').filter_map(|p| p.parse::<usize>().ok());
-                            self.row = parts
-                                .next()
-                                .unwrap_or(1)
-                                .saturating_sub(1)
-                                .min(self.height.saturating_sub(1));
-                            self.col = parts
-                                .next()
-                                .unwrap_or(1)
-                                .saturating_sub(1)
-                                .min(self.width.saturating_sub(1));
-                        }
-                        b'J' if params == "2" => {
-                            for r in self.grid.iter_mut() {
-                                r.fill(' ');
-                            }
-                        }
-                        _ => {} // colors etc. — not needed for content checks
-                    }
-                    i = j + 1;
-                } else {
-                    i += 1;
-                }
-            } else {
-                let ch = s[i..].chars().next().unwrap();
-                if !ch.is_control() {
-                    if self.row < self.height && self.col < self.width {
-                        self.grid[self.row][self.col] = ch;
-                    }
-                    self.col += 1;
-                }
-                i += ch.len_utf8();
-            }
-        }
-    }
-
-    fn char_at(&self, col: usize, row: usize) -> char {
-        self.grid[row][col]
-    }
-
-    fn row_str(&self, row: usize) -> String {
-        self.grid[row].iter().collect()
-    }
-}
+use shooting_game::renderer::TextRenderer;
 
 // ── Helpers ───────────────────────────────────────────────────────────────────
 
-const W: usize = 80;
-const H: usize = 20;
+const W: u16 = 80;
+const H: u16 = 20;
 
 fn make_state(width: u16, height: u16) -> EntireGameStateInfo {
     EntireGameStateInfo {
@@ -108,9 +23,13 @@ fn make_state(width: u16, height: u16) -> EntireGameStateInfo {
             x: (width / 2) as i32,
             y: (height - 4) as i32,
             lives: 3,
+            invulnerable: 0,
         },
+        player2: None,
         enemies: vec![],
         bullets: vec![],
+        enemy_shots: vec![],
+        boss: None,
         flame_bullets: vec![],
         firebombs: vec![],
         explosions: vec![],
@@ -119,6 +38,13 @@ fn make_state(width: u16, height: u16) -> EntireGameStateInfo {
         score: 0,
         high_score: 0,
         level: Level::Easy,
+        difficulty: Difficulty::preset(&Level::Easy),
+        wave: WaveState {
+            number: 1,
+            remaining: 6,
+            breather: 0,
+            boss_due: false,
+        },
         status: GameStatus::Playing,
         frame: 0,
         width,
@@ -128,35 +54,51 @@ fn make_state(width: u16, height: u16) -> EntireGameStateInfo {
         slow_mo: false,
         muzzle_flash: 0,
         cheer_msg: None,
+        events: Vec::new(),
+        stats: RunStats::default(),
     }
 }
 
-fn one_frame(state: &EntireGameStateInfo, full_redraw: bool) -> VirtualScreen {
-    let mut buf = Vec::new();
-    render(&mut buf, state, full_redraw).expect("render failed");
-    let mut screen = VirtualScreen::new(state.width as usize, state.height as usize);
-    screen.apply(&buf);
-    screen
+fn one_frame(state: &EntireGameStateInfo) -> Buffer {
+    draw(state, &Bindings::default())
 }
 
-/// Simulate two consecutive frames with accumulated screen state.
-fn two_frames(state1: &EntireGameStateInfo, state2: &EntireGameStateInfo) -> VirtualScreen {
-    let mut screen = VirtualScreen::new(state1.width as usize, state1.height as usize);
-    let mut buf = Vec::new();
-    render(&mut buf, state1, true).unwrap();
-    screen.apply(&buf);
-    buf.clear();
-    render(&mut buf, state2, false).unwrap();
-    screen.apply(&buf);
-    screen
+/// A frame as the plain-text backend writes it.
+fn text_frame(state: &EntireGameStateInfo) -> String {
+    let mut text = TextRenderer::new(Vec::new());
+    render(&mut text, state, &Bindings::default(), true).unwrap();
+    String::from_utf8(text.into_inner()).unwrap()
+}
+
+/// Compare `actual` with the golden file `tests/snapshots/<name>.txt`.  Run
+/// with `UPDATE_SNAPSHOTS=1` to rewrite the file after an intended change.
+fn assert_snapshot(name: &str, actual: &str) {
+    let path = Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/snapshots")
+        .join(format!("{name}.txt"));
+    if std::env::var_os("UPDATE_SNAPSHOTS").is_some() {
+        std::fs::write(&path, actual).unwrap();
+        return;
+    }
+    let expected = std::fs::read_to_string(&path).unwrap_or_else(|_| {
+        panic!(
+            "missing snapshot {}; run with UPDATE_SNAPSHOTS=1",
+            path.display()
+        )
+    });
+    assert!(
+        actual == expected,
+        "frame differs from {}:\n{actual}",
+        path.display()
+    );
 }
 
 // ── Border tests ──────────────────────────────────────────────────────────────
 
 #[test]
 fn first_frame_draws_top_border() {
-    let state = make_state(W as u16, H as u16);
-    let screen = one_frame(&state, true);
+    let state = make_state(W, H);
+    let screen = one_frame(&state);
     assert_eq!(screen.char_at(0, 1), '┌', "top-left corner missing");
     assert_eq!(screen.char_at(W - 1, 1), '┐', "top-right corner missing");
     assert_eq!(screen.char_at(W / 2, 1), '─', "top border bar missing");
@@ -164,8 +106,8 @@ fn first_frame_draws_top_border() {
 
 #[test]
 fn first_frame_draws_bottom_border() {
-    let state = make_state(W as u16, H as u16);
-    let screen = one_frame(&state, true);
+    let state = make_state(W, H);
+    let screen = one_frame(&state);
     assert_eq!(screen.char_at(0, H - 2), '└', "bottom-left corner missing");
     assert_eq!(
         screen.char_at(W - 1, H - 2),
@@ -176,8 +118,8 @@ fn first_frame_draws_bottom_border() {
 
 #[test]
 fn first_frame_draws_side_walls() {
-    let state = make_state(W as u16, H as u16);
-    let screen = one_frame(&state, true);
+    let state = make_state(W, H);
+    let screen = one_frame(&state);
     for row in 2..H - 2 {
         assert_eq!(
             screen.char_at(0, row),
@@ -194,8 +136,8 @@ fn first_frame_draws_side_walls() {
 
 #[test]
 fn first_frame_draws_controls_hint() {
-    let state = make_state(W as u16, H as u16);
-    let screen = one_frame(&state, true);
+    let state = make_state(W, H);
+    let screen = one_frame(&state);
     let hint_row = screen.row_str(H - 1);
     assert!(hint_row.contains('Q'), "controls hint missing Q");
     assert!(hint_row.contains("Move"), "controls hint missing Move");
@@ -205,9 +147,9 @@ fn first_frame_draws_controls_hint() {
 
 #[test]
 fn hud_shows_score_label() {
-    let mut state = make_state(W as u16, H as u16);
+    let mut state = make_state(W, H);
     state.score = 42;
-    let screen = one_frame(&state, true);
+    let screen = one_frame(&state);
     assert!(
         screen.row_str(0).contains("Score"),
         "HUD missing Score label"
@@ -216,28 +158,214 @@ fn hud_shows_score_label() {
 
 #[test]
 fn hud_shows_lives_hearts() {
-    let state = make_state(W as u16, H as u16); // 3 lives
-    let screen = one_frame(&state, true);
+    let state = make_state(W, H); // 3 lives
+    let screen = one_frame(&state);
     assert!(screen.row_str(0).contains('♥'), "HUD missing ♥ heart");
 }
 
 #[test]
 fn hud_shows_hi_score_when_nonzero() {
     // Use a wide screen so the score label and the centred level label don't overlap.
-    let mut state = make_state(80, H as u16);
+    let mut state = make_state(80, H);
     state.score = 10;
     state.high_score = 100;
-    let screen = one_frame(&state, true);
+    let screen = one_frame(&state);
     assert!(screen.row_str(0).contains("Hi"), "HUD missing Hi: label");
 }
 
+#[test]
+fn hud_shows_wave_number() {
+    let mut state = make_state(80, H);
+    state.wave.number = 7;
+    let screen = one_frame(&state);
+    assert!(screen.row_str(0).contains("W7"), "HUD missing wave number");
+}
+
+#[test]
+fn hud_shortens_level_label_instead_of_overlapping() {
+    let mut state = make_state(80, H);
+    state.level = Level::Medium;
+    state.wave.number = 3;
+    state.high_score = 3000;
+    assert!(one_frame(&state).row_str(0).contains("[ MEDIUM · W3 ]"));
+
+    state.active_power_up = Some((BonusKind::RapidFire, 200));
+    let row = one_frame(&state).row_str(0);
+    assert!(row.contains("[ W3 ]"), "{row}");
+    assert!(row.contains("[! RAPID   7s]"), "{row}");
+}
+
+#[test]
+fn armored_enemy_sprite() {
+    let mut state = make_state(W, H);
+    state.enemies.push(Enemy {
+        x: 10,
+        y: 5,
+        kind: EnemyKind::Armored,
+        hp: 2,
+        behavior: EnemyBehavior::Straight,
+    });
+    let screen = one_frame(&state);
+    assert!(screen.row_str(5).contains("[▼]"));
+    assert!(screen.row_str(6).contains("╚█╝"));
+}
+
+#[test]
+fn boss_sprite_and_health_bar() {
+    let mut state = make_state(80, H);
+    state.boss = Some(Boss {
+        x: 40,
+        y: 3,
+        hp: 10,
+        max_hp: 20,
+        dir: 1,
+        pattern: BossPattern::Aimed,
+        pattern_frame: 0,
+    });
+    let screen = one_frame(&state);
+    assert!(screen.row_str(3).contains("╔═▼═▼═╗"));
+    assert!(screen.row_str(4).contains("║(◉◉◉)║"));
+    assert!(screen.row_str(5).contains("╚╦═╩═╦╝"));
+    assert!(
+        screen.row_str(0).contains("BOSS [█████░░░░░]"),
+        "half-health bar expected"
+    );
+}
+
+#[test]
+fn invulnerable_player_blinks() {
+    let mut state = make_state(W, H);
+    let (x, y) = (state.player.x as u16, state.player.y as u16);
+    state.player.invulnerable = 4; // hidden phase
+    assert_eq!(one_frame(&state).char_at(x, y), ' ');
+    state.player.invulnerable = 3; // shown phase
+    assert_eq!(one_frame(&state).char_at(x, y), '▲');
+}
+
+#[test]
+fn coop_hud_shows_both_players() {
+    let mut state = make_state(80, H);
+    state.player2 = Some(Player2 {
+        ship: Player {
+            x: 50,
+            lives: 2,
+            ..state.player.clone()
+        },
+        active_power_up: Some((BonusKind::SpreadShot, 90)),
+        score: 0,
+    });
+    let screen = one_frame(&state);
+    assert!(screen.row_str(0).ends_with("P1 ♥♥♥  P2 [★4s] ♥♥ "));
+    let y = state.player.y as u16;
+    assert_eq!(screen.char_at(state.player.x as u16, y), '▲');
+    assert_eq!(screen.char_at(50, y), '▲');
+    assert_eq!(screen.cell(50, y).unwrap().fg, Color::Cyan);
+}
+
+#[test]
+fn coop_player_who_is_out_leaves_the_field() {
+    let mut state = make_state(80, H);
+    state.player2 = Some(Player2 {
+        ship: Player {
+            x: 50,
+            ..state.player.clone()
+        },
+        active_power_up: None,
+        score: 0,
+    });
+    state.player.lives = 0;
+    let screen = one_frame(&state);
+    assert_eq!(
+        screen.char_at(state.player.x as u16, state.player.y as u16),
+        ' '
+    );
+    assert!(screen.row_str(0).contains("P1 OUT"));
+}
+
+#[test]
+fn coop_game_over_splits_the_score() {
+    let mut state = make_state(80, H);
+    state.player2 = Some(Player2 {
+        ship: Player {
+            lives: 0,
+            ..state.player.clone()
+        },
+        active_power_up: None,
+        score: 400,
+    });
+    state.player.lives = 0;
+    state.score = 1000;
+    state.status = GameStatus::GameOver;
+    let text = text_frame(&state);
+    assert!(text.contains("P1 600  ·  P2 400"), "{text}");
+}
+
 // ── Game-over overlay test ────────────────────────────────────────────────────
 
+fn run_stats() -> RunStats {
+    RunStats {
+        shots_fired: 120,
+        shots_hit: 45,
+        kills: [20, 12, 3],
+        bosses: 1,
+        power_ups: [2, 1, 0, 1, 3],
+        lives_lost: 3,
+        frames: 5430,
+        peak_rate: 850,
+        window_points: 0,
+    }
+}
+
+#[test]
+fn game_over_shows_the_run_stats() {
+    let mut state = make_state(W, H);
+    state.status = GameStatus::GameOver;
+    state.wave.number = 4;
+    state.stats = run_stats();
+    let text = text_frame(&state);
+    for line in [
+        "Wave 4 · 3:01 survived",
+        "Shots 120 · Hits 45 (37%)",
+        "«▼» 20  (◎) 12  [▼] 3  Boss 1",
+        "★ 2  ♥ 1  ! 0  ~ 1  o 3",
+        "Lives lost 3 · Peak 850 pts/10s",
+    ] {
+        assert!(text.contains(line), "missing {line:?} in\n{text}");
+    }
+}
+
+#[test]
+fn game_over_without_shots_has_no_accuracy() {
+    let mut state = make_state(W, H);
+    state.status = GameStatus::GameOver;
+    assert!(text_frame(&state).contains("Shots 0 · Hits 0 (–)"));
+}
+
+#[test]
+fn coop_game_over_fits_the_smallest_terminal() {
+    let mut state = make_state(40, 16);
+    state.player2 = Some(Player2 {
+        ship: state.player.clone(),
+        active_power_up: None,
+        score: 400,
+    });
+    state.status = GameStatus::GameOver;
+    state.stats = RunStats {
+        shots_fired: 12345,
+        shots_hit: 6789,
+        ..run_stats()
+    };
+    let text = text_frame(&state);
+    assert!(text.contains("GAME  OVER"), "{text}");
+    assert!(text.contains("Shots 12345 · Hits 6789 (54%)"), "{text}");
+    assert!(text.contains("R - Play Again  Q - Quit"), "{text}");
+}
+
 #[test]
 fn game_over_overlay_appears() {
-    let mut state = make_state(W as u16, H as u16);
+    let mut state = make_state(W, H);
     state.status = GameStatus::GameOver;
-    let screen = one_frame(&state, true);
+    let screen = one_frame(&state);
     let all: String = (0..H)
         .map(|r| screen.row_str(r))
         .collect::<Vec<_>>()
@@ -246,63 +374,209 @@ fn game_over_overlay_appears() {
     assert!(all.contains("OVER"), "game-over overlay missing OVER");
 }
 
-// ── Ghost / subsequent-frame tests ────────────────────────────────────────────
+#[test]
+fn hints_follow_rebound_keys() {
+    let mut bindings = Bindings::default();
+    bindings.rebind(Action::Shoot, KeyCode::Char('k'));
+    bindings.rebind(Action::Pause, KeyCode::Tab);
+    bindings.rebind(Action::Restart, KeyCode::Enter);
+    let all = |state: &EntireGameStateInfo| {
+        let screen = draw(state, &bindings);
+        (0..state.height)
+            .map(|r| screen.row_str(r))
+            .collect::<Vec<_>>()
+            .join("\n")
+    };
+
+    let mut state = make_state(W, H);
+    let playing = all(&state);
+    assert!(playing.contains("K Shoot  TAB Pause"), "{playing}");
+
+    state.status = GameStatus::Paused(PauseItem::Resume);
+    let paused = all(&state);
+    assert!(paused.contains("TAB Resume"), "{paused}");
+
+    state.status = GameStatus::GameOver;
+    let over = all(&state);
+    assert!(over.contains("ENTER - Play Again  Q - Quit"), "{over}");
+}
 
 #[test]
-fn subsequent_frame_walls_redrawn() {
-    let state = make_state(W as u16, H as u16);
-    let screen = two_frames(&state, &state);
-    for row in 2..H - 2 {
-        assert_eq!(
-            screen.char_at(0, row),
-            '│',
-            "left wall missing at row {row}"
-        );
-        assert_eq!(
-            screen.char_at(W - 1, row),
-            '│',
-            "right wall missing at row {row}"
-        );
-    }
+fn game_over_box_hides_what_is_behind_it() {
+    let mut state = make_state(W, H);
+    state.status = GameStatus::GameOver;
+    // Straddles the box's right edge on its top two rows.
+    state.enemies.push(Enemy {
+        x: 51,
+        y: 5,
+        kind: EnemyKind::Armored,
+        hp: 2,
+        behavior: EnemyBehavior::Straight,
+    });
+    let screen = one_frame(&state);
+    assert_eq!(screen.char_at(50, 5), '╗');
+    assert_eq!(screen.char_at(51, 5), ' ', "{}", screen.row_str(5));
+    assert_eq!(screen.char_at(51, 6), ' ', "{}", screen.row_str(6));
 }
 
 #[test]
-fn no_ghost_at_col0_after_enemy_moves() {
-    // Enemy at x=1 renders starting at col 0 (lx = max(0, x-1) = 0).
-    // After the enemy disappears, the subsequent-frame clear must erase col 0.
-    const ENEMY_ROW: i32 = 5;
-    let mut state1 = make_state(W as u16, H as u16);
-    state1.enemies = vec![Enemy {
-        x: 1,
-        y: ENEMY_ROW,
-        kind: EnemyKind::Spacecraft,
-    }];
-    let state2 = make_state(W as u16, H as u16); // no enemies
-    let screen = two_frames(&state1, &state2);
-    // Col 0 must show the restored wall, not the enemy sprite.
+fn game_over_stats_hide_what_is_behind_them() {
+    let mut state = make_state(W, H);
+    state.status = GameStatus::GameOver;
+    state.stats = run_stats();
+    // Left of the box but inside the widest stats line, on the first stats row.
+    state.bullets.push(Bullet {
+        x: 26,
+        y: 10,
+        owner: BulletOwner::Enemy,
+    });
+    let screen = one_frame(&state);
+    assert!(screen.row_str(10).contains("survived"));
+    assert_eq!(screen.char_at(26, 10), ' ', "{}", screen.row_str(10));
+}
+
+#[test]
+fn pause_overlay_lists_menu() {
+    let mut state = make_state(W, H);
+    state.status = GameStatus::Paused(PauseItem::Restart);
+    let screen = one_frame(&state);
+    let all: String = (0..H)
+        .map(|r| screen.row_str(r))
+        .collect::<Vec<_>>()
+        .join("\n");
+    assert!(all.contains("PAUSED"), "pause overlay missing title");
+    assert!(all.contains("▶ Restart"), "selected item not marked");
+    assert!(all.contains("Quit to menu"), "pause overlay missing item");
+}
+
+// ── Resize tests ──────────────────────────────────────────────────────────────
+
+#[test]
+fn full_redraw_after_resize_uses_new_size() {
+    let state = make_state(W, H);
+    let resized = shooting_game::compute::resize(&state, W + 10, H + 4);
+    let screen = one_frame(&resized);
+    let (w, h) = (W + 10, H + 4);
+    assert_eq!(
+        screen.char_at(w - 1, 1),
+        '┐',
+        "top-right corner not at new width"
+    );
     assert_eq!(
-        screen.char_at(0, ENEMY_ROW as usize),
-        '│',
-        "ghost sprite at col 0 after enemy moved away"
+        screen.char_at(0, h - 2),
+        '└',
+        "bottom border not at new height"
     );
 }
 
 #[test]
-fn no_ghost_at_right_edge_after_bullet_moves() {
-    // A bullet at x = w-1 renders at the right border column.
-    // After it moves, col w-1 must be restored to the wall glyph.
-    const BULLET_ROW: i32 = 8;
-    let mut state1 = make_state(W as u16, H as u16);
-    state1.bullets = vec![Bullet {
-        x: W as i32 - 1,
-        y: BULLET_ROW,
-        owner: BulletOwner::Enemy,
-    }];
-    let state2 = make_state(W as u16, H as u16); // no bullets
-    let screen = two_frames(&state1, &state2);
+fn too_small_screen_explains_itself() {
+    let screen = draw_too_small(30, 10);
+    let all: String = (0..10)
+        .map(|r| screen.row_str(r))
+        .collect::<Vec<_>>()
+        .join("\n");
+    assert!(all.contains("Terminal too small"), "{all}");
+    assert!(all.contains("30×10, need 40×16"), "{all}");
+}
+
+// ── Cell colours ──────────────────────────────────────────────────────────────
+
+#[test]
+fn cells_carry_their_colour() {
+    let mut state = make_state(W, H);
+    state.enemies.push(Enemy {
+        x: 10,
+        y: 5,
+        kind: EnemyKind::Spacecraft,
+        hp: 1,
+        behavior: EnemyBehavior::Straight,
+    });
+    let grid = one_frame(&state);
+    assert_eq!(grid.cell(10, 5).unwrap().ch, '▼');
+    assert_eq!(grid.cell(10, 5).unwrap().fg, Color::Green);
     assert_eq!(
-        screen.char_at(W - 1, BULLET_ROW as usize),
-        '│',
-        "ghost sprite at col w-1 after bullet moved away"
+        grid.cell(0, 1).unwrap().fg,
+        Color::DarkBlue,
+        "border colour"
     );
+    assert_eq!(grid.cell(0, 5).unwrap().bg, Color::Reset);
+}
+
+// ── Golden snapshots ──────────────────────────────────────────────────────────
+
+/// A mid-game field: HUD with hi-score, power-up and bullet slots, a few
+/// enemies, shots and a falling bonus.
+fn busy_state() -> EntireGameStateInfo {
+    let mut state = make_state(W, H);
+    state.level = Level::Medium;
+    state.difficulty = Difficulty::preset(&Level::Medium);
+    state.wave.number = 3;
+    state.score = 1250;
+    state.high_score = 3000;
+    state.player.lives = 2;
+    state.active_power_up = Some((BonusKind::RapidFire, 200));
+    state.enemies = vec![
+        Enemy {
+            x: 12,
+            y: 4,
+            kind: EnemyKind::Spacecraft,
+            hp: 1,
+            behavior: EnemyBehavior::Straight,
+        },
+        Enemy {
+            x: 50,
+            y: 7,
+            kind: EnemyKind::Armored,
+            hp: 2,
+            behavior: EnemyBehavior::Straight,
+        },
+    ];
+    state.bullets = vec![
+        Bullet {
+            x: 40,
+            y: 12,
+            owner: BulletOwner::Player(PlayerId::One),
+        },
+        Bullet {
+            x: 12,
+            y: 7,
+            owner: BulletOwner::Enemy,
+        },
+    ];
+    state.bonus_items = vec![BonusItem {
+        x: 60,
+        y: 10,
+        kind: BonusKind::SpreadShot,
+    }];
+    state
+}
+
+#[test]
+fn snapshot_playfield() {
+    assert_snapshot("playfield", &text_frame(&busy_state()));
+}
+
+#[test]
+fn snapshot_game_over() {
+    let mut state = busy_state();
+    state.status = GameStatus::GameOver;
+    state.score = 4200;
+    state.stats = run_stats();
+    assert_snapshot("game_over", &text_frame(&state));
+}
+
+#[test]
+fn snapshot_debug_overlay() {
+    let mut state = busy_state();
+    state.debug_mode = true;
+    state.frame = 321;
+    assert_snapshot("debug_overlay", &text_frame(&state));
+}
+
+#[test]
+fn snapshot_pause_menu() {
+    let mut state = busy_state();
+    state.status = GameStatus::Paused(PauseItem::Settings);
+    assert_snapshot("pause_menu", &text_frame(&state));
 }
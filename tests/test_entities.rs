@@ -9,14 +9,35 @@ fn entity_clone_and_eq() {
     assert_ne!(Level::Easy, Level::Hard);
     assert_eq!(GameStatus::Playing, GameStatus::Playing);
     assert_ne!(GameStatus::Playing, GameStatus::GameOver);
-    assert_eq!(BulletOwner::Player, BulletOwner::Player);
-    assert_ne!(BulletOwner::Player, BulletOwner::Enemy);
+    assert_eq!(
+        BulletOwner::Player(PlayerId::One),
+        BulletOwner::Player(PlayerId::One)
+    );
+    assert_ne!(BulletOwner::Player(PlayerId::One), BulletOwner::Enemy);
 
     // Clone must produce an equal value
     let kind = EnemyKind::Octopus;
     assert_eq!(kind.clone(), EnemyKind::Octopus);
 }
 
+#[test]
+fn armored_enemies_take_several_hits() {
+    assert_eq!(EnemyKind::Spacecraft.max_hp(), 1);
+    assert_eq!(EnemyKind::Octopus.max_hp(), 1);
+    assert!(EnemyKind::Armored.max_hp() > 1);
+}
+
+#[test]
+fn octopus_fires_spreads_less_often() {
+    let octopus = EnemyKind::Octopus.fire_profile();
+    assert_eq!(octopus.pattern, FirePattern::Spread);
+    assert!(octopus.rarity > EnemyKind::Spacecraft.fire_profile().rarity);
+    assert_eq!(
+        EnemyKind::Armored.fire_profile().pattern,
+        FirePattern::Aimed
+    );
+}
+
 #[test]
 fn game_state_clone_is_independent() {
     let original = EntireGameStateInfo {
@@ -24,9 +45,13 @@ fn game_state_clone_is_independent() {
             x: 20,
             y: 16,
             lives: 3,
+            invulnerable: 0,
         },
+        player2: None,
         enemies: Vec::new(),
         bullets: Vec::new(),
+        enemy_shots: Vec::new(),
+        boss: None,
         flame_bullets: Vec::new(),
         firebombs: Vec::new(),
         explosions: Vec::new(),
@@ -35,6 +60,13 @@ fn game_state_clone_is_independent() {
         score: 0,
         high_score: 0,
         level: Level::Easy,
+        difficulty: Difficulty::preset(&Level::Easy),
+        wave: WaveState {
+            number: 1,
+            remaining: 6,
+            breather: 0,
+            boss_due: false,
+        },
         status: GameStatus::Playing,
         frame: 0,
         width: 40,
@@ -44,6 +76,8 @@ fn game_state_clone_is_independent() {
         slow_mo: false,
         muzzle_flash: 0,
         cheer_msg: None,
+        events: Vec::new(),
+        stats: RunStats::default(),
     };
     let mut cloned = original.clone();
 
@@ -54,9 +88,30 @@ fn game_state_clone_is_independent() {
         x: 5,
         y: 5,
         kind: EnemyKind::Spacecraft,
+        hp: 1,
+        behavior: EnemyBehavior::Straight,
     });
 
     assert_eq!(original.player.x, 20);
     assert_eq!(original.score, 0);
     assert!(original.enemies.is_empty());
 }
+
+#[test]
+fn kinds_index_their_tallies() {
+    for (i, kind) in EnemyKind::ALL.iter().enumerate() {
+        assert_eq!(kind.index(), i);
+    }
+    for (i, kind) in BonusKind::ALL.iter().enumerate() {
+        assert_eq!(kind.index(), i);
+    }
+}
+
+#[test]
+fn accuracy_is_a_rounded_down_percentage() {
+    let mut stats = RunStats::default();
+    assert_eq!(stats.accuracy(), None);
+    stats.shots_fired = 120;
+    stats.shots_hit = 45;
+    assert_eq!(stats.accuracy(), Some(37));
+}
@@ -1,7 +1,15 @@
 use std::collections::HashMap;
 
-use crossterm::event::KeyCode;
-use shooting_game::input_keyboard::{KeyState, GRACE_PERIOD, HOLD_WINDOW};
+use crossterm::event::{
+    KeyCode, KeyEvent, KeyEventKind, KeyModifiers, MouseButton, MouseEvent, MouseEventKind,
+};
+use shooting_game::bindings::{parse_bindings, Action, Bindings};
+use shooting_game::compute::{init_state_with, pause, with_player_two};
+use shooting_game::entities::{Difficulty, EntireGameStateInfo, GameStatus, Level, PlayerAction};
+use shooting_game::input_keyboard::{
+    ActionKeys, HeldDir, InputController, Intent, KeyState, AUTOFIRE_INTERVAL, GRACE_PERIOD,
+    HOLD_WINDOW, MOUSE_MAX_SPEED, WARP_COOLDOWN,
+};
 
 fn press(keys: &mut HashMap<KeyCode, KeyState>, key: KeyCode, frame: u64) {
     keys.insert(key, KeyState::Held(frame));
@@ -141,3 +149,446 @@ fn unrelated_key_does_not_affect_query() {
     press(&mut keys, KeyCode::Right, 10);
     assert!(!is_held(&keys, &KeyCode::Left, 10));
 }
+
+// ── Per-action state ──────────────────────────────────────────────────────────
+
+#[test]
+fn aliases_share_one_action_state() {
+    let bindings = Bindings::default();
+    let mut keys = ActionKeys::new();
+    // Held with A, released with Left: one action, so the release wins.
+    for action in bindings.actions_for(KeyCode::Char('a')) {
+        keys.press(action, 10);
+    }
+    assert!(keys.is_held(Action::MoveLeft, 10));
+    for action in bindings.actions_for(KeyCode::Left) {
+        keys.release(action, 11);
+    }
+    assert!(!keys.is_held(Action::MoveLeft, 11 + GRACE_PERIOD + 1));
+}
+
+#[test]
+fn actions_are_tracked_independently() {
+    let mut keys = ActionKeys::new();
+    keys.press(Action::MoveRight, 10);
+    keys.press(Action::Fast, 10);
+    keys.release(Action::Fast, 11);
+    assert!(keys.is_held(Action::MoveRight, 10 + HOLD_WINDOW));
+    assert!(!keys.is_held(Action::Fast, 11 + GRACE_PERIOD + 1));
+    assert!(!keys.is_held(Action::MoveLeft, 10));
+}
+
+#[test]
+fn action_state_follows_key_state_rules() {
+    let mut keys = ActionKeys::new();
+    keys.press(Action::Warp, 1);
+    assert_eq!(keys.get(Action::Warp), Some(KeyState::Held(1)));
+    assert!(!keys.is_held(Action::Warp, 1 + HOLD_WINDOW + 1));
+}
+
+// ── Input controller: scripted event sequences ───────────────────────────────
+
+use KeyEventKind::{Press, Release, Repeat};
+
+fn playing() -> EntireGameStateInfo {
+    init_state_with(Level::Medium, Difficulty::preset(&Level::Medium), 80, 24, 0)
+}
+
+/// Feed `script` (frame, key, kind) to a fresh controller frame by frame up
+/// to `frames`, with the state fixed, and collect every player action each
+/// frame produced — from presses first, then from held keys.
+fn run_script(
+    input: &mut InputController,
+    state: &EntireGameStateInfo,
+    script: &[(u64, KeyCode, KeyEventKind)],
+    frames: u64,
+) -> Vec<Vec<PlayerAction>> {
+    let mut per_frame = vec![Vec::new(); frames as usize + 1];
+    for frame in 1..=frames {
+        for &(_, code, kind) in script.iter().filter(|(f, ..)| *f == frame) {
+            let event = KeyEvent::new_with_kind(code, KeyModifiers::NONE, kind);
+            for intent in input.key_event(frame, event, state) {
+                if let Intent::Act(action) = intent {
+                    per_frame[frame as usize].push(action);
+                }
+            }
+        }
+        per_frame[frame as usize].extend(input.end_frame(frame, state));
+    }
+    per_frame
+}
+
+fn script(events: &[(u64, KeyCode, KeyEventKind)], frames: u64) -> Vec<Vec<PlayerAction>> {
+    run_script(
+        &mut InputController::new(Bindings::default()),
+        &playing(),
+        events,
+        frames,
+    )
+}
+
+fn press_once(
+    input: &mut InputController,
+    state: &EntireGameStateInfo,
+    code: KeyCode,
+) -> Vec<Intent> {
+    input.key_event(1, KeyEvent::new(code, KeyModifiers::NONE), state)
+}
+
+/// Frames 1..=n in which `action` happened.
+fn frames_with(per_frame: &[Vec<PlayerAction>], action: &PlayerAction) -> Vec<u64> {
+    (0..per_frame.len())
+        .filter(|&f| per_frame[f].contains(action))
+        .map(|f| f as u64)
+        .collect()
+}
+
+/// Left pressed at frame 1 and auto-repeated every frame up to `until`.
+fn hold_left(until: u64) -> Vec<(u64, KeyCode, KeyEventKind)> {
+    let mut events = vec![(1, KeyCode::Left, Press)];
+    events.extend((2..=until).map(|f| (f, KeyCode::Left, Repeat)));
+    events
+}
+
+#[test]
+fn tap_moves_exactly_one_step() {
+    let moves = script(
+        &[(1, KeyCode::Left, Press), (2, KeyCode::Left, Release)],
+        20,
+    );
+    assert_eq!(moves.concat(), vec![PlayerAction::MoveLeft(1)]);
+}
+
+#[test]
+fn classic_single_press_moves_once() {
+    // No Release ever arrives on a classic terminal.
+    let moves = script(&[(1, KeyCode::Char('d'), Press)], 20);
+    assert_eq!(moves.concat(), vec![PlayerAction::MoveRight(1)]);
+}
+
+#[test]
+fn repeats_move_every_frame_until_release() {
+    let mut events = hold_left(10);
+    events.push((11, KeyCode::Left, Release));
+    let moves = script(&events, 20);
+    assert_eq!(
+        frames_with(&moves, &PlayerAction::MoveLeft(1)),
+        (1..=10).collect::<Vec<_>>()
+    );
+}
+
+#[test]
+fn rapid_repress_counts_as_repeat() {
+    // Classic terminal OS key-repeat: Press, Press, Press ...
+    let events: Vec<_> = [1, 3, 5, 7]
+        .iter()
+        .map(|&f| (f, KeyCode::Left, Press))
+        .collect();
+    let moves = script(&events, 10);
+    // Frame 1 steps on press; the second Press turns on continuous movement.
+    assert_eq!(
+        frames_with(&moves, &PlayerAction::MoveLeft(1)),
+        vec![1, 3, 4, 5, 6, 7, 8, 9, 10]
+    );
+}
+
+#[test]
+fn aliases_drive_the_same_direction() {
+    // Held with A, released with Left.
+    let moves = script(
+        &[
+            (1, KeyCode::Char('a'), Press),
+            (2, KeyCode::Char('A'), Repeat),
+            (3, KeyCode::Left, Release),
+        ],
+        10,
+    );
+    assert_eq!(frames_with(&moves, &PlayerAction::MoveLeft(1)), vec![1, 2]);
+}
+
+#[test]
+fn straggler_repeat_cannot_undo_release() {
+    // Release and a late Repeat drained in the same frame: the release wins.
+    let mut input = InputController::new(Bindings::default());
+    let state = playing();
+    let mut events = hold_left(4);
+    events.push((5, KeyCode::Left, Release));
+    events.push((5, KeyCode::Left, Repeat));
+    let moves = run_script(&mut input, &state, &events, 10);
+    assert_eq!(input.held_dir(), HeldDir::None);
+    assert!(moves[6..].iter().all(|m| m.is_empty()));
+}
+
+#[test]
+fn ghostty_release_on_second_key_pauses_then_resumes() {
+    // Left held; Space pressed at 6 makes Ghostty send a Release for Left
+    // and stop repeating it until Space is let go at 9.
+    let mut events = hold_left(5);
+    events.push((6, KeyCode::Char(' '), Press));
+    events.push((6, KeyCode::Left, Release));
+    events.push((9, KeyCode::Char(' '), Release));
+    events.extend((10..=12).map(|f| (f, KeyCode::Left, Repeat)));
+    let moves = script(&events, 12);
+    assert_eq!(moves[6], vec![PlayerAction::Shoot]);
+    assert_eq!(
+        frames_with(&moves, &PlayerAction::MoveLeft(1)),
+        vec![1, 2, 3, 4, 5, 10, 11, 12]
+    );
+}
+
+#[test]
+fn fast_moves_two_columns_while_held() {
+    let mut events = hold_left(3);
+    events.push((4, KeyCode::Char('f'), Press));
+    events.push((5, KeyCode::Char('f'), Repeat));
+    events.push((6, KeyCode::Char('f'), Release));
+    let moves = script(&events, 12);
+    // One on press, one from the held check — in frames 4, 5 and 6 (grace).
+    assert_eq!(moves[4], vec![PlayerAction::MoveLeft(2); 2]);
+    assert_eq!(moves[5], vec![PlayerAction::MoveLeft(2)]);
+    assert!(!moves[8].contains(&PlayerAction::MoveLeft(2)));
+}
+
+#[test]
+fn warp_is_rate_limited_while_held() {
+    let mut events = hold_left(30);
+    events.push((5, KeyCode::Char('w'), Press));
+    events.extend((6..=30).map(|f| (f, KeyCode::Char('w'), Repeat)));
+    let moves = script(&events, 30);
+    let warps = frames_with(&moves, &PlayerAction::MoveLeft(10));
+    assert_eq!(warps[0], 5);
+    for pair in warps.windows(2) {
+        assert!(pair[1] - pair[0] > WARP_COOLDOWN as u64 - 1, "{warps:?}");
+    }
+    assert!(warps.len() >= 3, "{warps:?}");
+}
+
+#[test]
+fn warp_without_direction_does_nothing() {
+    let moves = script(&[(1, KeyCode::Char('w'), Press)], 10);
+    assert!(moves.concat().is_empty());
+}
+
+#[test]
+fn player_two_keys_drive_player_two_in_coop() {
+    let coop = with_player_two(&playing());
+    let mut input = InputController::new(Bindings::default());
+    let mut events = vec![
+        (1, KeyCode::Char('j'), Press),
+        (1, KeyCode::Left, Press),
+        (2, KeyCode::Char('i'), Press),
+    ];
+    events.extend((2..=4).map(|f| (f, KeyCode::Char('j'), Repeat)));
+    events.push((5, KeyCode::Char('j'), Release));
+    let moves = run_script(&mut input, &coop, &events, 10);
+    assert_eq!(
+        moves[1],
+        vec![PlayerAction::P2MoveLeft(1), PlayerAction::MoveLeft(1)]
+    );
+    assert!(moves[2].contains(&PlayerAction::P2Shoot));
+    assert_eq!(
+        frames_with(&moves, &PlayerAction::P2MoveLeft(1)),
+        vec![1, 2, 3, 4]
+    );
+    assert_eq!(input.p2_held_dir(), HeldDir::None);
+}
+
+#[test]
+fn player_two_keys_do_nothing_solo() {
+    let moves = script(
+        &[
+            (1, KeyCode::Char('j'), Press),
+            (2, KeyCode::Char('l'), Repeat),
+            (3, KeyCode::Char('i'), Press),
+        ],
+        10,
+    );
+    assert!(moves.concat().is_empty());
+}
+
+// ── Input controller: intents by game status ──────────────────────────────────
+
+#[test]
+fn pause_key_pauses_and_resumes() {
+    let mut input = InputController::new(Bindings::default());
+    let state = playing();
+    assert_eq!(
+        press_once(&mut input, &state, KeyCode::Char('p')),
+        vec![Intent::Pause]
+    );
+    let paused = pause(&state);
+    assert_eq!(
+        press_once(&mut input, &paused, KeyCode::Esc),
+        vec![Intent::Resume]
+    );
+    assert_eq!(
+        press_once(&mut input, &paused, KeyCode::Up),
+        vec![Intent::Select(-1)]
+    );
+    assert_eq!(
+        press_once(&mut input, &paused, KeyCode::Enter),
+        vec![Intent::Confirm]
+    );
+}
+
+#[test]
+fn paused_game_ignores_movement_and_fire() {
+    let mut input = InputController::new(Bindings::default());
+    let paused = pause(&playing());
+    assert!(press_once(&mut input, &paused, KeyCode::Left).is_empty());
+    assert!(press_once(&mut input, &paused, KeyCode::Char(' ')).is_empty());
+    assert!(input.end_frame(1, &paused).is_empty());
+}
+
+#[test]
+fn game_over_keys() {
+    let mut input = InputController::new(Bindings::default());
+    let over = EntireGameStateInfo {
+        status: GameStatus::GameOver,
+        ..playing()
+    };
+    assert_eq!(
+        press_once(&mut input, &over, KeyCode::Esc),
+        vec![Intent::Quit]
+    );
+    assert_eq!(
+        press_once(&mut input, &over, KeyCode::Char('R')),
+        vec![Intent::Restart]
+    );
+    // R means nothing mid-game.
+    assert!(press_once(&mut input, &playing(), KeyCode::Char('r')).is_empty());
+}
+
+#[test]
+fn ctrl_c_always_quits() {
+    let mut input = InputController::new(Bindings::default());
+    let ctrl_c = KeyEvent::new(KeyCode::Char('c'), KeyModifiers::CONTROL);
+    assert_eq!(input.key_event(1, ctrl_c, &playing()), vec![Intent::Quit]);
+    assert_eq!(
+        input.key_event(1, ctrl_c, &pause(&playing())),
+        vec![Intent::Quit]
+    );
+}
+
+#[test]
+fn debug_toggles_need_debug_mode() {
+    let mut input = InputController::new(Bindings::default());
+    let state = playing();
+    assert!(press_once(&mut input, &state, KeyCode::Char('g')).is_empty());
+    let debug = EntireGameStateInfo {
+        debug_mode: true,
+        ..state
+    };
+    assert_eq!(
+        press_once(&mut input, &debug, KeyCode::Char('g')),
+        vec![Intent::Act(PlayerAction::ToggleGodMode)]
+    );
+}
+
+#[test]
+fn rebound_keys_drive_the_controller() {
+    let bindings = parse_bindings(r#"shoot = ["k"]"#).unwrap();
+    let mut input = InputController::new(bindings);
+    let state = playing();
+    assert_eq!(
+        press_once(&mut input, &state, KeyCode::Char('k')),
+        vec![Intent::Act(PlayerAction::Shoot)]
+    );
+    assert!(press_once(&mut input, &state, KeyCode::Char(' ')).is_empty());
+}
+
+// ── Input controller: mouse mode ──────────────────────────────────────────────
+
+fn mouse(kind: MouseEventKind, column: u16) -> MouseEvent {
+    MouseEvent {
+        kind,
+        column,
+        row: 10,
+        modifiers: KeyModifiers::NONE,
+    }
+}
+
+fn mouse_controller() -> InputController {
+    let mut input = InputController::new(Bindings::default());
+    input.set_mouse(true);
+    input
+}
+
+/// Run `frames` frames, applying each frame's moves to the state.
+fn follow(input: &mut InputController, state: &mut EntireGameStateInfo, frames: u64) {
+    for frame in 1..=frames {
+        for action in input.end_frame(frame, state) {
+            *state = shooting_game::compute::apply_action(state, &action);
+        }
+    }
+}
+
+#[test]
+fn ship_follows_the_pointer_at_max_speed() {
+    let mut input = mouse_controller();
+    let mut state = playing();
+    let start = state.player.x;
+    input.mouse_event(1, mouse(MouseEventKind::Moved, start as u16 + 9), &state);
+    let moves = input.end_frame(1, &state);
+    assert_eq!(moves, vec![PlayerAction::MoveRight(MOUSE_MAX_SPEED)]);
+    follow(&mut input, &mut state, 10);
+    assert_eq!(state.player.x, start + 9);
+}
+
+#[test]
+fn ship_stops_short_of_the_walls() {
+    let mut input = mouse_controller();
+    let mut state = playing();
+    input.mouse_event(1, mouse(MouseEventKind::Moved, 0), &state);
+    follow(&mut input, &mut state, 60);
+    assert_eq!(state.player.x, 1);
+    // Target reached: the keyboard is free to move the ship again.
+    assert!(input.end_frame(61, &state).is_empty());
+}
+
+#[test]
+fn click_fires_and_hold_autofires() {
+    let mut input = mouse_controller();
+    let state = playing();
+    let x = state.player.x as u16;
+    assert_eq!(
+        input.mouse_event(1, mouse(MouseEventKind::Down(MouseButton::Left), x), &state),
+        vec![Intent::Act(PlayerAction::Shoot)]
+    );
+    let shots: Vec<u64> = (1..=1 + 2 * AUTOFIRE_INTERVAL)
+        .filter(|&f| input.end_frame(f, &state).contains(&PlayerAction::Shoot))
+        .collect();
+    assert_eq!(
+        shots,
+        vec![1 + AUTOFIRE_INTERVAL, 1 + 2 * AUTOFIRE_INTERVAL]
+    );
+
+    input.mouse_event(20, mouse(MouseEventKind::Up(MouseButton::Left), x), &state);
+    assert!((20..40).all(|f| input.end_frame(f, &state).is_empty()));
+}
+
+#[test]
+fn release_while_paused_stops_autofire() {
+    let mut input = mouse_controller();
+    let state = playing();
+    input.mouse_event(
+        1,
+        mouse(MouseEventKind::Down(MouseButton::Left), 40),
+        &state,
+    );
+    input.mouse_event(
+        2,
+        mouse(MouseEventKind::Up(MouseButton::Left), 40),
+        &pause(&state),
+    );
+    assert!((3..20).all(|f| !input.end_frame(f, &state).contains(&PlayerAction::Shoot)));
+}
+
+#[test]
+fn mouse_is_ignored_unless_enabled() {
+    let mut input = InputController::new(Bindings::default());
+    let state = playing();
+    let click = mouse(MouseEventKind::Down(MouseButton::Left), 5);
+    assert!(input.mouse_event(1, click, &state).is_empty());
+    assert!(input.end_frame(1, &state).is_empty());
+}
@@ -0,0 +1,495 @@
+use std::io::Cursor;
+use std::net::{TcpListener, TcpStream};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use shooting_game::compute::{apply_action, tick};
+use shooting_game::entities::*;
+use shooting_game::net::*;
+use shooting_game::replay::{state_hash, Replay, ReplayFrame};
+use shooting_game::savegame::SaveGame;
+
+/// A small co-op game to host.
+fn game() -> Replay {
+    let mut game = Replay::new(42, Level::Easy, Difficulty::preset(&Level::Easy), 60, 24);
+    game.coop = true;
+    game
+}
+
+/// A host and a client connected over localhost, with the game started.
+fn connect() -> (Host, Client) {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap();
+    let host = thread::spawn(move || {
+        let (stream, _) = listener.accept().unwrap();
+        let mut host = Host::accept(stream).unwrap();
+        host.start(&game()).unwrap();
+        host
+    });
+    let client = Client::join(addr).unwrap();
+    (host.join().unwrap(), client)
+}
+
+/// Poll `f` until it returns `Some`, for at most two seconds.
+fn eventually<T>(mut f: impl FnMut() -> Option<T>) -> T {
+    let deadline = Instant::now() + Duration::from_secs(2);
+    loop {
+        if let Some(v) = f() {
+            return v;
+        }
+        assert!(Instant::now() < deadline, "timed out");
+        thread::sleep(Duration::from_millis(5));
+    }
+}
+
+/// The host side of one frame: apply player two's inputs, tick, send.
+fn host_frame(
+    host: &mut Host,
+    state: &mut EntireGameStateInfo,
+    rng: &mut GameRng,
+    remote: Vec<PlayerAction>,
+) {
+    for action in &remote {
+        *state = apply_action(state, action);
+    }
+    *state = tick(state, rng);
+    host.send_frame(&ReplayFrame {
+        actions: remote,
+        hash: state_hash(state),
+    })
+    .unwrap();
+}
+
+// ── Protocol ──────────────────────────────────────────────────────────────────
+
+#[test]
+fn messages_round_trip() {
+    let (state, rng) = game().start();
+    let messages = [
+        Message::Hello { version: 1 },
+        Message::Refuse("host speaks protocol 1, client 2".to_string()),
+        Message::Start(game()),
+        Message::Input {
+            seq: 42,
+            actions: vec![PlayerAction::P2MoveLeft(1), PlayerAction::P2Shoot],
+        },
+        Message::Frame {
+            ack: 42,
+            frame: ReplayFrame {
+                actions: vec![PlayerAction::MoveRight(2), PlayerAction::P2Leave],
+                hash: 0x9c1d_2e3f_4a5b_6c7d,
+            },
+        },
+        Message::Idle,
+        Message::Snapshot {
+            frames: 200,
+            game: Box::new(SaveGame::new(&state, &rng)),
+        },
+        Message::Bye,
+    ];
+    for msg in messages {
+        assert_eq!(Message::parse(&msg.to_text()), Ok(msg));
+    }
+    assert_eq!(
+        Message::Input {
+            seq: 7,
+            actions: vec![PlayerAction::P2MoveRight(1)],
+        }
+        .to_text(),
+        "input 7 2R1"
+    );
+}
+
+#[test]
+fn rejects_garbage() {
+    assert!(Message::parse("").is_err());
+    assert!(Message::parse("launch missiles").is_err());
+    assert!(Message::parse("input x").is_err());
+    assert!(Message::parse("frame 1 nothex").is_err());
+    assert!(Message::parse("input 1 2Z").is_err());
+}
+
+#[test]
+fn rejects_a_start_the_client_could_not_run() {
+    let mut tiny = game();
+    tiny.width = 30;
+    tiny.height = 3;
+    assert!(Message::parse(&Message::Start(tiny).to_text()).is_err());
+
+    let (mut state, rng) = game().start();
+    state.height = 3;
+    let snapshot = Message::Snapshot {
+        frames: 0,
+        game: Box::new(SaveGame::new(&state, &rng)),
+    };
+    assert!(Message::parse(&snapshot.to_text()).is_err());
+}
+
+#[test]
+fn messages_are_length_prefixed() {
+    let mut bytes = Vec::new();
+    write_message(&mut bytes, &Message::Idle).unwrap();
+    write_message(&mut bytes, &Message::Bye).unwrap();
+    assert_eq!(&bytes[..8], b"\0\0\0\x04idle");
+
+    let mut r = Cursor::new(bytes);
+    assert_eq!(read_message(&mut r).unwrap(), Message::Idle);
+    assert_eq!(read_message(&mut r).unwrap(), Message::Bye);
+    assert!(read_message(&mut r).is_err());
+}
+
+#[test]
+fn oversized_messages_are_refused() {
+    let mut r = Cursor::new(vec![0xff, 0xff, 0xff, 0xff]);
+    assert!(read_message(&mut r).is_err());
+}
+
+#[test]
+fn local_keys_become_player_two() {
+    assert_eq!(
+        as_player_two(&PlayerAction::MoveLeft(2)),
+        Some(PlayerAction::P2MoveLeft(2))
+    );
+    assert_eq!(
+        as_player_two(&PlayerAction::Shoot),
+        Some(PlayerAction::P2Shoot)
+    );
+    assert_eq!(as_player_two(&PlayerAction::ToggleGodMode), None);
+    assert_eq!(as_player_two(&PlayerAction::Resize(80, 24)), None);
+}
+
+// ── Sessions over localhost ───────────────────────────────────────────────────
+
+#[test]
+fn client_starts_the_hosts_game() {
+    let (_host, client) = connect();
+    let (expected, _) = game().start();
+    assert_eq!(state_hash(client.state()), state_hash(&expected));
+    assert!(client.state().player2.is_some());
+}
+
+#[test]
+fn client_inputs_reach_the_host_as_player_two() {
+    let (mut host, mut client) = connect();
+    client
+        .send(&[PlayerAction::MoveRight(1), PlayerAction::Shoot])
+        .unwrap();
+    let received = eventually(|| Some(host.receive().unwrap()).filter(|a| !a.is_empty()));
+    assert_eq!(
+        received,
+        vec![PlayerAction::P2MoveRight(1), PlayerAction::P2Shoot]
+    );
+}
+
+#[test]
+fn client_follows_the_host_frame_for_frame() {
+    let (mut host, mut client) = connect();
+    let (mut state, mut rng) = game().start();
+    for i in 0..60 {
+        let remote = if i % 10 == 0 {
+            vec![PlayerAction::P2Shoot]
+        } else {
+            vec![]
+        };
+        host_frame(&mut host, &mut state, &mut rng, remote);
+    }
+    eventually(|| {
+        client.sync().unwrap();
+        (client.state().frame == state.frame).then_some(())
+    });
+    assert_eq!(state_hash(client.state()), state_hash(&state));
+}
+
+#[test]
+fn client_predicts_its_own_moves_until_the_host_confirms() {
+    let (mut host, mut client) = connect();
+    let (mut state, mut rng) = game().start();
+    let start_x = client.state().player2.as_ref().unwrap().ship.x;
+
+    client.send(&[PlayerAction::MoveLeft(1)]).unwrap();
+    client.send(&[PlayerAction::MoveLeft(1)]).unwrap();
+    // Not acknowledged yet: only the view has moved.
+    assert_eq!(client.view().player2.unwrap().ship.x, start_x - 2);
+    assert_eq!(client.state().player2.as_ref().unwrap().ship.x, start_x);
+
+    let remote = eventually(|| Some(host.receive().unwrap()).filter(|a| a.len() == 2));
+    host_frame(&mut host, &mut state, &mut rng, remote);
+    eventually(|| {
+        client.sync().unwrap();
+        (client.state().frame == state.frame).then_some(())
+    });
+    assert_eq!(client.state().player2.as_ref().unwrap().ship.x, start_x - 2);
+    assert_eq!(
+        client.view().player2.unwrap().ship.x,
+        start_x - 2,
+        "confirmed moves are not applied twice"
+    );
+}
+
+#[test]
+fn host_only_accepts_player_two_inputs() {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let mut raw = TcpStream::connect(listener.local_addr().unwrap()).unwrap();
+    write_message(
+        &mut raw,
+        &Message::Hello {
+            version: PROTOCOL_VERSION,
+        },
+    )
+    .unwrap();
+    let (stream, _) = listener.accept().unwrap();
+    let mut host = Host::accept(stream).unwrap();
+
+    let input = Message::Input {
+        seq: 1,
+        actions: vec![
+            PlayerAction::MoveLeft(5),
+            PlayerAction::ToggleGodMode,
+            PlayerAction::P2MoveRight(50),
+            PlayerAction::P2Leave,
+        ],
+    };
+    write_message(&mut raw, &input).unwrap();
+    let received = eventually(|| Some(host.receive().unwrap()).filter(|a| !a.is_empty()));
+    assert_eq!(received, vec![PlayerAction::P2MoveRight(10)]);
+}
+
+#[test]
+fn host_refuses_another_protocol_version() {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let mut raw = TcpStream::connect(listener.local_addr().unwrap()).unwrap();
+    write_message(&mut raw, &Message::Hello { version: 999 }).unwrap();
+    let (stream, _) = listener.accept().unwrap();
+    assert!(Host::accept(stream).is_err());
+    assert!(matches!(
+        read_message(&mut raw).unwrap(),
+        Message::Refuse(_)
+    ));
+}
+
+#[test]
+fn client_reports_a_desync() {
+    let (mut host, mut client) = connect();
+    host.send_frame(&ReplayFrame {
+        actions: vec![],
+        hash: 0,
+    })
+    .unwrap();
+    let err = eventually(|| client.sync().err());
+    assert!(matches!(err, NetError::Desync(d) if d.frame == 0));
+}
+
+#[test]
+fn idle_frames_mean_the_host_is_paused() {
+    let (mut host, mut client) = connect();
+    host.send_idle().unwrap();
+    eventually(|| {
+        client.sync().unwrap();
+        client.host_idle().then_some(())
+    });
+}
+
+#[test]
+fn host_restart_resets_the_client() {
+    let (mut host, mut client) = connect();
+    let (mut state, mut rng) = game().start();
+    host_frame(&mut host, &mut state, &mut rng, vec![]);
+    let mut next = game();
+    next.seed = 43;
+    host.start(&next).unwrap();
+    eventually(|| {
+        client.sync().unwrap();
+        (client.state().frame == 0).then_some(())
+    });
+    assert_eq!(state_hash(client.state()), state_hash(&next.start().0));
+}
+
+// ── Disconnects ───────────────────────────────────────────────────────────────
+
+#[test]
+fn host_sees_the_client_leave() {
+    let (mut host, client) = connect();
+    client.leave();
+    assert_eq!(eventually(|| host.receive().err()), NetError::Left);
+}
+
+#[test]
+fn host_sees_a_dropped_connection() {
+    let (mut host, client) = connect();
+    drop(client);
+    assert!(matches!(
+        eventually(|| host.receive().err()),
+        NetError::Lost(_)
+    ));
+}
+
+#[test]
+fn client_stays_while_the_host_is_in_a_menu() {
+    // The host's game loop sends nothing while the pause menu's settings or
+    // quit prompt is up; the link's heartbeat has to carry the session.
+    let (mut host, mut client) = connect();
+    let (mut state, mut rng) = game().start();
+    host_frame(&mut host, &mut state, &mut rng, vec![]);
+
+    let until = Instant::now() + TIMEOUT + Duration::from_secs(1);
+    while Instant::now() < until {
+        client.send(&[]).unwrap();
+        client.sync().unwrap();
+        thread::sleep(Duration::from_millis(33));
+    }
+    assert!(client.host_idle());
+
+    // Back in the game, frames carry on where they left off.
+    host.receive().unwrap();
+    host_frame(&mut host, &mut state, &mut rng, vec![]);
+    eventually(|| {
+        client.sync().unwrap();
+        (client.state().frame == 2).then_some(())
+    });
+}
+
+#[test]
+fn client_sees_the_host_leave() {
+    let (host, mut client) = connect();
+    host.leave();
+    assert_eq!(eventually(|| client.sync().err()), NetError::Left);
+}
+
+// ── Broadcasts ────────────────────────────────────────────────────────────────
+
+fn watch(broadcast: &mut Broadcast) -> Viewer {
+    Viewer::watch(broadcast.local_addr().unwrap()).unwrap()
+}
+
+/// Tick `frames` frames of `game` and broadcast each one.
+fn broadcast_frames(
+    broadcast: &mut Broadcast,
+    state: &mut EntireGameStateInfo,
+    rng: &mut GameRng,
+    frames: usize,
+) {
+    for _ in 0..frames {
+        *state = tick(state, rng);
+        broadcast.send_frame(
+            &ReplayFrame {
+                actions: vec![],
+                hash: state_hash(state),
+            },
+            state,
+            rng,
+        );
+    }
+}
+
+#[test]
+fn viewer_connects_while_nothing_is_played() {
+    // The broadcaster is on the menu: no game, nothing sent.
+    let broadcast = Broadcast::bind("127.0.0.1:0").unwrap();
+    let mut viewer = Viewer::watch(broadcast.local_addr().unwrap()).unwrap();
+    assert_eq!(broadcast.viewers(), 1);
+    viewer.sync().unwrap();
+    assert!(viewer.state().is_none());
+}
+
+#[test]
+fn viewer_waits_for_a_game() {
+    let mut broadcast = Broadcast::bind("127.0.0.1:0").unwrap();
+    let mut viewer = watch(&mut broadcast);
+    viewer.sync().unwrap();
+    assert!(viewer.state().is_none());
+
+    broadcast.start(&game());
+    let (mut state, mut rng) = game().start();
+    broadcast_frames(&mut broadcast, &mut state, &mut rng, 5);
+    eventually(|| {
+        viewer.sync().unwrap();
+        (viewer.state()?.frame == state.frame).then_some(())
+    });
+    assert_eq!(state_hash(viewer.state().unwrap()), state_hash(&state));
+}
+
+#[test]
+fn viewer_joining_mid_game_gets_a_snapshot_first() {
+    let mut broadcast = Broadcast::bind("127.0.0.1:0").unwrap();
+    broadcast.start(&game());
+    let (mut state, mut rng) = game().start();
+    broadcast_frames(&mut broadcast, &mut state, &mut rng, 200);
+
+    let mut viewer = watch(&mut broadcast);
+    eventually(|| {
+        viewer.sync().unwrap();
+        (viewer.state()?.frame == state.frame).then_some(())
+    });
+    assert_eq!(state_hash(viewer.state().unwrap()), state_hash(&state));
+
+    // ...and then follows live.
+    broadcast_frames(&mut broadcast, &mut state, &mut rng, 10);
+    eventually(|| {
+        viewer.sync().unwrap();
+        (viewer.state()?.frame == state.frame).then_some(())
+    });
+    assert_eq!(state_hash(viewer.state().unwrap()), state_hash(&state));
+}
+
+#[test]
+fn late_joiners_get_the_latest_state_not_the_history() {
+    let mut broadcast = Broadcast::bind("127.0.0.1:0").unwrap();
+    broadcast.start(&game());
+    let (mut state, mut rng) = game().start();
+    broadcast_frames(&mut broadcast, &mut state, &mut rng, 300);
+
+    let mut stream = TcpStream::connect(broadcast.local_addr().unwrap()).unwrap();
+    assert_eq!(
+        read_message(&mut stream).unwrap(),
+        Message::Hello {
+            version: PROTOCOL_VERSION
+        }
+    );
+    match read_message(&mut stream).unwrap() {
+        Message::Snapshot { frames, game } => {
+            assert_eq!(frames, 300);
+            assert_eq!(state_hash(&game.state), state_hash(&state));
+            assert_eq!(game.rng, rng);
+        }
+        other => panic!("expected a snapshot, got {:?}", other.to_text()),
+    }
+    // Nothing else until the next frame.
+    assert_eq!(read_message(&mut stream).unwrap(), Message::Idle);
+}
+
+#[test]
+fn every_viewer_gets_every_frame() {
+    let mut broadcast = Broadcast::bind("127.0.0.1:0").unwrap();
+    let mut viewers = [watch(&mut broadcast), watch(&mut broadcast)];
+    assert_eq!(broadcast.viewers(), 2);
+
+    broadcast.start(&game());
+    let (mut state, mut rng) = game().start();
+    broadcast_frames(&mut broadcast, &mut state, &mut rng, 20);
+    for viewer in &mut viewers {
+        eventually(|| {
+            viewer.sync().unwrap();
+            (viewer.state()?.frame == state.frame).then_some(())
+        });
+    }
+}
+
+#[test]
+fn departed_viewers_are_dropped() {
+    let mut broadcast = Broadcast::bind("127.0.0.1:0").unwrap();
+    let viewer = watch(&mut broadcast);
+    assert_eq!(broadcast.viewers(), 1);
+    drop(viewer);
+    eventually(|| {
+        broadcast.send_idle();
+        (broadcast.viewers() == 0).then_some(())
+    });
+}
+
+#[test]
+fn viewer_sees_the_broadcast_end() {
+    let mut broadcast = Broadcast::bind("127.0.0.1:0").unwrap();
+    let mut viewer = watch(&mut broadcast);
+    drop(broadcast);
+    assert_eq!(eventually(|| viewer.sync().err()), NetError::Left);
+}
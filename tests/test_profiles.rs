@@ -0,0 +1,154 @@
+use shooting_game::entities::{Difficulty, Level, PowerUpLoss};
+use shooting_game::profiles::*;
+
+use std::path::Path;
+
+// ── parse_profiles ────────────────────────────────────────────────────────────
+
+#[test]
+fn empty_file_has_no_profiles() {
+    assert_eq!(parse_profiles("").unwrap(), vec![]);
+}
+
+#[test]
+fn unset_fields_inherit_from_base() {
+    let text = r#"
+        [[profile]]
+        name = "Bullet Hell"
+        base = "hard"
+        enemy_fire_chance = 60
+        spacecraft_chance = 0.3
+    "#;
+    let profiles = parse_profiles(text).unwrap();
+    assert_eq!(profiles.len(), 1);
+    let p = &profiles[0];
+    assert_eq!(p.level, Level::Hard);
+    assert_eq!(
+        p.difficulty,
+        Difficulty {
+            name: "Bullet Hell".to_string(),
+            builtin: false,
+            enemy_fire_chance: 60,
+            spacecraft_chance: 0.3,
+            ..Difficulty::preset(&Level::Hard)
+        }
+    );
+}
+
+#[test]
+fn base_defaults_to_medium() {
+    let p = &parse_profiles("[[profile]]\nname = \"x\"\n").unwrap()[0];
+    assert_eq!(p.level, Level::Medium);
+    assert_eq!(
+        p.difficulty.enemy_spawn_rate,
+        Difficulty::preset(&Level::Medium).enemy_spawn_rate
+    );
+}
+
+#[test]
+fn profiles_keep_file_order() {
+    let text = "[[profile]]\nname = \"b\"\n[[profile]]\nname = \"a\"\n";
+    let names: Vec<String> = parse_profiles(text)
+        .unwrap()
+        .into_iter()
+        .map(|p| p.difficulty.name)
+        .collect();
+    assert_eq!(names, ["b", "a"]);
+}
+
+#[test]
+fn rejects_zero_interval() {
+    let err = parse_profiles("[[profile]]\nname = \"x\"\nenemy_spawn_rate = 0\n").unwrap_err();
+    assert!(
+        err.contains("\"x\""),
+        "error should name the profile: {err}"
+    );
+}
+
+#[test]
+fn rejects_huge_interval() {
+    let err = parse_profiles("[[profile]]\nname = \"x\"\nenemy_spawn_rate = 9223372036854775807\n")
+        .unwrap_err();
+    assert_eq!(
+        err,
+        "profile \"x\": enemy_spawn_rate must be at most 1000000"
+    );
+}
+
+#[test]
+fn rejects_out_of_range_chance() {
+    assert!(parse_profiles("[[profile]]\nname = \"x\"\nspacecraft_chance = 1.5\n").is_err());
+}
+
+#[test]
+fn rejects_unknown_base_and_fields() {
+    assert!(parse_profiles("[[profile]]\nname = \"x\"\nbase = \"insane\"\n").is_err());
+    assert!(parse_profiles("[[profile]]\nname = \"x\"\nenemy_speed = 3\n").is_err());
+}
+
+#[test]
+fn death_rules_can_be_overridden() {
+    let text = "[[profile]]\nname = \"x\"\nbase = \"easy\"\nclear_shots_on_hit = false\npower_up_loss = \"lose\"\n";
+    let d = &parse_profiles(text).unwrap()[0].difficulty;
+    assert!(!d.clear_shots_on_hit);
+    assert_eq!(d.power_up_loss, PowerUpLoss::Lose);
+    assert!(parse_profiles("[[profile]]\nname = \"x\"\npower_up_loss = \"halve\"\n").is_err());
+}
+
+#[test]
+fn rejects_blank_name() {
+    assert!(parse_profiles("[[profile]]\nname = \"  \"\n").is_err());
+}
+
+#[test]
+fn missing_file_is_not_an_error() {
+    let profiles = load_profiles(Path::new("does/not/exist/profiles.toml")).unwrap();
+    assert!(profiles.is_empty());
+}
+
+// ── score_key ─────────────────────────────────────────────────────────────────
+
+#[test]
+fn builtin_score_key_is_level_name() {
+    let d = Difficulty::preset(&Level::Extreme);
+    assert_eq!(score_key(&Level::Extreme, &d), "extreme");
+}
+
+#[test]
+fn custom_score_key_changes_with_parameters() {
+    let text = "[[profile]]\nname = \"x\"\nbase = \"easy\"\n";
+    let a = parse_profiles(text).unwrap().remove(0);
+    let mut b = a.clone();
+    b.difficulty.enemy_fire_chance += 1;
+
+    let key_a = score_key(&a.level, &a.difficulty);
+    assert!(key_a.starts_with("custom:x:"));
+    assert_ne!(key_a, "easy");
+    assert_ne!(key_a, score_key(&b.level, &b.difficulty));
+}
+
+#[test]
+fn default_death_rules_keep_older_keys() {
+    // The key this profile had before the death rules were added.
+    let text = "[[profile]]\nname = \"x\"\nbase = \"easy\"\n";
+    let p = parse_profiles(text).unwrap().remove(0);
+    assert_eq!(
+        score_key(&p.level, &p.difficulty),
+        "custom:x:b25b10022f6934d7"
+    );
+
+    let mut lose = p.clone();
+    lose.difficulty.power_up_loss = PowerUpLoss::Lose;
+    let mut clear = p.clone();
+    clear.difficulty.clear_shots_on_hit = true;
+    let keys = [&p, &lose, &clear].map(|p| score_key(&p.level, &p.difficulty));
+    assert_ne!(keys[0], keys[1]);
+    assert_ne!(keys[0], keys[2]);
+    assert_ne!(keys[1], keys[2]);
+}
+
+#[test]
+fn coop_score_key_is_prefixed() {
+    let d = Difficulty::preset(&Level::Hard);
+    assert_eq!(coop_score_key(&Level::Hard, &d), "coop:hard");
+}
@@ -0,0 +1,362 @@
+use shooting_game::bindings::Bindings;
+use shooting_game::buffer::{Buffer, Cell};
+use shooting_game::compute::init_state;
+use shooting_game::display::draw;
+use shooting_game::entities::{
+    Bullet, BulletOwner, Enemy, EnemyBehavior, EnemyKind, EntireGameStateInfo, Level,
+};
+use shooting_game::renderer::{write_diff, Renderer, TerminalRenderer, TextRenderer};
+
+use crossterm::{
+    cursor,
+    style::{Color, Print, SetBackgroundColor, SetForegroundColor},
+    terminal, QueueableCommand,
+};
+
+/// Escape sequence crossterm writes for `Clear(All)`.
+fn clear_all() -> Vec<u8> {
+    let mut out = Vec::new();
+    out.queue(terminal::Clear(terminal::ClearType::All))
+        .unwrap();
+    out
+}
+
+fn contains(haystack: &[u8], needle: &[u8]) -> bool {
+    haystack.windows(needle.len()).any(|w| w == needle)
+}
+
+fn diff(prev: &Buffer, next: &Buffer) -> Vec<u8> {
+    let mut out = Vec::new();
+    write_diff(&mut out, prev, next).unwrap();
+    out
+}
+
+// ── Buffer ────────────────────────────────────────────────────────────────────
+
+#[test]
+fn print_clips_at_the_edges() {
+    let mut buf = Buffer::new(5, 2);
+    buf.print(3, 0, "abcd", Color::White);
+    buf.print(0, 2, "off", Color::White);
+    assert_eq!(buf.row_str(0), "   ab");
+    assert_eq!(buf.row_str(1), "     ");
+    assert_eq!(buf.char_at(9, 9), ' ');
+}
+
+#[test]
+fn to_text_trims_trailing_spaces() {
+    let mut buf = Buffer::new(6, 3);
+    buf.print(1, 1, "hi", Color::Red);
+    assert_eq!(buf.to_text(), "\n hi\n\n");
+}
+
+// ── write_diff ────────────────────────────────────────────────────────────────
+
+#[test]
+fn identical_buffers_write_nothing() {
+    let mut buf = Buffer::new(10, 3);
+    buf.print(2, 1, "same", Color::Green);
+    assert!(diff(&buf, &buf.clone()).is_empty());
+}
+
+#[test]
+fn changed_run_shares_one_move_and_colour() {
+    let prev = Buffer::new(10, 3);
+    let mut next = prev.clone();
+    next.print(2, 1, "ab", Color::Green);
+
+    let mut expected = Vec::new();
+    expected.queue(cursor::MoveTo(2, 1)).unwrap();
+    expected.queue(SetForegroundColor(Color::Green)).unwrap();
+    expected.queue(SetBackgroundColor(Color::Reset)).unwrap();
+    expected.queue(Print("ab")).unwrap();
+    assert_eq!(diff(&prev, &next), expected);
+}
+
+#[test]
+fn colour_change_splits_the_run_without_moving() {
+    let prev = Buffer::new(10, 3);
+    let mut next = prev.clone();
+    next.print(0, 0, "a", Color::Green);
+    next.print(1, 0, "b", Color::Red);
+
+    let mut expected = Vec::new();
+    expected.queue(cursor::MoveTo(0, 0)).unwrap();
+    expected.queue(SetForegroundColor(Color::Green)).unwrap();
+    expected.queue(SetBackgroundColor(Color::Reset)).unwrap();
+    expected.queue(Print("a")).unwrap();
+    expected.queue(SetForegroundColor(Color::Red)).unwrap();
+    expected.queue(Print("b")).unwrap();
+    assert_eq!(diff(&prev, &next), expected);
+}
+
+#[test]
+fn erased_sprite_restores_what_was_under_it() {
+    // An enemy drawn over the left wall moves away: the wall comes back.
+    let mut prev = Buffer::new(10, 3);
+    prev.print(0, 1, "│", Color::DarkBlue);
+    let mut next = prev.clone();
+    prev.print(0, 1, "«▼»", Color::Green);
+
+    let mut expected = Vec::new();
+    expected.queue(cursor::MoveTo(0, 1)).unwrap();
+    expected.queue(SetForegroundColor(Color::DarkBlue)).unwrap();
+    expected.queue(SetBackgroundColor(Color::Reset)).unwrap();
+    expected.queue(Print("│")).unwrap();
+    expected.queue(SetForegroundColor(Color::Reset)).unwrap();
+    expected.queue(Print("  ")).unwrap();
+    assert_eq!(diff(&prev, &next), expected);
+
+    next.set(5, 2, Cell::BLANK);
+    assert_eq!(
+        diff(&prev, &next),
+        expected,
+        "blank over blank is no change"
+    );
+}
+
+// ── TerminalRenderer ──────────────────────────────────────────────────────────
+
+/// Everything written while presenting `frames` in order to one renderer.
+fn output(frames: &[(&Buffer, bool)]) -> Vec<u8> {
+    let mut term = TerminalRenderer::new(Vec::new());
+    for (frame, full_redraw) in frames {
+        term.present((*frame).clone(), *full_redraw).unwrap();
+    }
+    term.into_inner()
+}
+
+/// Bytes written for the last of `frames`.
+fn last_frame_bytes(frames: &[(&Buffer, bool)]) -> Vec<u8> {
+    let before = output(&frames[..frames.len() - 1]).len();
+    output(frames)[before..].to_vec()
+}
+
+#[test]
+fn terminal_first_frame_clears() {
+    let mut frame = Buffer::new(20, 5);
+    frame.print(1, 1, "hello", Color::Yellow);
+    let out = output(&[(&frame, false)]);
+    assert!(contains(&out, &clear_all()));
+    assert!(contains(&out, "hello".as_bytes()));
+}
+
+#[test]
+fn terminal_unchanged_frame_writes_almost_nothing() {
+    let mut frame = Buffer::new(20, 5);
+    frame.print(1, 1, "hello", Color::Yellow);
+    let second = last_frame_bytes(&[(&frame, false), (&frame, false)]);
+    // Only the colour reset and cursor park.
+    assert!(
+        second.len() < 32,
+        "unchanged frame wrote {} bytes",
+        second.len()
+    );
+    assert!(!contains(&second, &clear_all()));
+}
+
+#[test]
+fn terminal_full_redraw_and_new_size_clear_again() {
+    let frame = Buffer::new(20, 5);
+    let bigger = Buffer::new(30, 6);
+    assert!(contains(
+        &last_frame_bytes(&[(&frame, false), (&frame, true)]),
+        &clear_all()
+    ));
+    assert!(contains(
+        &last_frame_bytes(&[(&frame, false), (&bigger, false)]),
+        &clear_all()
+    ));
+}
+
+#[test]
+fn terminal_moving_sprite_costs_a_fraction_of_a_repaint() {
+    let mut frame1 = Buffer::new(80, 24);
+    for y in 0..24 {
+        frame1.print(0, y, &"·".repeat(80), Color::DarkGrey);
+    }
+    let mut frame2 = frame1.clone();
+    frame1.print(30, 10, "║", Color::Cyan);
+    frame2.print(30, 9, "║", Color::Cyan);
+
+    let full = output(&[(&frame2, false)]).len();
+    let moved = last_frame_bytes(&[(&frame1, false), (&frame2, false)]).len();
+    assert!(
+        moved * 50 < full,
+        "moved {moved} bytes vs full {full} bytes"
+    );
+}
+
+// ── Frames on a virtual terminal ──────────────────────────────────────────────
+//
+// `VirtualScreen` applies the bytes `TerminalRenderer` writes to a character
+// grid the way a real terminal would, so consecutive frames can be checked
+// for what is actually left on screen.  Only the sequences the renderer's
+// content depends on are interpreted:
+//   ESC [ <row> ; <col> H  — MoveTo (1-indexed)
+//   ESC [ 2 J              — Clear All
+//   anything else          — colours, cursor visibility (ignored)
+
+struct VirtualScreen {
+    grid: Vec<Vec<char>>,
+    col: usize,
+    row: usize,
+}
+
+impl VirtualScreen {
+    fn new(width: u16, height: u16) -> Self {
+        VirtualScreen {
+            grid: vec![vec![' '; width as usize]; height as usize],
+            col: 0,
+            row: 0,
+        }
+    }
+
+    fn apply(&mut self, bytes: &[u8]) {
+        let s = std::str::from_utf8(bytes).expect("terminal output must be valid UTF-8");
+        let mut chars = s.chars().peekable();
+        while let Some(ch) = chars.next() {
+            if ch == '\x1b' && chars.peek() == Some(&'[') {
+                chars.next();
+                let mut params = String::new();
+                while let Some(&c) = chars.peek() {
+                    if c.is_ascii_digit() || c == ';' || c == '?' {
+                        params.push(c);
+                        chars.next();
+                    } else {
+                        break;
+                    }
+                }
+                match chars.next() {
+                    Some('H') => {
+                        let mut parts = params.split(';').map(|p| p.parse().unwrap_or(1));
+                        self.row = parts.next().unwrap_or(1usize).saturating_sub(1);
+                        self.col = parts.next().unwrap_or(1usize).saturating_sub(1);
+                    }
+                    Some('J') if params == "2" => {
+                        for row in &mut self.grid {
+                            row.fill(' ');
+                        }
+                    }
+                    _ => {}
+                }
+            } else if !ch.is_control() {
+                if let Some(cell) = self
+                    .grid
+                    .get_mut(self.row)
+                    .and_then(|r| r.get_mut(self.col))
+                {
+                    *cell = ch;
+                }
+                self.col += 1;
+            }
+        }
+    }
+
+    fn char_at(&self, col: u16, row: u16) -> char {
+        self.grid[row as usize][col as usize]
+    }
+}
+
+const W: u16 = 80;
+const H: u16 = 20;
+
+fn empty_state() -> EntireGameStateInfo {
+    let mut state = init_state(Level::Easy, W, H, 0);
+    state.enemies.clear();
+    state.bullets.clear();
+    state
+}
+
+/// The screen after drawing `first` and then `second` through one renderer.
+fn two_frames(first: &EntireGameStateInfo, second: &EntireGameStateInfo) -> VirtualScreen {
+    let bindings = Bindings::default();
+    let mut term = TerminalRenderer::new(Vec::new());
+    term.present(draw(first, &bindings), true).unwrap();
+    term.present(draw(second, &bindings), false).unwrap();
+    let mut screen = VirtualScreen::new(W, H);
+    screen.apply(&term.into_inner());
+    screen
+}
+
+#[test]
+fn subsequent_frame_walls_redrawn() {
+    let state = empty_state();
+    let screen = two_frames(&state, &state);
+    for row in 2..H - 2 {
+        assert_eq!(
+            screen.char_at(0, row),
+            '│',
+            "left wall missing at row {row}"
+        );
+        assert_eq!(
+            screen.char_at(W - 1, row),
+            '│',
+            "right wall missing at row {row}"
+        );
+    }
+}
+
+#[test]
+fn no_ghost_at_col0_after_enemy_moves() {
+    // Enemy at x=1 renders starting at col 0 (lx = max(0, x-1) = 0).
+    // After the enemy disappears, col 0 must show the wall again.
+    const ENEMY_ROW: i32 = 5;
+    let mut state1 = empty_state();
+    state1.enemies = vec![Enemy {
+        x: 1,
+        y: ENEMY_ROW,
+        kind: EnemyKind::Spacecraft,
+        hp: 1,
+        behavior: EnemyBehavior::Straight,
+    }];
+    let state2 = empty_state(); // no enemies
+    assert_eq!(
+        two_frames(&state1, &state1).char_at(0, ENEMY_ROW as u16),
+        '«'
+    );
+    let screen = two_frames(&state1, &state2);
+    assert_eq!(
+        screen.char_at(0, ENEMY_ROW as u16),
+        '│',
+        "ghost sprite at col 0 after enemy moved away"
+    );
+}
+
+#[test]
+fn no_ghost_at_right_edge_after_bullet_moves() {
+    // A bullet at x = w-1 renders at the right border column.
+    // After it moves, col w-1 must be restored to the wall glyph.
+    const BULLET_ROW: i32 = 8;
+    let mut state1 = empty_state();
+    state1.bullets = vec![Bullet {
+        x: W as i32 - 1,
+        y: BULLET_ROW,
+        owner: BulletOwner::Enemy,
+    }];
+    let state2 = empty_state(); // no bullets
+    assert_eq!(
+        two_frames(&state1, &state1).char_at(W - 1, BULLET_ROW as u16),
+        '↓'
+    );
+    let screen = two_frames(&state1, &state2);
+    assert_eq!(
+        screen.char_at(W - 1, BULLET_ROW as u16),
+        '│',
+        "ghost sprite at col w-1 after bullet moved away"
+    );
+}
+
+// ── TextRenderer ──────────────────────────────────────────────────────────────
+
+#[test]
+fn text_renderer_writes_frames_separated_by_blank_lines() {
+    let mut frame = Buffer::new(8, 2);
+    frame.print(0, 0, "ab", Color::Red);
+    let mut text = TextRenderer::new(Vec::new());
+    text.present(frame.clone(), true).unwrap();
+    frame.print(0, 1, "cd", Color::Red);
+    text.present(frame, false).unwrap();
+    let out = String::from_utf8(text.into_inner()).unwrap();
+    assert_eq!(out, "ab\n\n\nab\ncd\n\n");
+}
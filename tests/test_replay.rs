@@ -0,0 +1,327 @@
+use shooting_game::compute::{init_state, tick, with_player_two};
+use shooting_game::demo::demo_actions;
+use shooting_game::entities::*;
+use shooting_game::replay::*;
+
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+
+/// Record `frames` ticks of the demo bot playing from `seed`.
+fn record_demo(seed: u64, frames: usize) -> Replay {
+    let mut replay = Replay::new(seed, Level::Hard, Difficulty::preset(&Level::Hard), 60, 24);
+    let (mut state, mut rng) = replay.start();
+    for _ in 0..frames {
+        if state.status != GameStatus::Playing {
+            break;
+        }
+        let actions = demo_actions(&state);
+        let frame = ReplayFrame {
+            actions: actions.clone(),
+            hash: 0,
+        };
+        state = step(&state, &frame, &mut rng);
+        replay.record(actions, &state);
+    }
+    replay
+}
+
+// ── Text format ───────────────────────────────────────────────────────────────
+
+#[test]
+fn text_round_trip() {
+    let mut replay = Replay::new(
+        99,
+        Level::Extreme,
+        Difficulty::preset(&Level::Extreme),
+        80,
+        24,
+    );
+    replay.frames.push(ReplayFrame {
+        actions: vec![
+            PlayerAction::MoveLeft(10),
+            PlayerAction::Shoot,
+            PlayerAction::ToggleDebug,
+            PlayerAction::Resize(100, 30),
+        ],
+        hash: 0xdead_beef,
+    });
+    replay.frames.push(ReplayFrame {
+        actions: vec![],
+        hash: 1,
+    });
+    let parsed = Replay::from_text(&replay.to_text()).unwrap();
+    assert_eq!(parsed, replay);
+}
+
+#[test]
+fn custom_difficulty_round_trips() {
+    let difficulty = Difficulty {
+        name: "Bullet Hell".to_string(),
+        builtin: false,
+        enemy_fire_chance: 60,
+        spacecraft_chance: 0.3,
+        power_up_loss: PowerUpLoss::Lose,
+        ..Difficulty::preset(&Level::Hard)
+    };
+    let replay = Replay::new(5, Level::Hard, difficulty, 60, 24);
+    let text = replay.to_text();
+    assert!(text.contains("\ndifficulty 8 55 60 0.3 150 Bullet Hell\non_hit 0 lose\n"));
+    assert_eq!(Replay::from_text(&text).unwrap(), replay);
+}
+
+#[test]
+fn custom_difficulty_requires_on_hit() {
+    let text = "shooting_game replay 2\nseed 1\nlevel easy\nsize 40 20\ndifficulty 22 130 220 0.6 150 Old\n";
+    assert_eq!(Replay::from_text(text).unwrap_err(), "missing on_hit");
+}
+
+#[test]
+fn coop_replay_round_trips() {
+    let mut replay = Replay::new(3, Level::Easy, Difficulty::preset(&Level::Easy), 80, 24);
+    replay.coop = true;
+    replay.frames.push(ReplayFrame {
+        actions: vec![
+            PlayerAction::P2MoveLeft(1),
+            PlayerAction::P2MoveRight(2),
+            PlayerAction::P2Shoot,
+            PlayerAction::Shoot,
+            PlayerAction::P2Leave,
+        ],
+        hash: 7,
+    });
+    let text = replay.to_text();
+    assert!(text.contains("\nplayers 2\nf 0000000000000007 2L1 2R2 2S S 2X\n"));
+    assert_eq!(Replay::from_text(&text).unwrap(), replay);
+    assert!(replay.start().0.player2.is_some());
+}
+
+#[test]
+fn builtin_difficulty_is_implied_by_level() {
+    let replay = Replay::new(5, Level::Easy, Difficulty::preset(&Level::Easy), 60, 24);
+    assert!(!replay.to_text().contains("difficulty"));
+}
+
+#[test]
+fn rejects_wrong_header() {
+    assert!(Replay::from_text("hello\nseed 1\n").is_err());
+}
+
+#[test]
+fn rejects_older_format_version() {
+    let text = "shooting_game replay 1\nseed 1\nlevel easy\nsize 40 20\n";
+    let err = Replay::from_text(text).unwrap_err();
+    assert!(err.contains("replay format 1 is not supported"), "{err}");
+}
+
+#[test]
+fn rejects_unknown_action_with_line_number() {
+    let text = "shooting_game replay 2\nseed 1\nlevel easy\nsize 40 20\nf 00 X\n";
+    let err = Replay::from_text(text).unwrap_err();
+    assert!(err.contains("line 5"), "error should name the line: {err}");
+}
+
+#[test]
+fn bad_difficulty_names_the_value() {
+    let text = "shooting_game replay 2\nseed 1\nlevel easy\nsize 40 20\ndifficulty 22 130 220 lots 150 X\n";
+    let err = Replay::from_text(text).unwrap_err();
+    assert_eq!(err, "line 5: bad difficulty spacecraft_chance");
+}
+
+#[test]
+fn rejects_size_below_minimum() {
+    for size in ["30 3", "10 24", "80 15"] {
+        let text = format!("shooting_game replay 2\nseed 1\nlevel easy\nsize {size}\n");
+        let err = Replay::from_text(&text).unwrap_err();
+        assert!(err.starts_with("line 4: "), "{size}: {err}");
+        assert!(err.contains("below the minimum"), "{size}: {err}");
+    }
+}
+
+#[test]
+fn rejects_zero_intervals_and_rates() {
+    for params in ["0 130 220 0.6 150", "22 0 220 0.6 150", "22 130 220 0.6 0"] {
+        let text = format!(
+            "shooting_game replay 2\nseed 1\nlevel easy\nsize 40 20\ndifficulty {params} X\n"
+        );
+        let err = Replay::from_text(&text).unwrap_err();
+        assert_eq!(
+            err, "line 5: bad difficulty: intervals and rates must be at least 1",
+            "{params}"
+        );
+    }
+}
+
+#[test]
+fn rejects_huge_interval() {
+    let text = "shooting_game replay 2\nseed 1\nlevel easy\nsize 40 20\ndifficulty 18446744073709551615 130 220 0.6 150 X\n";
+    let err = Replay::from_text(text).unwrap_err();
+    assert_eq!(
+        err,
+        "line 5: bad difficulty: enemy_move_interval must be at most 1000000"
+    );
+}
+
+#[test]
+fn rejects_zero_fire_chance() {
+    let text =
+        "shooting_game replay 2\nseed 1\nlevel easy\nsize 40 20\ndifficulty 22 130 0 0.6 150 X\n";
+    let err = Replay::from_text(text).unwrap_err();
+    assert_eq!(
+        err,
+        "line 5: bad difficulty: enemy_fire_chance must be at least 1"
+    );
+}
+
+#[test]
+fn rejects_spacecraft_chance_outside_zero_to_one() {
+    for chance in ["1.5", "-0.1", "NaN"] {
+        let text = format!(
+            "shooting_game replay 2\nseed 1\nlevel easy\nsize 40 20\ndifficulty 22 130 220 {chance} 150 X\n"
+        );
+        let err = Replay::from_text(&text).unwrap_err();
+        assert_eq!(
+            err, "line 5: bad difficulty: spacecraft_chance must be between 0 and 1",
+            "{chance}"
+        );
+    }
+}
+
+#[test]
+fn rejects_missing_size() {
+    let text = "shooting_game replay 2\nseed 1\nlevel easy\n";
+    assert!(Replay::from_text(text).is_err());
+}
+
+// ── State hash ────────────────────────────────────────────────────────────────
+
+#[test]
+fn hash_ignores_high_score_and_debug_overlay() {
+    let a = init_state(Level::Easy, 40, 20, 0);
+    let mut b = a.clone();
+    b.high_score = 5000;
+    b.debug_mode = true;
+    assert_eq!(state_hash(&a), state_hash(&b));
+}
+
+#[test]
+fn hash_changes_with_gameplay_state() {
+    let a = init_state(Level::Easy, 40, 20, 0);
+    let mut b = a.clone();
+    b.player.x += 1;
+    assert_ne!(state_hash(&a), state_hash(&b));
+}
+
+#[test]
+fn hash_covers_player_two() {
+    let a = with_player_two(&init_state(Level::Easy, 40, 20, 0));
+    assert_ne!(
+        state_hash(&a),
+        state_hash(&init_state(Level::Easy, 40, 20, 0))
+    );
+    let mut b = a.clone();
+    b.player2.as_mut().unwrap().ship.x += 1;
+    assert_ne!(state_hash(&a), state_hash(&b));
+}
+
+#[test]
+fn hash_is_stable_across_identical_runs() {
+    let s = init_state(Level::Hard, 40, 20, 0);
+    let a = tick(&s, &mut StdRng::seed_from_u64(7));
+    let b = tick(&s, &mut StdRng::seed_from_u64(7));
+    assert_eq!(state_hash(&a), state_hash(&b));
+}
+
+// ── Verification ──────────────────────────────────────────────────────────────
+
+#[test]
+fn recorded_demo_run_verifies_clean() {
+    let replay = record_demo(1234, 600);
+    let check = verify(&replay);
+    assert!(check.is_clean(), "{check}");
+    assert_eq!(check.frames_checked, replay.frames.len());
+}
+
+#[test]
+fn verify_survives_text_round_trip() {
+    let replay = record_demo(55, 300);
+    let parsed = Replay::from_text(&replay.to_text()).unwrap();
+    assert!(verify(&parsed).is_clean());
+}
+
+#[test]
+fn altered_input_is_reported_as_drift() {
+    let mut replay = record_demo(1234, 300);
+    replay.frames[100].actions.push(PlayerAction::MoveLeft(10));
+    let check = verify(&replay);
+    let drift = check.first_drift.expect("drift must be detected");
+    assert_eq!(drift.frame, 100);
+    assert!(check.mismatches >= 1);
+}
+
+#[test]
+fn recorded_resize_replays_clean() {
+    let mut replay = Replay::new(7, Level::Hard, Difficulty::preset(&Level::Hard), 80, 24);
+    let (mut state, mut rng) = replay.start();
+    for i in 0..200 {
+        if state.status != GameStatus::Playing {
+            break;
+        }
+        let mut actions = demo_actions(&state);
+        if i == 100 {
+            actions.push(PlayerAction::Resize(50, 18));
+        }
+        let frame = ReplayFrame {
+            actions: actions.clone(),
+            hash: 0,
+        };
+        state = step(&state, &frame, &mut rng);
+        replay.record(actions, &state);
+    }
+    assert_eq!((state.width, state.height), (50, 18));
+    let parsed = Replay::from_text(&replay.to_text()).unwrap();
+    assert!(verify(&parsed).is_clean());
+}
+
+#[test]
+fn different_seed_is_reported_as_drift() {
+    let mut replay = record_demo(1234, 300);
+    replay.seed = 4321;
+    assert!(!verify(&replay).is_clean());
+}
+
+#[test]
+fn coop_run_replays_clean() {
+    let mut replay = Replay::new(11, Level::Hard, Difficulty::preset(&Level::Hard), 80, 24);
+    replay.coop = true;
+    let (mut state, mut rng) = replay.start();
+    for i in 0..300 {
+        if state.status != GameStatus::Playing {
+            break;
+        }
+        // Player one follows the demo bot; player two sweeps and fires.
+        let mut actions = demo_actions(&state);
+        actions.push(if i % 40 < 20 {
+            PlayerAction::P2MoveLeft(1)
+        } else {
+            PlayerAction::P2MoveRight(1)
+        });
+        if i % 6 == 0 {
+            actions.push(PlayerAction::P2Shoot);
+        }
+        let frame = ReplayFrame {
+            actions: actions.clone(),
+            hash: 0,
+        };
+        state = step(&state, &frame, &mut rng);
+        replay.record(actions, &state);
+    }
+    let parsed = Replay::from_text(&replay.to_text()).unwrap();
+    assert!(verify(&parsed).is_clean());
+
+    let mut solo = parsed;
+    solo.coop = false;
+    assert!(
+        !verify(&solo).is_clean(),
+        "a co-op run drifts when played solo"
+    );
+}
@@ -0,0 +1,150 @@
+use shooting_game::compute::{init_state, tick, with_player_two};
+use shooting_game::demo::demo_actions;
+use shooting_game::entities::*;
+use shooting_game::replay::{state_hash, step, ReplayFrame};
+use shooting_game::savegame::*;
+
+use rand::SeedableRng;
+
+fn temp_path(name: &str) -> std::path::PathBuf {
+    std::env::temp_dir().join(format!("shooting_game_{}_{}", std::process::id(), name))
+}
+
+/// Let the demo bot play `frames` ticks of a co-op game.
+fn played(frames: usize) -> (EntireGameStateInfo, GameRng) {
+    let mut rng = GameRng::seed_from_u64(11);
+    let mut state = with_player_two(&init_state(Level::Hard, 60, 24, 0));
+    for _ in 0..frames {
+        let frame = ReplayFrame {
+            actions: demo_actions(&state),
+            hash: 0,
+        };
+        state = step(&state, &frame, &mut rng);
+    }
+    (state, rng)
+}
+
+#[test]
+fn round_trip_keeps_the_state() {
+    let (state, rng) = played(200);
+    let loaded = SaveGame::from_json(&SaveGame::new(&state, &rng).to_json()).unwrap();
+    assert_eq!(loaded.version, SAVE_VERSION);
+    assert_eq!(state_hash(&loaded.state), state_hash(&state));
+}
+
+#[test]
+fn continued_game_plays_out_the_same() {
+    let (state, rng) = played(100);
+    let loaded = SaveGame::from_json(&SaveGame::new(&state, &rng).to_json()).unwrap();
+
+    let (mut a, mut rng_a) = (state, rng);
+    let (mut b, mut rng_b) = (loaded.state, loaded.rng);
+    for _ in 0..300 {
+        a = tick(&a, &mut rng_a);
+        b = tick(&b, &mut rng_b);
+        assert_eq!(state_hash(&a), state_hash(&b), "frame {}", a.frame);
+    }
+}
+
+#[test]
+fn timers_and_explosions_are_kept() {
+    let (mut state, rng) = played(10);
+    state.active_power_up = Some((BonusKind::RapidFire, 123));
+    state.player2.as_mut().unwrap().active_power_up = Some((BonusKind::SpreadShot, 45));
+    state.explosions.push(Explosion {
+        x: 10,
+        y: 8,
+        frames: 3,
+    });
+    state.cheer_msg = Some(("WAVE 2".to_string(), 17));
+    state.status = GameStatus::Paused(PauseItem::Settings);
+
+    let loaded = SaveGame::from_json(&SaveGame::new(&state, &rng).to_json()).unwrap();
+    let s = loaded.state;
+    assert_eq!(s.active_power_up, Some((BonusKind::RapidFire, 123)));
+    assert_eq!(
+        s.player2.unwrap().active_power_up,
+        Some((BonusKind::SpreadShot, 45))
+    );
+    assert_eq!(s.explosions.len(), state.explosions.len());
+    let last = s.explosions.last().unwrap();
+    assert_eq!((last.x, last.y, last.frames), (10, 8, 3));
+    assert_eq!(s.cheer_msg, Some(("WAVE 2".to_string(), 17)));
+    assert_eq!(s.status, GameStatus::Paused(PauseItem::Settings));
+}
+
+#[test]
+fn older_save_is_rejected() {
+    let (state, rng) = played(5);
+    let mut game = SaveGame::new(&state, &rng);
+    game.version = 0;
+    let err = SaveGame::from_json(&game.to_json()).unwrap_err();
+    assert!(err.contains("older version"), "{}", err);
+}
+
+#[test]
+fn older_save_is_rejected_before_its_contents() {
+    // An old layout need not parse at all; the version alone decides.
+    let err = SaveGame::from_json(r#"{"version": 0, "player": {"x": 3}}"#).unwrap_err();
+    assert!(err.contains("older version"), "{}", err);
+}
+
+#[test]
+fn newer_save_is_rejected() {
+    let text = format!(r#"{{"version": {}}}"#, SAVE_VERSION + 1);
+    let err = SaveGame::from_json(&text).unwrap_err();
+    assert!(err.contains("newer version"), "{}", err);
+}
+
+#[test]
+fn garbage_is_not_a_save() {
+    assert_eq!(
+        SaveGame::from_json("hello").unwrap_err(),
+        "not a saved game"
+    );
+}
+
+#[test]
+fn damaged_save_is_rejected() {
+    let text = format!(r#"{{"version": {}, "state": {{}}}}"#, SAVE_VERSION);
+    let err = SaveGame::from_json(&text).unwrap_err();
+    assert!(err.starts_with("damaged saved game"), "{}", err);
+}
+
+#[test]
+fn finished_game_cannot_be_saved() {
+    let (mut state, _) = played(5);
+    assert!(can_save(&state));
+    state.status = GameStatus::GameOver;
+    assert!(!can_save(&state));
+}
+
+// ── Files ─────────────────────────────────────────────────────────────────────
+
+#[test]
+fn save_load_remove() {
+    let path = temp_path("savegame.json");
+    let (state, rng) = played(50);
+    save(&path, &SaveGame::new(&state, &rng)).unwrap();
+
+    let loaded = load(&path).unwrap().unwrap();
+    assert_eq!(state_hash(&loaded.state), state_hash(&state));
+
+    remove(&path).unwrap();
+    assert!(load(&path).unwrap().is_none());
+    // Removing twice is fine.
+    remove(&path).unwrap();
+}
+
+#[test]
+fn missing_file_is_no_save() {
+    assert!(load(&temp_path("no_such_save.json")).unwrap().is_none());
+}
+
+#[test]
+fn unreadable_save_is_an_error() {
+    let path = temp_path("old_save.json");
+    std::fs::write(&path, r#"{"version": 0}"#).unwrap();
+    assert!(load(&path).is_err());
+    let _ = std::fs::remove_file(&path);
+}
@@ -0,0 +1,47 @@
+use shooting_game::settings::*;
+
+use std::path::PathBuf;
+
+fn temp_path(name: &str) -> PathBuf {
+    std::env::temp_dir().join(format!("shooting_game_{}_{}", std::process::id(), name))
+}
+
+#[test]
+fn empty_file_uses_defaults() {
+    let settings = parse_settings("").unwrap();
+    assert_eq!(settings, Settings::default());
+    assert!(settings.pause_on_focus_loss);
+    assert!(!settings.mouse_control);
+}
+
+#[test]
+fn missing_file_uses_defaults() {
+    let settings = load_settings(&temp_path("does_not_exist.toml")).unwrap();
+    assert_eq!(settings, Settings::default());
+}
+
+#[test]
+fn settings_round_trip() {
+    let path = temp_path("round_trip.toml");
+    let settings = Settings {
+        pause_on_focus_loss: false,
+        mouse_control: true,
+    };
+    save_settings(&path, &settings).unwrap();
+    let loaded = load_settings(&path);
+    let _ = std::fs::remove_file(&path);
+    assert_eq!(loaded.unwrap(), settings);
+}
+
+#[test]
+fn unknown_key_is_rejected() {
+    let err = parse_settings("pause_on_focus_lost = false").unwrap_err();
+    assert!(err.contains("pause_on_focus_lost"), "{err}");
+}
+
+#[test]
+fn files_without_mouse_control_still_load() {
+    let settings = parse_settings("pause_on_focus_loss = false").unwrap();
+    assert!(!settings.pause_on_focus_loss);
+    assert!(!settings.mouse_control);
+}
@@ -0,0 +1,126 @@
+use shooting_game::demo::demo_actions;
+use shooting_game::entities::{Difficulty, GameStatus, HitCause, Level};
+use shooting_game::replay::{step, Replay, ReplayFrame};
+use shooting_game::sim::*;
+
+/// Simulate on a built-in preset at 60x24.
+fn sim(level: Level, seed: u64, max_frames: u64) -> GameSummary {
+    let difficulty = Difficulty::preset(&level);
+    simulate_game(level, &difficulty, seed, 60, 24, max_frames)
+}
+
+// ── simulate_game ─────────────────────────────────────────────────────────────
+
+#[test]
+fn same_seed_gives_same_game() {
+    let a = sim(Level::Extreme, 3, 2000);
+    let b = sim(Level::Extreme, 3, 2000);
+    assert_eq!(a, b);
+}
+
+#[test]
+fn seed_plays_out_the_same_as_a_replay() {
+    let g = sim(Level::Hard, 9, 3000);
+    let replay = Replay::new(9, Level::Hard, Difficulty::preset(&Level::Hard), 60, 24);
+    let (mut state, mut rng) = replay.start();
+    while state.status == GameStatus::Playing && state.frame < 3000 {
+        let frame = ReplayFrame {
+            actions: demo_actions(&state),
+            hash: 0,
+        };
+        state = step(&state, &frame, &mut rng);
+    }
+    assert_eq!((g.frames, g.score), (state.frame, state.score));
+}
+
+#[test]
+fn frame_cap_ends_game_without_death() {
+    let g = sim(Level::Easy, 1, 50);
+    assert_eq!(g.frames, 50);
+    assert_eq!(g.death, None);
+}
+
+#[test]
+fn lost_game_records_cause_and_hits() {
+    let g = sim(Level::Extreme, 3, 20_000);
+    assert!(g.frames < 20_000, "bot should die on Extreme");
+    let cause = g.death.expect("death cause recorded");
+    let hits: u32 = g.hits.values().sum();
+    assert!(hits >= 3, "at least the three starting lives were lost");
+    assert!(g.hits.contains_key(cause_label(&cause)));
+}
+
+#[test]
+fn kills_account_for_score() {
+    let g = sim(Level::Hard, 2, 3000);
+    let from_kills = g.kills.get("spacecraft").copied().unwrap_or(0) * 100
+        + g.kills.get("octopus").copied().unwrap_or(0) * 150
+        + g.kills.get("armored").copied().unwrap_or(0) * 250;
+    assert!(
+        !g.kills.contains_key("boss"),
+        "boss points depend on the wave"
+    );
+    assert_eq!(g.score, from_kills);
+}
+
+// ── Distribution ──────────────────────────────────────────────────────────────
+
+#[test]
+fn distribution_of_empty_is_zero() {
+    assert_eq!(Distribution::of(vec![]), Distribution::default());
+}
+
+#[test]
+fn distribution_percentiles() {
+    let d = Distribution::of(vec![50, 10, 40, 20, 30]);
+    assert_eq!(d.min, 10);
+    assert_eq!(d.p25, 20);
+    assert_eq!(d.median, 30);
+    assert_eq!(d.p75, 40);
+    assert_eq!(d.max, 50);
+    assert!((d.mean - 30.0).abs() < f64::EPSILON);
+}
+
+// ── ProfileReport ─────────────────────────────────────────────────────────────
+
+#[test]
+fn report_counts_survivors_and_causes() {
+    let mut died = sim(Level::Easy, 1, 10);
+    died.death = Some(HitCause::EnemyContact);
+    let survived = sim(Level::Easy, 2, 10);
+    let r = ProfileReport::from_games("easy", &[died, survived]);
+    assert_eq!(r.profile, "easy");
+    assert_eq!(r.games, 2);
+    assert_eq!(r.deaths.get("enemy_contact"), Some(&1));
+    assert_eq!(r.deaths.get("survived"), Some(&1));
+}
+
+#[test]
+fn report_adds_up_lives_lost_by_cause() {
+    let games = [
+        sim(Level::Extreme, 3, 20_000),
+        sim(Level::Extreme, 4, 20_000),
+    ];
+    let r = ProfileReport::from_games("extreme", &games);
+    for cause in [HitCause::EnemyBullet, HitCause::EnemyContact] {
+        let label = cause_label(&cause);
+        let total: u32 = games.iter().filter_map(|g| g.hits.get(label)).sum();
+        assert_eq!(r.hits.get(label).copied().unwrap_or(0), total);
+    }
+    assert!(r.hits.values().sum::<u32>() >= 6);
+}
+
+#[test]
+fn custom_profile_is_simulated_with_its_parameters() {
+    let calm = Difficulty {
+        name: "Calm".to_string(),
+        builtin: false,
+        enemy_spawn_rate: 100_000,
+        ..Difficulty::preset(&Level::Easy)
+    };
+    let g = simulate_game(Level::Easy, &calm, 1, 60, 24, 500);
+    assert_eq!(g.profile, "Calm");
+    assert_eq!(g.frames, 500);
+    assert!(g.kills.is_empty(), "no enemies ever spawn");
+    assert!(g.hits.is_empty());
+}